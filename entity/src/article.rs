@@ -1,8 +1,9 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.10
 
 use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "article")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -14,10 +15,10 @@ pub struct Model {
     pub content: String,
     pub summary: String,
     pub password_hash: String,
-    pub source: u8,
+    pub source: i16,
     pub source_url: String,
-    pub topping: u8,
-    pub status: u8,
+    pub topping: i16,
+    pub status: i16,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
     pub deleted_at: Option<DateTimeUtc>,
@@ -39,6 +40,8 @@ pub enum Relation {
     Category,
     #[sea_orm(has_many = "super::comment::Entity")]
     Comment,
+    #[sea_orm(has_many = "super::redirect::Entity")]
+    Redirect,
     #[sea_orm(has_many = "super::series_article::Entity")]
     SeriesArticle,
     #[sea_orm(
@@ -69,6 +72,12 @@ impl Related<super::comment::Entity> for Entity {
     }
 }
 
+impl Related<super::redirect::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Redirect.def()
+    }
+}
+
 impl Related<super::series_article::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::SeriesArticle.def()
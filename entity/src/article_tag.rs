@@ -1,8 +1,9 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.10
 
 use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "article_tag")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -6,6 +6,10 @@ pub mod article;
 pub mod article_tag;
 pub mod category;
 pub mod comment;
+pub mod import_record;
+pub mod media;
+pub mod media_variant;
+pub mod redirect;
 pub mod series;
 pub mod series_article;
 pub mod tag;
@@ -4,6 +4,10 @@ pub use super::article::Entity as Article;
 pub use super::article_tag::Entity as ArticleTag;
 pub use super::category::Entity as Category;
 pub use super::comment::Entity as Comment;
+pub use super::import_record::Entity as ImportRecord;
+pub use super::media::Entity as Media;
+pub use super::media_variant::Entity as MediaVariant;
+pub use super::redirect::Entity as Redirect;
 pub use super::series::Entity as Series;
 pub use super::series_article::Entity as SeriesArticle;
 pub use super::tag::Entity as Tag;
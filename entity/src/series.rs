@@ -1,8 +1,9 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.10
 
 use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "series")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -11,9 +12,9 @@ pub struct Model {
     pub name: String,
     pub description: Option<String>,
     pub cover: String,
-    pub status: u8,
-    pub nums: u32,
-    pub r#type: u8,
+    pub status: i16,
+    pub nums: i32,
+    pub r#type: i16,
     pub published_at: DateTimeUtc,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
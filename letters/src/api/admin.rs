@@ -0,0 +1,11 @@
+use axum::{routing::get, Router};
+use std::sync::Arc;
+
+use crate::{app::AppState, handlers};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route(
+        "/log-level",
+        get(handlers::admin::get_log_level).put(handlers::admin::set_log_level),
+    )
+}
@@ -1,7 +1,7 @@
-use axum::{routing::get, Router};
+use axum::{middleware, routing::get, Router};
 use std::sync::Arc;
 
-use crate::{app::AppState, handlers};
+use crate::{app::AppState, cache, handlers};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -9,10 +9,17 @@ pub fn router() -> Router<Arc<AppState>> {
             "/",
             get(handlers::article::get_articles).post(handlers::article::create_article),
         )
+        .route("/export", get(handlers::article::export_articles))
         .route(
             "/:article_id",
             get(handlers::article::get_article_by_id)
                 .put(handlers::article::update_article)
                 .delete(handlers::article::delete_article),
         )
+        .route(
+            "/:article_id/related",
+            get(handlers::article::get_related_articles),
+        )
+        .route("/:article_id/seo", get(handlers::seo::get_article_seo))
+        .route_layer(middleware::from_fn(cache::cached))
 }
@@ -1,10 +1,11 @@
 use axum::{
+    middleware,
     routing::{get, put},
     Router,
 };
 use std::sync::Arc;
 
-use crate::{app::AppState, handlers};
+use crate::{app::AppState, cache, handlers};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -20,4 +21,5 @@ pub fn router() -> Router<Arc<AppState>> {
             "/:category_id",
             put(handlers::category::update_category).delete(handlers::category::delete_category),
         )
+        .route_layer(middleware::from_fn(cache::cached))
 }
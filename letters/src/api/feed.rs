@@ -0,0 +1,19 @@
+use axum::{middleware, routing::get, Router};
+use std::sync::Arc;
+
+use crate::{app::AppState, cache, handlers};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/feed.xml", get(handlers::feed::rss))
+        .route("/atom.xml", get(handlers::feed::atom))
+        .route("/feed.json", get(handlers::feed::json))
+        .route(
+            "/categories/:category_id/:file",
+            get(handlers::feed::category_feed),
+        )
+        .route("/tags/:tag_id/:file", get(handlers::feed::tag_feed))
+        .route("/series/:series_id/:file", get(handlers::feed::series_feed))
+        .route("/authors/:user_id/:file", get(handlers::feed::author_feed))
+        .route_layer(middleware::from_fn(cache::cached))
+}
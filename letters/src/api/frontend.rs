@@ -0,0 +1,37 @@
+use axum::{middleware, routing::get, Router};
+use std::sync::Arc;
+
+use crate::{app::AppState, cache, handlers};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(handlers::frontend::index))
+        .route("/page/:page", get(handlers::frontend::index_page))
+        .route("/articles/:slug", get(handlers::frontend::article))
+        .route(
+            "/categories/:category_id",
+            get(handlers::frontend::category),
+        )
+        .route(
+            "/categories/:category_id/page/:page",
+            get(handlers::frontend::category_page),
+        )
+        .route("/tags/:tag_id", get(handlers::frontend::tag))
+        .route(
+            "/tags/:tag_id/page/:page",
+            get(handlers::frontend::tag_page),
+        )
+        .route("/series/:series_id", get(handlers::frontend::series))
+        .route(
+            "/series/:series_id/page/:page",
+            get(handlers::frontend::series_page),
+        )
+        .route("/authors/:user_id", get(handlers::frontend::author))
+        .route(
+            "/authors/:user_id/page/:page",
+            get(handlers::frontend::author_page),
+        )
+        .route("/archive", get(handlers::frontend::archive))
+        .route_layer(middleware::from_fn(cache::cached))
+        .route("/search", get(handlers::frontend::search))
+}
@@ -0,0 +1,11 @@
+use axum::{routing::get, Router};
+use std::sync::Arc;
+
+use crate::{app::AppState, handlers};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/healthz", get(handlers::health::healthz))
+        .route("/readyz", get(handlers::health::readyz))
+        .route("/metrics", get(handlers::health::metrics))
+}
@@ -0,0 +1,28 @@
+use axum::{extract::DefaultBodyLimit, routing::get, Router};
+use std::sync::Arc;
+
+use crate::{app::AppState, handlers};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/",
+            get(handlers::media::get_media)
+                // The upload handler enforces the configured size limit itself.
+                .post(handlers::media::upload_media)
+                .layer(DefaultBodyLimit::disable()),
+        )
+        .route(
+            "/:media_id",
+            get(handlers::media::get_media_by_id).delete(handlers::media::delete_media),
+        )
+        .route(
+            "/:media_id/signed-url",
+            get(handlers::media::get_signed_url),
+        )
+}
+
+/// Routes of the site root.
+pub fn site_router() -> Router<Arc<AppState>> {
+    Router::new().route("/images/:media_id", get(handlers::media::resize_image))
+}
@@ -1,14 +1,36 @@
+mod admin;
 mod article;
 mod category;
+mod feed;
+mod frontend;
+mod health;
+mod media;
+mod seo;
 mod series;
 mod tag;
+mod trash;
 mod user;
 mod v1;
 
-use crate::app::AppState;
+use crate::{app::AppState, handlers};
 use axum::Router;
 use std::sync::Arc;
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new().nest("/v1", v1::router())
 }
+
+/// Routes served from the root of the site, outside of the api.
+pub fn site_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .merge(health::router())
+        .merge(feed::router())
+        .merge(seo::router())
+        .merge(media::site_router())
+        .fallback(handlers::redirect::fallback)
+}
+
+/// Server rendered html pages.
+pub fn frontend_router() -> Router<Arc<AppState>> {
+    frontend::router()
+}
@@ -0,0 +1,12 @@
+use axum::{middleware, routing::get, Router};
+use std::sync::Arc;
+
+use crate::{app::AppState, cache, handlers};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/sitemap.xml", get(handlers::seo::sitemap))
+        .route("/sitemaps/:file", get(handlers::seo::sitemap_page))
+        .route("/robots.txt", get(handlers::seo::robots))
+        .route_layer(middleware::from_fn(cache::cached))
+}
@@ -1,7 +1,11 @@
-use axum::{routing::get, Router};
+use axum::{
+    middleware,
+    routing::{delete, get},
+    Router,
+};
 use std::sync::Arc;
 
-use crate::{app::AppState, handlers};
+use crate::{app::AppState, cache, handlers};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -11,7 +15,14 @@ pub fn router() -> Router<Arc<AppState>> {
         )
         .route(
             "/:series_id/articles",
-            get(handlers::series::get_series_articles),
+            get(handlers::series::get_series_articles)
+                .post(handlers::series::add_series_article)
+                .put(handlers::series::reorder_series_articles),
+        )
+        .route("/:series_id/export", get(handlers::series::export_series))
+        .route(
+            "/:series_id/articles/:article_id",
+            delete(handlers::series::remove_series_article),
         )
         .route(
             "/:series_id",
@@ -19,4 +30,5 @@ pub fn router() -> Router<Arc<AppState>> {
                 .put(handlers::series::update_series)
                 .delete(handlers::series::delete_series),
         )
+        .route_layer(middleware::from_fn(cache::cached))
 }
@@ -1,10 +1,11 @@
 use axum::{
+    middleware,
     routing::{get, put},
     Router,
 };
 use std::sync::Arc;
 
-use crate::{app::AppState, handlers};
+use crate::{app::AppState, cache, handlers};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -17,4 +18,5 @@ pub fn router() -> Router<Arc<AppState>> {
             "/:tag_id",
             put(handlers::tag::update_tag).delete(handlers::tag::delete_tag),
         )
+        .route_layer(middleware::from_fn(cache::cached))
 }
@@ -0,0 +1,13 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+use std::sync::Arc;
+
+use crate::{app::AppState, handlers};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(handlers::trash::get_trash))
+        .route("/:resource/:id/restore", post(handlers::trash::restore))
+}
@@ -10,7 +10,9 @@ pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route(
             "/profile",
-            get(handlers::user::get_profile).put(handlers::user::update_profile),
+            get(handlers::user::get_profile)
+                .put(handlers::user::update_profile)
+                .delete(handlers::user::delete_profile),
         )
         .route("/password", put(handlers::user::reset_password))
 }
@@ -1,17 +1,24 @@
 use std::sync::Arc;
 
-use axum::{routing::post, Router};
+use axum::{
+    routing::{get, post},
+    Router,
+};
 
 use crate::{app::AppState, handlers};
 
-use super::{article, category, series, tag, user};
+use super::{admin, article, category, media, series, tag, trash, user};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/authorize", post(handlers::auth::authorize))
+        .route("/search", get(handlers::search::search))
         .nest("/users", user::router())
         .nest("/categories", category::router())
         .nest("/tags", tag::router())
         .nest("/articles", article::router())
         .nest("/series", series::router())
+        .nest("/trash", trash::router())
+        .nest("/media", media::router())
+        .nest("/admin", admin::router())
 }
@@ -1,55 +1,208 @@
-use axum::Router;
+use anyhow::Context;
+use axum::{extract::DefaultBodyLimit, middleware, Extension, Router};
 use sea_orm::{Database, DatabaseConnection};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tower::ServiceBuilder;
 use tower_http::cors::Any;
-use tower_http::{
-    cors::CorsLayer,
-    trace::{self, TraceLayer},
-};
-use tracing::Level;
+use tower_http::{cors::CorsLayer, services::ServeDir, timeout::TimeoutLayer, trace::TraceLayer};
 use utoipa::OpenApi;
 use utoipa_rapidoc::RapiDoc;
 use utoipa_swagger_ui::SwaggerUi;
 
-use crate::{api, conf::Conf, handlers::openapi::ApiDoc};
+use crate::{
+    api,
+    cache::{self, Cache},
+    conf::{Conf, StorageBackend},
+    error::AppResult,
+    handlers::openapi::ApiDoc,
+    media::{self, Storage},
+    metrics::{self, Metrics},
+    related::RelatedArticles,
+    render::SharedTheme,
+    repos::{article, series, trash},
+    search::SearchIndex,
+    server, telemetry,
+};
 
 pub struct AppState {
     pub dbc: Arc<DatabaseConnection>,
     pub conf: Arc<Conf>,
+    pub search: Arc<SearchIndex>,
+    pub related: Arc<RelatedArticles>,
+    pub storage: Arc<dyn Storage>,
+    pub metrics: Arc<Metrics>,
+    pub cache: Arc<Cache>,
+    /// Theme of the html frontend, when it is enabled
+    pub theme: Option<Arc<SharedTheme>>,
+}
+
+impl AppState {
+    /// Open the search index, the media storage and the theme of `conf`
+    /// around an already connected database.
+    pub fn new(mut dbc: DatabaseConnection, conf: &Conf) -> anyhow::Result<Self> {
+        dbc.set_metric_callback(telemetry::record_query);
+        let search =
+            SearchIndex::open(&conf.search.index_dir).context("Open search index failed")?;
+        let storage = media::open_storage(conf).context("Open media storage failed")?;
+        let cache = Cache::open(&conf.cache).context("Open cache failed")?;
+        let theme = if conf.theme.frontend {
+            let theme = SharedTheme::load(&conf.theme.dir, conf.server.is_debug())
+                .context("Load theme failed")?;
+            Some(Arc::new(theme))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            dbc: Arc::new(dbc),
+            conf: Arc::new(conf.clone()),
+            search: Arc::new(search),
+            related: Arc::new(RelatedArticles::default()),
+            storage: Arc::from(storage),
+            metrics: Arc::new(Metrics::default()),
+            cache: Arc::new(cache),
+            theme,
+        })
+    }
+
+    /// Bring the data derived from articles up to date after `article_id`
+    /// was written.
+    pub async fn article_changed(&self, article_id: i32) {
+        self.search.sync_article(&self.dbc, article_id).await;
+        self.related.invalidate();
+        self.evict_article(article_id).await;
+    }
+
+    /// Evict the cached pages of `article_id` before it is updated or
+    /// deleted, its slug, category, tags and series may not be the same
+    /// afterwards.
+    pub async fn article_changing(&self, article_id: i32) {
+        self.evict_article(article_id).await;
+    }
+
+    /// Evict the cached listings after a category, tag or series was
+    /// created.
+    pub async fn listings_changed(&self) {
+        self.cache.evict(&[cache::ARTICLES.to_string()]).await;
+    }
+
+    /// Drop every cached page after a category, tag, series or user was
+    /// changed, their names show on most of them.
+    pub async fn names_changed(&self) {
+        self.related.invalidate();
+        self.cache.clear().await;
+    }
+
+    async fn evict_article(&self, article_id: i32) {
+        let scopes = match self.article_scopes(article_id).await {
+            Ok(Some(scopes)) => scopes,
+            Ok(None) => vec![
+                cache::ARTICLES.to_string(),
+                format!("article:{}", article_id),
+            ],
+            Err(err) => {
+                tracing::warn!(?err, article_id, "Read article scopes failed");
+                self.cache.clear().await;
+                return;
+            }
+        };
+        self.cache.evict(&scopes).await;
+    }
+
+    async fn article_scopes(&self, article_id: i32) -> AppResult<Option<Vec<String>>> {
+        let Some(model) = article::read_model_by_id(&self.dbc, article_id).await? else {
+            return Ok(None);
+        };
+        let tags = article::read_tags(&self.dbc, article_id).await?;
+        let series = series::read_by_article(&self.dbc, article_id, false).await?;
+
+        Ok(Some(cache::article_scopes(
+            article_id,
+            &model.slug,
+            model.category_id,
+            model.user_id,
+            tags.iter().map(|tag| tag.id),
+            series.iter().map(|series| series.id),
+        )))
+    }
 }
 
-pub async fn serve(port: u16, conf: &Conf) {
-    let db_url = conf.database.url.clone().unwrap_or("".to_string());
-    let dbc = Database::connect(db_url)
+pub async fn serve(conf: &Conf) -> anyhow::Result<()> {
+    let dbc = Database::connect(conf.database.url())
         .await
-        .expect("Database connection failed");
+        .context("Database connection failed")?;
 
-    let state = Arc::new(AppState {
-        dbc: Arc::new(dbc),
-        conf: Arc::new(conf.clone()),
-    });
+    let state = Arc::new(AppState::new(dbc, conf)?);
+
+    spawn_purge_job(&state);
+
+    let app = router(Arc::clone(&state));
 
+    server::run(app, &conf.server).await
+}
+
+/// Every route of the application with its layers, without binding a socket,
+/// so that it can also be called directly, e.g. by the tests.
+pub fn router(state: Arc<AppState>) -> Router {
+    let conf = Arc::clone(&state.conf);
     let cors_layer = CorsLayer::new().allow_origin(Any).allow_methods(Any);
 
-    let app = Router::new()
+    let mut app = Router::new()
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
         .merge(RapiDoc::new("/api-docs/openapi.json").path("/rapidoc"))
         .nest("/api", api::router())
+        .merge(api::site_router());
+    if conf.media.backend == StorageBackend::Local {
+        app = app.nest_service("/media", ServeDir::new(&conf.media.local_dir));
+    }
+    if conf.theme.frontend {
+        app = app.merge(api::frontend_router());
+    }
+    if conf.server.request_timeout_seconds > 0 {
+        let timeout = Duration::from_secs(conf.server.request_timeout_seconds);
+        app = app.route_layer(TimeoutLayer::new(timeout));
+    }
+    app = app
+        .route_layer(middleware::from_fn(telemetry::name_span))
+        .route_layer(middleware::from_fn(metrics::mark_route));
+
+    app.layer(DefaultBodyLimit::max(conf.server.body_limit))
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            metrics::track,
+        ))
         .layer(
             ServiceBuilder::new()
                 .layer(
                     TraceLayer::new_for_http()
-                        .make_span_with(trace::DefaultMakeSpan::new().level(Level::INFO))
-                        .on_response(trace::DefaultOnResponse::new().level(Level::INFO)),
+                        .make_span_with(telemetry::make_span)
+                        .on_response(telemetry::on_response),
                 )
                 .layer(cors_layer),
         )
-        .with_state(Arc::clone(&state));
+        .layer(middleware::from_fn(telemetry::set_request_id))
+        .layer(Extension(Arc::clone(&state.cache)))
+        .with_state(state)
+}
 
-    let addr = format!("127.0.0.1:{}", port);
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+/// Periodically hard delete the rows which stayed in the trash longer than
+/// the retention period.
+fn spawn_purge_job(state: &Arc<AppState>) {
+    let interval_hours = state.conf.trash.purge_interval_hours;
+    if interval_hours == 0 {
+        return;
+    }
 
-    tracing::debug!("Listening on http://{}", listener.local_addr().unwrap());
-    axum::serve(listener, app).await.unwrap()
+    let retention = chrono::Duration::days(state.conf.trash.retention_days);
+    let dbc = Arc::clone(&state.dbc);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_hours * 3600));
+        loop {
+            interval.tick().await;
+            match trash::purge(&dbc, chrono::Utc::now() - retention).await {
+                Ok(report) => tracing::info!(?report, "Purged trash"),
+                Err(err) => tracing::error!(?err, "Purge trash failed"),
+            }
+        }
+    });
 }
@@ -0,0 +1,48 @@
+use sea_orm::prelude::DateTimeUtc;
+use serde::{Deserialize, Serialize};
+
+/// Path of the manifest in the archive, written after everything else.
+pub const FILE_NAME: &str = "manifest.json";
+
+/// Version of the archive layout, bumped when an older `restore` could no
+/// longer read it.
+pub const FORMAT: u32 = 1;
+
+/// Describes a backup archive and the checksum of every file it holds.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: u32,
+    pub app_version: String,
+    /// Name of the last migration applied to the database, the rows match
+    /// the schema left by it.
+    pub schema_version: Option<String>,
+    pub backend: String,
+    pub created_at: DateTimeUtc,
+    pub tables: Vec<TableEntry>,
+    pub media: Vec<MediaEntry>,
+}
+
+/// `tables/{name}.jsonl`, one JSON object per row.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TableEntry {
+    pub name: String,
+    pub rows: u64,
+    pub sha256: String,
+}
+
+/// `media/{key}`, a file of the media storage.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MediaEntry {
+    pub key: String,
+    pub mime: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+pub fn table_path(name: &str) -> String {
+    format!("tables/{}.jsonl", name)
+}
+
+pub fn media_path(key: &str) -> String {
+    format!("media/{}", key)
+}
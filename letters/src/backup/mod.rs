@@ -0,0 +1,781 @@
+mod manifest;
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Read, Seek, Write},
+    path::Path,
+};
+
+use chrono::Utc;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{
+    ActiveModelTrait, ConnectionTrait, DatabaseConnection, DatabaseTransaction, EntityTrait,
+    IntoActiveModel, TransactionTrait,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::{AppError, AppResult},
+    media::Storage,
+    repos::backup,
+};
+use entity::{
+    article as ArticleEntity, article_tag as ArticleTagEntity, category as CategoryEntity,
+    comment as CommentEntity, import_record as ImportRecordEntity, media as MediaEntity,
+    media_variant as MediaVariantEntity, redirect as RedirectEntity, series as SeriesEntity,
+    series_article as SeriesArticleEntity, tag as TagEntity, user as UserEntity,
+};
+pub use manifest::Manifest;
+use manifest::{MediaEntry, TableEntry, FORMAT};
+
+/// Every table, referenced ones before the tables referencing them so that
+/// they can be restored in this order.
+const TABLES: [&str; 12] = [
+    "user",
+    "category",
+    "tag",
+    "series",
+    "article",
+    "article_tag",
+    "series_article",
+    "comment",
+    "media",
+    "media_variant",
+    "redirect",
+    "import_record",
+];
+
+/// Rows inserted by a single statement on restore.
+const BATCH_SIZE: usize = 500;
+
+/// Write the rows of every table and the media files into a gzipped tar
+/// archive at `out`, followed by a manifest with their checksums.
+///
+/// Tables are spooled through temporary files and media are copied one at
+/// a time, the archive only appears at `out` once it is complete.
+pub async fn backup(
+    dbc: &DatabaseConnection,
+    storage: &dyn Storage,
+    out: &Path,
+) -> AppResult<Manifest> {
+    let dir = match out.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let part = tempfile::NamedTempFile::new_in(dir)?;
+    let mut archive = tar::Builder::new(GzEncoder::new(
+        BufWriter::new(part.as_file()),
+        Compression::default(),
+    ));
+
+    let applied = Migrator::get_applied_migrations(dbc).await?;
+    let mut manifest = Manifest {
+        format: FORMAT,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: applied.last().map(|m| m.name().to_string()),
+        backend: format!("{:?}", dbc.get_database_backend()).to_lowercase(),
+        created_at: Utc::now(),
+        tables: Vec::with_capacity(TABLES.len()),
+        media: vec![],
+    };
+    let mtime = manifest.created_at.timestamp() as u64;
+
+    let mut files = vec![];
+    for name in TABLES {
+        let mut spool = tempfile::tempfile()?;
+        let mut out = Hashing::new(BufWriter::new(&mut spool));
+        let rows = dump_table(dbc, name, &mut out, &mut files).await?;
+        out.flush()?;
+        let (_, sha256, size) = out.finish();
+        spool.rewind()?;
+
+        append(
+            &mut archive,
+            &manifest::table_path(name),
+            size,
+            mtime,
+            spool,
+        )?;
+        manifest.tables.push(TableEntry {
+            name: name.to_string(),
+            rows,
+            sha256,
+        });
+    }
+
+    for (key, mime) in files {
+        let Some(data) = storage.get(&key).await? else {
+            tracing::warn!("media {} is missing from the storage, skipped", key);
+            continue;
+        };
+
+        append(
+            &mut archive,
+            &manifest::media_path(&key),
+            data.len() as u64,
+            mtime,
+            data.as_ref(),
+        )?;
+        manifest.media.push(MediaEntry {
+            key,
+            mime,
+            size: data.len() as u64,
+            sha256: hex(&Sha256::digest(&data)),
+        });
+    }
+
+    let data = serde_json::to_vec_pretty(&manifest).map_err(anyhow::Error::from)?;
+    append(
+        &mut archive,
+        manifest::FILE_NAME,
+        data.len() as u64,
+        mtime,
+        data.as_slice(),
+    )?;
+    archive.into_inner()?.finish()?.flush()?;
+
+    part.persist(out).map_err(|err| err.error)?;
+    Ok(manifest)
+}
+
+/// Check the archive against its manifest, then load it into the empty
+/// database behind `dbc` and put the media files back into `storage`.
+///
+/// The database is first migrated to the schema of the archive, so that the
+/// rows fit, and to the latest schema once they are loaded.
+pub async fn restore(
+    dbc: &DatabaseConnection,
+    storage: &dyn Storage,
+    archive: &Path,
+) -> AppResult<Manifest> {
+    let manifest = verify(archive)?;
+
+    let migrations: Vec<String> = Migrator::migrations()
+        .iter()
+        .map(|m| m.name().to_string())
+        .collect();
+    let target = match &manifest.schema_version {
+        Some(version) => match migrations.iter().position(|name| name == version) {
+            Some(i) => i + 1,
+            None => {
+                return Err(invalid(format!(
+                    "the archive was made with the unknown migration {}, \
+                     restore it with a newer version of letters",
+                    version
+                )))
+            }
+        },
+        None => 0,
+    };
+    let applied = Migrator::get_applied_migrations(dbc).await?.len();
+    if applied > target {
+        return Err(invalid(
+            "the database schema is newer than the archive, restore into an empty database"
+                .to_string(),
+        ));
+    }
+    if applied < target {
+        Migrator::up(dbc, Some((target - applied) as u32)).await?;
+    }
+    for name in TABLES {
+        if !is_empty(dbc, name).await? {
+            return Err(invalid(format!(
+                "table {} is not empty, restore into an empty database",
+                name
+            )));
+        }
+    }
+
+    let mime: HashMap<String, String> = manifest
+        .media
+        .iter()
+        .map(|entry| (manifest::media_path(&entry.key), entry.mime.clone()))
+        .collect();
+
+    let tables: HashSet<String> = manifest
+        .tables
+        .iter()
+        .map(|table| manifest::table_path(&table.name))
+        .collect();
+
+    let mut txn = Some(dbc.begin().await?);
+    let mut entries = open(archive)?;
+    for entry in entries.entries()? {
+        let mut entry = entry?;
+        let path = entry_path(&entry)?;
+
+        if let Some(name) = path
+            .strip_prefix("tables/")
+            .and_then(|name| name.strip_suffix(".jsonl"))
+        {
+            if !tables.contains(&path) {
+                return Err(invalid(format!("{} is not listed in the manifest", path)));
+            }
+            let Some(txn) = &txn else {
+                return Err(invalid(format!("{} comes after the media", path)));
+            };
+            load_table(txn, name, BufReader::new(&mut entry)).await?;
+            continue;
+        }
+
+        // The tables come first, they are all loaded by now
+        if let Some(txn) = txn.take() {
+            txn.commit().await?;
+        }
+        if let (Some(key), Some(mime)) = (path.strip_prefix("media/"), mime.get(&path)) {
+            let mut data = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut data)?;
+            storage.put(key, data.into(), mime).await?;
+        }
+    }
+    if let Some(txn) = txn.take() {
+        txn.commit().await?;
+    }
+
+    for name in TABLES {
+        reset_sequence(dbc, name).await?;
+    }
+    Migrator::up(dbc, None).await?;
+
+    Ok(manifest)
+}
+
+/// Read the whole archive once, checking every file listed by the manifest.
+fn verify(archive: &Path) -> AppResult<Manifest> {
+    let mut checksums = HashMap::new();
+    let mut manifest = None;
+
+    let mut entries = open(archive)?;
+    for entry in entries.entries()? {
+        let mut entry = entry?;
+        let path = entry_path(&entry)?;
+
+        if path == manifest::FILE_NAME {
+            if manifest.is_some() {
+                return Err(invalid(format!("{} appears twice", path)));
+            }
+            let mut data = vec![];
+            entry.read_to_end(&mut data)?;
+            manifest = Some(
+                serde_json::from_slice::<Manifest>(&data)
+                    .map_err(|err| invalid(format!("unreadable manifest: {}", err)))?,
+            );
+        } else {
+            let mut sink = Hashing::new(io::sink());
+            io::copy(&mut entry, &mut sink)?;
+            let (_, sha256, _) = sink.finish();
+            if checksums.insert(path.clone(), sha256).is_some() {
+                return Err(invalid(format!("{} appears twice", path)));
+            }
+        }
+    }
+
+    let Some(manifest) = manifest else {
+        return Err(invalid("the archive has no manifest".to_string()));
+    };
+    if manifest.format > FORMAT {
+        return Err(invalid(format!(
+            "archive format {} is not supported, restore it with a newer version of letters",
+            manifest.format
+        )));
+    }
+
+    let expected = manifest
+        .tables
+        .iter()
+        .map(|table| (manifest::table_path(&table.name), &table.sha256))
+        .chain(
+            manifest
+                .media
+                .iter()
+                .map(|media| (manifest::media_path(&media.key), &media.sha256)),
+        );
+    for (path, sha256) in expected {
+        match checksums.remove(&path) {
+            Some(actual) if &actual == sha256 => {}
+            Some(_) => return Err(invalid(format!("checksum mismatch for {}", path))),
+            None => return Err(invalid(format!("{} is missing", path))),
+        }
+    }
+    // Whatever the manifest does not list could not be checked.
+    if let Some(path) = checksums.keys().min() {
+        return Err(invalid(format!("{} is not listed in the manifest", path)));
+    }
+    if let Some(table) = manifest
+        .tables
+        .iter()
+        .find(|table| !TABLES.contains(&table.name.as_str()))
+    {
+        return Err(invalid(format!("unknown table {}", table.name)));
+    }
+
+    Ok(manifest)
+}
+
+async fn dump_table<W: Write>(
+    dbc: &DatabaseConnection,
+    name: &str,
+    out: &mut W,
+    files: &mut Vec<(String, String)>,
+) -> AppResult<u64> {
+    match name {
+        "user" => dump::<UserEntity::Entity, _>(dbc, out, |_| {}).await,
+        "category" => dump::<CategoryEntity::Entity, _>(dbc, out, |_| {}).await,
+        "tag" => dump::<TagEntity::Entity, _>(dbc, out, |_| {}).await,
+        "series" => dump::<SeriesEntity::Entity, _>(dbc, out, |_| {}).await,
+        "article" => dump::<ArticleEntity::Entity, _>(dbc, out, |_| {}).await,
+        "article_tag" => dump::<ArticleTagEntity::Entity, _>(dbc, out, |_| {}).await,
+        "series_article" => dump::<SeriesArticleEntity::Entity, _>(dbc, out, |_| {}).await,
+        "comment" => dump::<CommentEntity::Entity, _>(dbc, out, |_| {}).await,
+        "media" => {
+            dump::<MediaEntity::Entity, _>(dbc, out, |m| {
+                files.push((m.storage_key.clone(), m.mime.clone()))
+            })
+            .await
+        }
+        "media_variant" => {
+            dump::<MediaVariantEntity::Entity, _>(dbc, out, |m| {
+                files.push((m.storage_key.clone(), m.mime.clone()))
+            })
+            .await
+        }
+        "redirect" => dump::<RedirectEntity::Entity, _>(dbc, out, |_| {}).await,
+        "import_record" => dump::<ImportRecordEntity::Entity, _>(dbc, out, |_| {}).await,
+        _ => unreachable!("unknown table {}", name),
+    }
+}
+
+/// Write the rows of `E` as JSON lines, `inspect` sees every row on the way.
+async fn dump<E, W>(
+    dbc: &DatabaseConnection,
+    out: &mut W,
+    mut inspect: impl FnMut(&E::Model),
+) -> AppResult<u64>
+where
+    E: EntityTrait,
+    E::Model: Serialize + Sync,
+    W: Write,
+{
+    backup::for_each::<E, _>(dbc, |model| {
+        inspect(&model);
+        serde_json::to_writer(&mut *out, &model).map_err(io::Error::from)?;
+        out.write_all(b"\n")?;
+        Ok(())
+    })
+    .await
+}
+
+async fn load_table<R: BufRead>(txn: &DatabaseTransaction, name: &str, rows: R) -> AppResult<()> {
+    match name {
+        "user" => load::<UserEntity::ActiveModel, _>(txn, rows).await,
+        "category" => load::<CategoryEntity::ActiveModel, _>(txn, rows).await,
+        "tag" => load::<TagEntity::ActiveModel, _>(txn, rows).await,
+        "series" => load::<SeriesEntity::ActiveModel, _>(txn, rows).await,
+        "article" => load::<ArticleEntity::ActiveModel, _>(txn, rows).await,
+        "article_tag" => load::<ArticleTagEntity::ActiveModel, _>(txn, rows).await,
+        "series_article" => load::<SeriesArticleEntity::ActiveModel, _>(txn, rows).await,
+        "comment" => load::<CommentEntity::ActiveModel, _>(txn, rows).await,
+        "media" => load::<MediaEntity::ActiveModel, _>(txn, rows).await,
+        "media_variant" => load::<MediaVariantEntity::ActiveModel, _>(txn, rows).await,
+        "redirect" => load::<RedirectEntity::ActiveModel, _>(txn, rows).await,
+        "import_record" => load::<ImportRecordEntity::ActiveModel, _>(txn, rows).await,
+        _ => Err(invalid(format!("unknown table {}", name))),
+    }
+}
+
+/// Insert the JSON lines of `rows` into the table of `A`, `BATCH_SIZE` rows at a time.
+async fn load<A, R>(txn: &DatabaseTransaction, rows: R) -> AppResult<()>
+where
+    A: ActiveModelTrait,
+    <A::Entity as EntityTrait>::Model: IntoActiveModel<A> + DeserializeOwned,
+    R: BufRead,
+{
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    for line in rows.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row = serde_json::from_str(&line)
+            .map_err(|err| invalid(format!("unreadable row: {}", err)))?;
+        batch.push(row);
+
+        if batch.len() == BATCH_SIZE {
+            backup::load::<A, _>(txn, std::mem::take(&mut batch)).await?;
+        }
+    }
+    backup::load::<A, _>(txn, batch).await
+}
+
+async fn is_empty(dbc: &DatabaseConnection, name: &str) -> AppResult<bool> {
+    match name {
+        "user" => backup::is_empty::<UserEntity::Entity, _>(dbc).await,
+        "category" => backup::is_empty::<CategoryEntity::Entity, _>(dbc).await,
+        "tag" => backup::is_empty::<TagEntity::Entity, _>(dbc).await,
+        "series" => backup::is_empty::<SeriesEntity::Entity, _>(dbc).await,
+        "article" => backup::is_empty::<ArticleEntity::Entity, _>(dbc).await,
+        "article_tag" => backup::is_empty::<ArticleTagEntity::Entity, _>(dbc).await,
+        "series_article" => backup::is_empty::<SeriesArticleEntity::Entity, _>(dbc).await,
+        "comment" => backup::is_empty::<CommentEntity::Entity, _>(dbc).await,
+        "media" => backup::is_empty::<MediaEntity::Entity, _>(dbc).await,
+        "media_variant" => backup::is_empty::<MediaVariantEntity::Entity, _>(dbc).await,
+        "redirect" => backup::is_empty::<RedirectEntity::Entity, _>(dbc).await,
+        "import_record" => backup::is_empty::<ImportRecordEntity::Entity, _>(dbc).await,
+        _ => unreachable!("unknown table {}", name),
+    }
+}
+
+async fn reset_sequence(dbc: &DatabaseConnection, name: &str) -> AppResult<()> {
+    match name {
+        "user" => backup::reset_sequence::<UserEntity::Entity, _>(dbc).await,
+        "category" => backup::reset_sequence::<CategoryEntity::Entity, _>(dbc).await,
+        "tag" => backup::reset_sequence::<TagEntity::Entity, _>(dbc).await,
+        "series" => backup::reset_sequence::<SeriesEntity::Entity, _>(dbc).await,
+        "article" => backup::reset_sequence::<ArticleEntity::Entity, _>(dbc).await,
+        "article_tag" => backup::reset_sequence::<ArticleTagEntity::Entity, _>(dbc).await,
+        "series_article" => backup::reset_sequence::<SeriesArticleEntity::Entity, _>(dbc).await,
+        "comment" => backup::reset_sequence::<CommentEntity::Entity, _>(dbc).await,
+        "media" => backup::reset_sequence::<MediaEntity::Entity, _>(dbc).await,
+        "media_variant" => backup::reset_sequence::<MediaVariantEntity::Entity, _>(dbc).await,
+        "redirect" => backup::reset_sequence::<RedirectEntity::Entity, _>(dbc).await,
+        "import_record" => backup::reset_sequence::<ImportRecordEntity::Entity, _>(dbc).await,
+        _ => unreachable!("unknown table {}", name),
+    }
+}
+
+fn open(archive: &Path) -> AppResult<tar::Archive<GzDecoder<BufReader<File>>>> {
+    Ok(tar::Archive::new(GzDecoder::new(BufReader::new(
+        File::open(archive)?,
+    ))))
+}
+
+fn entry_path<R: Read>(entry: &tar::Entry<R>) -> AppResult<String> {
+    let path = entry.path()?;
+    path.to_str()
+        .map(str::to_string)
+        .ok_or_else(|| invalid(format!("invalid path {}", path.display())))
+}
+
+fn append<W: Write, R: Read>(
+    archive: &mut tar::Builder<W>,
+    path: &str,
+    size: u64,
+    mtime: u64,
+    data: R,
+) -> AppResult<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(size);
+    header.set_mode(0o644);
+    header.set_mtime(mtime);
+    archive.append_data(&mut header, path, data)?;
+    Ok(())
+}
+
+fn invalid(message: String) -> AppError {
+    AppError::InvalidInput(message)
+}
+
+/// Passes the bytes through while computing their SHA-256 and size.
+struct Hashing<W> {
+    inner: W,
+    hasher: Sha256,
+    size: u64,
+}
+
+impl<W> Hashing<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+            size: 0,
+        }
+    }
+
+    fn finish(self) -> (W, String, u64) {
+        (self.inner, hex(&self.hasher.finalize()), self.size)
+    }
+}
+
+impl<W: Write> Write for Hashing<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.size += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use sea_orm::{Database, Set};
+
+    use super::*;
+    use crate::media::LocalStorage;
+
+    /// An archive of `files`, with a manifest listing the `listed` ones.
+    fn archive(files: &[(&str, &[u8])], listed: &[&str]) -> tempfile::NamedTempFile {
+        let tables = files
+            .iter()
+            .filter(|(path, _)| listed.contains(path))
+            .map(|(path, data)| TableEntry {
+                name: path
+                    .trim_start_matches("tables/")
+                    .trim_end_matches(".jsonl")
+                    .to_string(),
+                rows: 1,
+                sha256: hex(&Sha256::digest(data)),
+            })
+            .collect();
+        pack(files, tables)
+    }
+
+    /// An archive of `files`, with a manifest listing `tables`.
+    fn pack(files: &[(&str, &[u8])], tables: Vec<TableEntry>) -> tempfile::NamedTempFile {
+        let manifest = Manifest {
+            format: FORMAT,
+            app_version: "test".to_string(),
+            schema_version: None,
+            backend: "sqlite".to_string(),
+            created_at: Utc::now(),
+            tables,
+            media: vec![],
+        };
+        let manifest = serde_json::to_vec(&manifest).unwrap();
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut builder =
+            tar::Builder::new(GzEncoder::new(file.reopen().unwrap(), Compression::fast()));
+        for (path, data) in files
+            .iter()
+            .chain([(manifest::FILE_NAME, manifest.as_slice())].iter())
+        {
+            append(&mut builder, path, data.len() as u64, 0, *data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+        file
+    }
+
+    fn error(result: AppResult<Manifest>) -> String {
+        match result {
+            Err(AppError::InvalidInput(message)) => message,
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn verify_a_listed_table() {
+        let file = archive(&[("tables/user.jsonl", b"{}\n")], &["tables/user.jsonl"]);
+
+        let manifest = verify(file.path()).unwrap();
+
+        assert_eq!(manifest.tables[0].name, "user");
+    }
+
+    #[test]
+    fn verify_rejects_a_checksum_mismatch() {
+        let table = TableEntry {
+            name: "user".to_string(),
+            rows: 1,
+            sha256: hex(&Sha256::digest(b"{}\n")),
+        };
+        let file = pack(&[("tables/user.jsonl", b"{\"id\":1}\n")], vec![table]);
+
+        let message = error(verify(file.path()));
+
+        assert_eq!(message, "checksum mismatch for tables/user.jsonl");
+    }
+
+    #[test]
+    fn verify_rejects_a_missing_entry() {
+        let table = TableEntry {
+            name: "user".to_string(),
+            rows: 1,
+            sha256: hex(&Sha256::digest(b"{}\n")),
+        };
+        let file = pack(&[], vec![table]);
+
+        let message = error(verify(file.path()));
+
+        assert_eq!(message, "tables/user.jsonl is missing");
+    }
+
+    #[test]
+    fn verify_rejects_an_unlisted_entry() {
+        let file = archive(
+            &[
+                ("tables/user.jsonl", b"{}\n"),
+                ("tables/article.jsonl", b"{}\n"),
+            ],
+            &["tables/user.jsonl"],
+        );
+
+        let message = error(verify(file.path()));
+
+        assert_eq!(
+            message,
+            "tables/article.jsonl is not listed in the manifest"
+        );
+    }
+
+    #[test]
+    fn verify_rejects_a_duplicated_entry() {
+        let file = archive(
+            &[
+                ("tables/user.jsonl", b"{}\n"),
+                ("tables/user.jsonl", b"{\"id\":1}\n"),
+            ],
+            &["tables/user.jsonl"],
+        );
+
+        let message = error(verify(file.path()));
+
+        assert_eq!(message, "tables/user.jsonl appears twice");
+    }
+
+    /// A fresh, migrated SQLite database and a media storage, both in `dir`.
+    async fn site(dir: &Path) -> (DatabaseConnection, LocalStorage) {
+        let url = format!("sqlite://{}?mode=rwc", dir.join("letters.db").display());
+        let dbc = Database::connect(url).await.unwrap();
+        Migrator::up(&dbc, None).await.unwrap();
+        (dbc, LocalStorage::new(dir.join("media"), "/media"))
+    }
+
+    #[tokio::test]
+    async fn backup_then_restore_round_trip() {
+        let source = tempfile::tempdir().unwrap();
+        let (dbc, storage) = site(source.path()).await;
+        let now = Utc::now();
+        let user = UserEntity::ActiveModel {
+            username: Set("alice".to_string()),
+            email: Set("alice@example.com".to_string()),
+            password_hash: Set("hash".to_string()),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&dbc)
+        .await
+        .unwrap();
+        let category = CategoryEntity::ActiveModel {
+            name: Set("Rust".to_string()),
+            status: Set(1),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&dbc)
+        .await
+        .unwrap();
+        let article = ArticleEntity::ActiveModel {
+            title: Set("Pinning".to_string()),
+            slug: Set("pinning".to_string()),
+            cover: Set(String::new()),
+            content: Set("Self referential futures.".to_string()),
+            summary: Set(String::new()),
+            password_hash: Set(String::new()),
+            source: Set(0),
+            source_url: Set(String::new()),
+            topping: Set(0),
+            status: Set(1),
+            created_at: Set(now),
+            updated_at: Set(now),
+            user_id: Set(user.id),
+            category_id: Set(category.id),
+            ..Default::default()
+        }
+        .insert(&dbc)
+        .await
+        .unwrap();
+        let media = MediaEntity::ActiveModel {
+            storage_key: Set("2024/05/pin.png".to_string()),
+            file_name: Set("pin.png".to_string()),
+            mime: Set("image/png".to_string()),
+            size: Set(4),
+            hash: Set("hash".to_string()),
+            created_at: Set(now),
+            updated_at: Set(now),
+            user_id: Set(user.id),
+            ..Default::default()
+        }
+        .insert(&dbc)
+        .await
+        .unwrap();
+        storage
+            .put(
+                &media.storage_key,
+                Bytes::from_static(b"\x89PNG"),
+                "image/png",
+            )
+            .await
+            .unwrap();
+
+        let out = source.path().join("letters.tar.gz");
+        let written = backup(&dbc, &storage, &out).await.unwrap();
+
+        let target = tempfile::tempdir().unwrap();
+        let (restored_dbc, restored_storage) = site(target.path()).await;
+        // Restore migrates the database itself.
+        Migrator::down(&restored_dbc, None).await.unwrap();
+        let read = restore(&restored_dbc, &restored_storage, &out)
+            .await
+            .unwrap();
+
+        assert_eq!(read.tables.len(), TABLES.len());
+        assert_eq!(read.schema_version, written.schema_version);
+        assert_eq!(read.media.len(), 1);
+        assert_eq!(
+            UserEntity::Entity::find().all(&restored_dbc).await.unwrap(),
+            [user]
+        );
+        assert_eq!(
+            ArticleEntity::Entity::find()
+                .all(&restored_dbc)
+                .await
+                .unwrap(),
+            [article]
+        );
+        assert_eq!(
+            restored_storage.get(&media.storage_key).await.unwrap(),
+            Some(Bytes::from_static(b"\x89PNG"))
+        );
+        assert_eq!(
+            MediaEntity::Entity::find()
+                .all(&restored_dbc)
+                .await
+                .unwrap(),
+            [media]
+        );
+
+        // The sequences go on after the restored rows.
+        let next = CategoryEntity::ActiveModel {
+            name: Set("Go".to_string()),
+            status: Set(1),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&restored_dbc)
+        .await
+        .unwrap();
+        assert_eq!(next.id, category.id + 1);
+
+        // Only into an empty database.
+        let message = error(restore(&restored_dbc, &restored_storage, &out).await);
+        assert_eq!(
+            message,
+            "table user is not empty, restore into an empty database"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+
+use super::{Entry, Store};
+use crate::error::AppResult;
+
+/// Entries kept in the process, lost on restart and not shared between
+/// servers.
+pub struct MemoryStore {
+    max_entries: usize,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<String, Kept>,
+    /// Keys of the entries by scope
+    scopes: HashMap<String, HashSet<String>>,
+}
+
+struct Kept {
+    entry: Entry,
+    expires_at: Instant,
+    scopes: Vec<String>,
+}
+
+impl MemoryStore {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            max_entries,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+}
+
+impl Inner {
+    fn remove(&mut self, key: &str) {
+        if let Some(kept) = self.entries.remove(key) {
+            for scope in kept.scopes {
+                if let Some(keys) = self.scopes.get_mut(&scope) {
+                    keys.remove(key);
+                    if keys.is_empty() {
+                        self.scopes.remove(&scope);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Drop the expired entries, then the closest to expire until there is
+    /// room for one more.
+    fn make_room(&mut self, max_entries: usize) {
+        let now = Instant::now();
+        let expired: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|(_, kept)| kept.expires_at <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired {
+            self.remove(&key);
+        }
+
+        while self.entries.len() >= max_entries {
+            let Some(key) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, kept)| kept.expires_at)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.remove(&key);
+        }
+    }
+}
+
+#[async_trait]
+impl Store for MemoryStore {
+    async fn get(&self, key: &str) -> AppResult<Option<Entry>> {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.entries.get(key) {
+            Some(kept) if kept.expires_at > Instant::now() => Ok(Some(kept.entry.clone())),
+            Some(_) => {
+                inner.remove(key);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn put(
+        &self,
+        key: &str,
+        entry: &Entry,
+        scopes: &[String],
+        ttl: Duration,
+    ) -> AppResult<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.remove(key);
+        inner.make_room(self.max_entries);
+
+        for scope in scopes {
+            inner
+                .scopes
+                .entry(scope.clone())
+                .or_default()
+                .insert(key.to_string());
+        }
+        inner.entries.insert(
+            key.to_string(),
+            Kept {
+                entry: entry.clone(),
+                expires_at: Instant::now() + ttl,
+                scopes: scopes.to_vec(),
+            },
+        );
+        Ok(())
+    }
+
+    async fn evict(&self, scopes: &[String]) -> AppResult<()> {
+        let mut inner = self.inner.lock().unwrap();
+        for scope in scopes {
+            let keys = inner.scopes.get(scope).cloned().unwrap_or_default();
+            for key in keys {
+                inner.remove(&key);
+            }
+        }
+        Ok(())
+    }
+
+    async fn clear(&self) -> AppResult<()> {
+        *self.inner.lock().unwrap() = Inner::default();
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+//! Rendered responses of the public routes, kept until what they show is
+//! written or they expire.
+//!
+//! Each response is tagged with the scopes it depends on, read from the
+//! parameters of its route: `/categories/:category_id` depends on
+//! `category:<id>`, a route without an id, e.g. the home page or a feed,
+//! on every article through `articles`. Handlers add the scopes their route
+//! does not name through [`Scopes`], like the series of an article whose
+//! chapters link to each other. Writes evict the scopes they touch.
+
+mod memory;
+#[cfg(feature = "redis")]
+mod redis;
+
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use axum::{
+    body::{Body, Bytes},
+    extract::{MatchedPath, OriginalUri, RawPathParams, Request},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Extension,
+};
+use axum_extra::headers::{ETag, HeaderMapExt, LastModified};
+use bytes::BytesMut;
+use futures::{future, stream, StreamExt};
+use std::sync::Arc;
+
+use crate::{
+    conf::{self, CacheBackend},
+    error::AppResult,
+    utils::conditional,
+};
+use memory::MemoryStore;
+
+static X_CACHE: HeaderName = HeaderName::from_static("x-cache");
+
+/// Scope of the routes listing every article.
+pub const ARTICLES: &str = "articles";
+
+/// Scopes a handler adds to those of its route, for what its response shows
+/// beyond the parameters of the route. Set as a response extension.
+#[derive(Debug, Clone, Default)]
+pub struct Scopes(pub Vec<String>);
+
+impl Scopes {
+    /// The series of an article, whose chapters link to each other.
+    pub fn series(series_ids: impl IntoIterator<Item = i32>) -> Self {
+        Self(
+            series_ids
+                .into_iter()
+                .map(|id| format!("series:{}", id))
+                .collect(),
+        )
+    }
+}
+
+/// A response as it is kept, the status is always 200.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub headers: Vec<(String, Vec<u8>)>,
+    pub body: Bytes,
+}
+
+/// Where the entries live.
+#[async_trait]
+pub trait Store: Send + Sync {
+    async fn get(&self, key: &str) -> AppResult<Option<Entry>>;
+
+    async fn put(
+        &self,
+        key: &str,
+        entry: &Entry,
+        scopes: &[String],
+        ttl: Duration,
+    ) -> AppResult<()>;
+
+    /// Remove every entry tagged with one of `scopes`.
+    async fn evict(&self, scopes: &[String]) -> AppResult<()>;
+
+    async fn clear(&self) -> AppResult<()>;
+}
+
+pub struct Cache {
+    store: Option<Box<dyn Store>>,
+    ttl: Duration,
+    max_body_size: usize,
+}
+
+impl Cache {
+    pub fn open(conf: &conf::Cache) -> anyhow::Result<Self> {
+        let store: Option<Box<dyn Store>> = match (conf.enabled, conf.backend) {
+            (false, _) => None,
+            (true, CacheBackend::Memory) => Some(Box::new(MemoryStore::new(conf.max_entries))),
+            #[cfg(feature = "redis")]
+            (true, CacheBackend::Redis) => Some(Box::new(redis::RedisStore::new(&conf.redis)?)),
+            #[cfg(not(feature = "redis"))]
+            (true, CacheBackend::Redis) => {
+                anyhow::bail!("The redis cache backend needs the `redis` feature")
+            }
+        };
+
+        Ok(Self {
+            store,
+            ttl: Duration::from_secs(conf.ttl_seconds),
+            max_body_size: conf.max_body_size,
+        })
+    }
+
+    /// Remove the responses depending on any of `scopes`. A store which
+    /// can not be reached is logged, its entries expire in time.
+    pub async fn evict(&self, scopes: &[String]) {
+        if let Some(store) = &self.store {
+            if let Err(err) = store.evict(scopes).await {
+                tracing::warn!(?err, ?scopes, "Evict cached responses failed");
+            }
+        }
+    }
+
+    /// Remove every response, for the writes which show on too many pages
+    /// to be told apart.
+    pub async fn clear(&self) {
+        if let Some(store) = &self.store {
+            if let Err(err) = store.clear().await {
+                tracing::warn!(?err, "Clear cached responses failed");
+            }
+        }
+    }
+}
+
+/// Scopes of an article, as they are named by the routes showing it.
+pub fn article_scopes(
+    article_id: i32,
+    slug: &str,
+    category_id: i32,
+    author_id: i32,
+    tag_ids: impl IntoIterator<Item = i32>,
+    series_ids: impl IntoIterator<Item = i32>,
+) -> Vec<String> {
+    let mut scopes = vec![
+        ARTICLES.to_string(),
+        format!("article:{}", article_id),
+        format!("article:{}", slug),
+        format!("category:{}", category_id),
+        format!("author:{}", author_id),
+    ];
+    scopes.extend(tag_ids.into_iter().map(|id| format!("tag:{}", id)));
+    scopes.extend(series_ids.into_iter().map(|id| format!("series:{}", id)));
+    scopes
+}
+
+/// Scopes a route depends on, from the names of its parameters. The related
+/// articles are picked among every article, they depend on all of them as
+/// well.
+fn route_scopes(route: Option<&str>, params: &RawPathParams) -> Vec<String> {
+    let mut scopes: Vec<String> = params
+        .iter()
+        .filter_map(|(name, value)| {
+            let scope = match name {
+                "article_id" | "slug" => "article",
+                "category_id" => "category",
+                "tag_id" => "tag",
+                "series_id" => "series",
+                "user_id" => "author",
+                _ => return None,
+            };
+            Some(format!("{}:{}", scope, value))
+        })
+        .collect();
+
+    if scopes.is_empty() || route.is_some_and(|route| route.ends_with("/related")) {
+        scopes.push(ARTICLES.to_string());
+    }
+    scopes
+}
+
+/// Answer the GET requests of the route from the cache, or keep the
+/// response of the handler. Requests with credentials, and every other
+/// method, go straight to the handler.
+///
+/// Every response passing through gets an ETag, and a `304 Not Modified`
+/// when the client holds it already.
+pub async fn cached(
+    Extension(cache): Extension<Arc<Cache>>,
+    OriginalUri(uri): OriginalUri,
+    route: Option<MatchedPath>,
+    params: RawPathParams,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let Some(store) = &cache.store else {
+        return next.run(req).await;
+    };
+    if req.method() != Method::GET || req.headers().contains_key(header::AUTHORIZATION) {
+        return next.run(req).await;
+    }
+
+    // The nested routers see their path without the prefix.
+    let key = uri
+        .path_and_query()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_default();
+    let req_headers = req.headers().clone();
+
+    match store.get(&key).await {
+        Ok(Some(entry)) => return respond(&req_headers, entry, "HIT"),
+        Ok(None) => {}
+        Err(err) => tracing::warn!(?err, key, "Read cached response failed"),
+    }
+
+    // The whole representation is needed to keep it, whatever the client
+    // holds.
+    let headers = req.headers_mut();
+    headers.remove(header::IF_NONE_MATCH);
+    headers.remove(header::IF_MODIFIED_SINCE);
+    let response = next.run(req).await;
+    if response.status() != StatusCode::OK || response.headers().contains_key(header::SET_COOKIE) {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match read_body(body, cache.max_body_size).await {
+        Ok(Ok(body)) => body,
+        Ok(Err(body)) => return Response::from_parts(parts, body),
+        Err(err) => {
+            tracing::warn!(?err, key, "Read response body failed");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let mut headers = parts.headers;
+    headers.remove(header::CONTENT_LENGTH);
+    if headers.typed_get::<ETag>().is_none() {
+        headers.typed_insert(conditional::etag(&body));
+    }
+    let mut scopes = route_scopes(route.as_ref().map(MatchedPath::as_str), &params);
+    if let Some(Scopes(extra)) = parts.extensions.get::<Scopes>() {
+        scopes.extend(extra.iter().cloned());
+    }
+    let entry = Entry {
+        headers: headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.as_bytes().to_vec()))
+            .collect(),
+        body,
+    };
+    if let Err(err) = store.put(&key, &entry, &scopes, cache.ttl).await {
+        tracing::warn!(?err, key, "Keep response failed");
+    }
+
+    let mut response = respond(&req_headers, entry, "MISS");
+    response.extensions_mut().extend(parts.extensions);
+    response
+}
+
+/// Read the body while it fits in `limit`, or give it back whole, the part
+/// already read followed by the rest.
+async fn read_body(body: Body, limit: usize) -> Result<Result<Bytes, Body>, axum::Error> {
+    let mut stream = body.into_data_stream();
+    let mut read = BytesMut::new();
+    while let Some(chunk) = stream.next().await {
+        read.extend_from_slice(&chunk?);
+        if read.len() > limit {
+            let head = stream::once(future::ready(Ok(read.freeze())));
+            return Ok(Err(Body::from_stream(head.chain(stream))));
+        }
+    }
+    Ok(Ok(read.freeze()))
+}
+
+fn respond(req_headers: &HeaderMap, entry: Entry, status: &'static str) -> Response {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &entry.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::try_from(name.as_str()),
+            HeaderValue::from_bytes(value),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    headers.insert(X_CACHE.clone(), HeaderValue::from_static(status));
+
+    let etag = headers
+        .typed_get::<ETag>()
+        .unwrap_or_else(|| conditional::etag(&entry.body));
+    let last_modified = headers.typed_get::<LastModified>().map(SystemTime::from);
+    if conditional::is_not_modified(req_headers, &etag, last_modified) {
+        headers.remove(header::CONTENT_TYPE);
+        headers.remove(header::CONTENT_LENGTH);
+        return (StatusCode::NOT_MODIFIED, headers).into_response();
+    }
+
+    (headers, Body::from(entry.body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunked(chunks: &[&'static str]) -> Body {
+        let chunks: Vec<Result<Bytes, axum::Error>> =
+            chunks.iter().map(|chunk| Ok(Bytes::from(*chunk))).collect();
+        Body::from_stream(stream::iter(chunks))
+    }
+
+    #[tokio::test]
+    async fn read_body_within_the_limit() {
+        let body = read_body(chunked(&["abc", "def"]), 6).await.unwrap();
+
+        assert_eq!(body.unwrap(), "abcdef");
+    }
+
+    #[tokio::test]
+    async fn read_body_gives_back_a_larger_body_whole() {
+        let body = read_body(chunked(&["abc", "def", "ghi"]), 4).await.unwrap();
+
+        let body = body.unwrap_err();
+        let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        assert_eq!(body, "abcdefghi");
+    }
+}
@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use redis::{aio::ConnectionManager, AsyncCommands, AsyncIter, Client};
+use secrecy::ExposeSecret;
+use tokio::sync::OnceCell;
+
+use super::{Entry, Store};
+use crate::{
+    conf::Redis,
+    error::{AppError, AppResult},
+};
+
+/// Entries shared by every server using the same Redis database.
+///
+/// A response is a hash of its headers and body, each scope a set of the
+/// keys of its responses. Both expire on their own.
+pub struct RedisStore {
+    client: Client,
+    /// Connected on first use, `AppState::new` is not async
+    conn: OnceCell<ConnectionManager>,
+    prefix: String,
+}
+
+impl RedisStore {
+    pub fn new(conf: &Redis) -> anyhow::Result<Self> {
+        let url = conf
+            .url
+            .as_ref()
+            .map(|url| url.expose_secret().as_str())
+            .unwrap_or("");
+        Ok(Self {
+            client: Client::open(url)?,
+            conn: OnceCell::new(),
+            prefix: conf.prefix.clone(),
+        })
+    }
+
+    async fn conn(&self) -> AppResult<ConnectionManager> {
+        let conn = self
+            .conn
+            .get_or_try_init(|| self.client.get_connection_manager())
+            .await
+            .map_err(unexpected)?;
+        Ok(conn.clone())
+    }
+
+    fn response_key(&self, key: &str) -> String {
+        format!("{}response:{}", self.prefix, key)
+    }
+
+    fn scope_key(&self, scope: &str) -> String {
+        format!("{}scope:{}", self.prefix, scope)
+    }
+}
+
+fn unexpected(err: redis::RedisError) -> AppError {
+    AppError::Unexpected(err.into())
+}
+
+#[async_trait]
+impl Store for RedisStore {
+    async fn get(&self, key: &str) -> AppResult<Option<Entry>> {
+        let mut conn = self.conn().await?;
+        let (headers, body): (Option<Vec<u8>>, Option<Vec<u8>>) = conn
+            .hget(self.response_key(key), &["headers", "body"])
+            .await
+            .map_err(unexpected)?;
+
+        match (headers, body) {
+            (Some(headers), Some(body)) => Ok(Some(Entry {
+                headers: serde_json::from_slice(&headers)
+                    .map_err(|err| AppError::Unexpected(err.into()))?,
+                body: body.into(),
+            })),
+            _ => Ok(None),
+        }
+    }
+
+    async fn put(
+        &self,
+        key: &str,
+        entry: &Entry,
+        scopes: &[String],
+        ttl: Duration,
+    ) -> AppResult<()> {
+        let headers =
+            serde_json::to_vec(&entry.headers).map_err(|err| AppError::Unexpected(err.into()))?;
+        let response_key = self.response_key(key);
+        let seconds = ttl.as_secs() as i64;
+
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .hset_multiple(
+                &response_key,
+                &[
+                    ("headers", headers.as_slice()),
+                    ("body", entry.body.as_ref()),
+                ],
+            )
+            .ignore()
+            .expire(&response_key, seconds)
+            .ignore();
+        for scope in scopes {
+            let scope_key = self.scope_key(scope);
+            pipe.sadd(&scope_key, &response_key)
+                .ignore()
+                .expire(&scope_key, seconds)
+                .ignore();
+        }
+
+        let mut conn = self.conn().await?;
+        pipe.query_async::<()>(&mut conn)
+            .await
+            .map_err(unexpected)?;
+        Ok(())
+    }
+
+    async fn evict(&self, scopes: &[String]) -> AppResult<()> {
+        let mut conn = self.conn().await?;
+        for scope in scopes {
+            let scope_key = self.scope_key(scope);
+            let mut keys: Vec<String> = conn.smembers(&scope_key).await.map_err(unexpected)?;
+            keys.push(scope_key);
+            conn.del::<_, ()>(keys).await.map_err(unexpected)?;
+        }
+        Ok(())
+    }
+
+    async fn clear(&self) -> AppResult<()> {
+        let mut conn = self.conn().await?;
+        let keys: Vec<String> = {
+            let iter: AsyncIter<String> = conn
+                .scan_match(format!("{}*", self.prefix))
+                .await
+                .map_err(unexpected)?;
+            futures::StreamExt::collect(iter).await
+        };
+        for keys in keys.chunks(500) {
+            conn.del::<_, ()>(keys).await.map_err(unexpected)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use sea_orm::Database;
+
+use crate::{backup, conf::Conf, media};
+
+#[derive(Debug, Args)]
+pub struct Cmd {
+    /// Archive the backup is written to
+    #[arg(short, long, default_value = "./letters-backup.tar.gz")]
+    pub out: PathBuf,
+}
+
+pub fn handle(cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
+    let storage = media::open_storage(conf)?;
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async move {
+            let conn = Database::connect(conf.database.url())
+                .await
+                .expect("Database connection failed");
+
+            let manifest = backup::backup(&conn, storage.as_ref(), &cmd.out).await?;
+            let rows: u64 = manifest.tables.iter().map(|table| table.rows).sum();
+            println!(
+                "Backed up {} rows and {} media files to {}.",
+                rows,
+                manifest.media.len(),
+                cmd.out.display()
+            );
+
+            Ok::<(), anyhow::Error>(())
+        })?;
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use clap::{Args, Subcommand};
+
+use crate::conf::Conf;
+
+#[derive(Debug, Args)]
+pub struct Cmd {
+    #[command(subcommand)]
+    pub action: Action,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Action {
+    /// List the files read and validate the result
+    Check,
+    /// Print the configuration as loaded, in TOML
+    Show {
+        /// Mask the secrets
+        #[arg(long)]
+        redacted: bool,
+    },
+}
+
+pub fn handle(cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
+    match cmd.action {
+        Action::Check => check(conf),
+        Action::Show { redacted } => {
+            let conf = if redacted {
+                conf.redacted()
+            } else {
+                conf.clone()
+            };
+            print!("{}", toml::to_string(&conf)?);
+            Ok(())
+        }
+    }
+}
+
+fn check(conf: &Conf) -> anyhow::Result<()> {
+    let location = conf.info.location.as_deref().unwrap_or("");
+    let profile = conf.info.profile.as_deref().unwrap_or("");
+    println!("Profile {}", profile);
+    for (name, _) in Conf::layers(location, profile) {
+        let state = if exists(&name) { "read" } else { "missing" };
+        println!("  {:40} {}", name, state);
+    }
+    println!(
+        "  {:40} read",
+        format!(
+            "{}_* variables",
+            conf.info.env_prefix.as_deref().unwrap_or("")
+        )
+    );
+
+    conf.check()?;
+    println!("The configuration is valid");
+    Ok(())
+}
+
+/// Whether a layer is there, with or without its extension.
+fn exists(name: &str) -> bool {
+    let path = Path::new(name);
+    path.extension().is_some() && path.is_file()
+        || ["toml", "json", "yaml", "yml", "ini", "ron", "json5"]
+            .iter()
+            .any(|ext| path.with_extension(ext).is_file())
+}
@@ -22,8 +22,7 @@ pub fn handle(cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
         .build()
         .unwrap()
         .block_on(async move {
-            let db_url = conf.database.url.clone().unwrap_or("".to_string());
-            let conn = Database::connect(db_url)
+            let conn = Database::connect(conf.database.url())
                 .await
                 .expect("Database connection failed");
 
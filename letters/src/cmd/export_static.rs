@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use sea_orm::Database;
+
+use crate::{conf::Conf, render::Theme, static_site};
+
+#[derive(Debug, Args)]
+pub struct Cmd {
+    /// Directory the site is written to
+    #[arg(short, long, default_value = "./public")]
+    pub out: PathBuf,
+
+    /// Render every file, even the unchanged ones
+    #[arg(long)]
+    pub full: bool,
+}
+
+pub fn handle(cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
+    let theme = Theme::load(&conf.theme.dir)?;
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async move {
+            let conn = Database::connect(conf.database.url())
+                .await
+                .expect("Database connection failed");
+
+            let report = static_site::export(&conn, conf, &theme, &cmd.out, cmd.full).await?;
+            println!(
+                "Exported to {}: {} written, {} unchanged, {} removed.",
+                cmd.out.display(),
+                report.written,
+                report.unchanged,
+                report.removed
+            );
+
+            Ok::<(), anyhow::Error>(())
+        })?;
+
+    Ok(())
+}
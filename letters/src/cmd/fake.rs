@@ -1,72 +1,92 @@
 use clap::Args;
-use fake::{Fake, Faker};
 use sea_orm::Database;
 
-use crate::conf::Conf;
-use crate::dto::article::ArticleRequest;
-use crate::dto::category::CategoryRequest;
-use crate::dto::tag::TagRequest;
-use crate::dto::user::NewUser;
-use crate::repos::{article, category, tag, user};
+use crate::{
+    conf::Conf,
+    dataset::{self, Locale, Options},
+};
 
 #[derive(Debug, Args)]
-pub struct Cmd {}
+pub struct Cmd {
+    #[arg(long, default_value_t = 1)]
+    pub users: usize,
 
-pub fn handle(_cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
-    println!("Fake some data for test");
+    #[arg(long, default_value_t = 10)]
+    pub categories: usize,
+
+    #[arg(long, default_value_t = 30)]
+    pub tags: usize,
+
+    #[arg(long, default_value_t = 30)]
+    pub articles: usize,
+
+    #[arg(long, default_value_t = 3)]
+    pub series: usize,
+
+    #[arg(long, default_value_t = 100)]
+    pub comments: usize,
+
+    /// Seed of the random generator, the same seed gives the same dataset.
+    /// A random one is used and printed when it is not given
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Language of the names of the users, categories and tags
+    #[arg(long, value_enum, default_value = "en")]
+    pub locale: Locale,
+
+    /// Password of every user
+    #[arg(long, default_value = "Pa$$wd123")]
+    pub password: String,
+
+    /// Rows inserted by a single statement, databases limit the number of
+    /// values a statement can hold
+    #[arg(long, default_value_t = 500)]
+    pub batch_size: usize,
+}
+
+pub fn handle(cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
+    let seed = cmd.seed.unwrap_or_else(rand::random);
+    println!("Faking data with the seed {}", seed);
+
+    let options = Options {
+        users: cmd.users,
+        categories: cmd.categories,
+        tags: cmd.tags,
+        articles: cmd.articles,
+        series: cmd.series,
+        comments: cmd.comments,
+        seed,
+        locale: cmd.locale,
+        password: cmd.password.clone(),
+        batch_size: cmd.batch_size,
+    };
 
     tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
         .unwrap()
         .block_on(async move {
-            let db_url = conf.database.url.clone().unwrap_or("".to_string());
-            let conn = Database::connect(db_url)
+            let conn = Database::connect(conf.database.url())
                 .await
                 .expect("Database connection failed");
 
-            let user_data: NewUser = Faker.fake();
-            let user_id = user::create(&conn, &user_data)
-                .await
-                .expect("Create user failed");
-
-            for _i in 0..30 {
-                let tag_data: TagRequest = Faker.fake();
-                let t = tag::check_name_exist(&conn, &tag_data.name)
-                    .await
-                    .unwrap_or(None);
-                if t.is_some() {
-                    continue;
-                }
-
-                tag::create(&conn, &tag_data)
-                    .await
-                    .expect("Create tag failed");
+            if cmd.batch_size == 0 {
+                anyhow::bail!("The batch size can not be 0");
             }
-
-            let mut category_id = 1;
-            for _i in 0..10 {
-                let category_data: CategoryRequest = Faker.fake();
-                let c = category::check_name_exist(&conn, &category_data.name)
-                    .await
-                    .unwrap_or(None);
-                if c.is_some() {
-                    continue;
-                }
-
-                category_id = category::create(&conn, &category_data)
-                    .await
-                    .expect("Create category failed");
+            if cmd.users == 0 && cmd.articles > 0 {
+                anyhow::bail!("Articles need at least one user");
             }
-
-            for _i in 0..30 {
-                let mut article_data: ArticleRequest = Faker.fake();
-                article_data.category_id = Some(category_id);
-                article::create(&conn, user_id, &article_data)
-                    .await
-                    .expect("Create article failed");
+            if cmd.categories == 0 && cmd.articles > 0 {
+                anyhow::bail!("Articles need at least one category");
             }
-        });
+
+            let report = dataset::generate(&conn, &options).await?;
+            print!("{}", report);
+            println!("Every user logs in with the password {}", options.password);
+            println!("Run `reindex` to make the articles searchable");
+            Ok(())
+        })?;
 
     Ok(())
 }
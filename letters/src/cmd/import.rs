@@ -0,0 +1,99 @@
+use std::{io::Write, path::PathBuf};
+
+use clap::{Args, ValueEnum};
+use sea_orm::Database;
+
+use crate::{
+    conf::Conf,
+    import::{markdown, wxr, Plan},
+    repos::user,
+};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// Directory of markdown files with a front matter, as used by Hugo,
+    /// Hexo and Jekyll
+    Markdown,
+    /// WordPress export file
+    Wxr,
+}
+
+#[derive(Debug, Args)]
+pub struct Cmd {
+    /// Directory of the markdown files, searched recursively, or WXR file
+    pub path: PathBuf,
+
+    #[arg(short, long, value_enum, default_value = "markdown")]
+    pub format: Format,
+
+    /// Email of the user the articles without a known author are created for
+    #[arg(short, long, value_name = "EMAIL")]
+    pub email: String,
+
+    /// Category of the posts which have none
+    #[arg(long, default_value = "Uncategorized")]
+    pub default_category: String,
+
+    /// Category of the WordPress pages
+    #[arg(long, default_value = "Pages")]
+    pub pages_category: String,
+
+    /// Only print what would be imported
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Import without asking for a confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+pub fn handle(cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
+    let export = match cmd.format {
+        Format::Markdown => markdown::read_dir(&cmd.path),
+        Format::Wxr => {
+            let xml = std::fs::read_to_string(&cmd.path)?;
+            wxr::read(&xml, &cmd.pages_category).map_err(anyhow::Error::msg)?
+        }
+    };
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async move {
+            let conn = Database::connect(conf.database.url())
+                .await
+                .expect("Database connection failed");
+
+            let Some(user) = user::read_by_email(&conn, &cmd.email).await? else {
+                anyhow::bail!("No user with the email {}", cmd.email);
+            };
+
+            let plan = Plan::new(&conn, export, &cmd.default_category).await?;
+            print!("{}", plan);
+            let nothing = plan.posts.is_empty() && plan.comments.is_empty();
+            if cmd.dry_run || nothing || !(cmd.yes || confirm()?) {
+                return Ok(());
+            }
+
+            let report = plan.apply(&conn, user.id).await?;
+            println!(
+                "Imported {} articles and {} comments, created {} users and {} redirects.",
+                report.articles, report.comments, report.users, report.redirects
+            );
+            println!("Run `reindex` to make them searchable.");
+
+            Ok::<(), anyhow::Error>(())
+        })?;
+
+    Ok(())
+}
+
+fn confirm() -> anyhow::Result<bool> {
+    print!("Import them? [y/N] ");
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
@@ -1,25 +1,263 @@
-use clap::Args;
+use std::{
+    collections::HashMap,
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use clap::{Args, Subcommand};
 use migration::{Migrator, MigratorTrait};
-use sea_orm_migration::sea_orm::Database;
+use sea_orm::{
+    ConnectionTrait, Database, DatabaseConnection, DbErr, ProxyDatabaseTrait, ProxyExecResult,
+    ProxyRow, Statement,
+};
+use sea_orm_migration::{MigrationTrait, SchemaManager};
 
 use crate::conf::Conf;
 
 #[derive(Debug, Args)]
-pub struct Cmd {}
+pub struct Cmd {
+    #[command(subcommand)]
+    pub action: Option<Action>,
+
+    /// Only print the SQL the migrations would run
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Roll back or drop without asking for a confirmation, it is only asked
+    /// when the server is not in debug mode
+    #[arg(short, long, global = true)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Action {
+    /// Apply the pending migrations, the default
+    Up {
+        /// Stop after the migration of this name
+        #[arg(long, value_name = "NAME")]
+        to: Option<String>,
+    },
+    /// Roll back the last applied migrations
+    Down {
+        /// Number of migrations to roll back
+        #[arg(default_value_t = 1)]
+        n: u32,
+    },
+    /// List the applied and the pending migrations
+    Status,
+    /// Drop every table, then apply all the migrations
+    Fresh,
+    /// Roll back all the applied migrations, then apply all of them
+    Refresh,
+}
+
+impl Action {
+    /// What the action destroys, `None` when it keeps the data.
+    fn destroys(&self) -> Option<String> {
+        match self {
+            Action::Up { .. } | Action::Status => None,
+            Action::Down { n: 1 } => {
+                Some("roll back the last migration and drop the data it holds".to_string())
+            }
+            Action::Down { n } => Some(format!(
+                "roll back the last {} migrations and drop the data they hold",
+                n
+            )),
+            Action::Fresh => Some("drop every table of the database".to_string()),
+            Action::Refresh => Some("roll back every migration and drop all the data".to_string()),
+        }
+    }
+}
+
+pub fn handle(cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
+    let default = Action::Up { to: None };
+    let action = cmd.action.as_ref().unwrap_or(&default);
 
-pub fn handle(_cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
     tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
         .unwrap()
         .block_on(async move {
-            let db_url = conf.database.url.clone().unwrap_or("".to_string());
-            let conn = Database::connect(db_url)
+            let conn = Database::connect(conf.database.url())
                 .await
                 .expect("Database connection failed");
 
-            Migrator::up(&conn, None).await.unwrap();
-        });
+            if let Action::Status = action {
+                return status(&conn).await;
+            }
+            if cmd.dry_run {
+                return print_sql(&conn, action).await;
+            }
+            if let Some(what) = action.destroys() {
+                if !(conf.server.is_debug() || cmd.yes || confirm(&what)?) {
+                    return Ok(());
+                }
+            }
+
+            match action {
+                Action::Up { to } => {
+                    let steps = match to {
+                        Some(name) => Some(steps_to(&conn, name).await?),
+                        None => None,
+                    };
+                    Migrator::up(&conn, steps).await?;
+                }
+                Action::Down { n } => Migrator::down(&conn, Some(*n)).await?,
+                Action::Fresh => Migrator::fresh(&conn).await?,
+                Action::Refresh => Migrator::refresh(&conn).await?,
+                Action::Status => unreachable!(),
+            }
+
+            status(&conn).await
+        })?;
+
+    Ok(())
+}
+
+/// Print every migration with when it was applied.
+async fn status(conn: &DatabaseConnection) -> anyhow::Result<()> {
+    let applied: HashMap<String, i64> = Migrator::get_migration_models(conn)
+        .await?
+        .into_iter()
+        .map(|model| (model.version, model.applied_at))
+        .collect();
+
+    let migrations = Migrator::migrations();
+    let width = migrations
+        .iter()
+        .map(|migration| migration.name().len())
+        .max()
+        .unwrap_or(0);
+    for migration in &migrations {
+        let state = match applied.get(migration.name()) {
+            Some(&applied_at) => match chrono::DateTime::from_timestamp(applied_at, 0) {
+                Some(at) => format!("applied at {}", at.format("%Y-%m-%d %H:%M:%S UTC")),
+                None => "applied".to_string(),
+            },
+            None => "pending".to_string(),
+        };
+        println!("{:width$}  {}", migration.name(), state, width = width);
+    }
+
+    Ok(())
+}
+
+/// Number of migrations `up` applies to stop after `name`.
+async fn steps_to(conn: &DatabaseConnection, name: &str) -> anyhow::Result<u32> {
+    let pending = Migrator::get_pending_migrations(conn).await?;
+    if let Some(index) = pending
+        .iter()
+        .position(|migration| migration.name() == name)
+    {
+        return Ok(index as u32 + 1);
+    }
+
+    if Migrator::migrations()
+        .iter()
+        .any(|migration| migration.name() == name)
+    {
+        anyhow::bail!("The migration {} is already applied", name);
+    }
+    anyhow::bail!("No migration is named {}", name);
+}
+
+/// Print the statements `action` would run, in order.
+///
+/// The migrations are run against a connection which records the statements
+/// instead of executing them, the database is only read to know which ones
+/// are applied.
+async fn print_sql(conn: &DatabaseConnection, action: &Action) -> anyhow::Result<()> {
+    let migrations = Migrator::migrations();
+    let applied: Vec<String> = Migrator::get_applied_migrations(conn)
+        .await?
+        .iter()
+        .map(|migration| migration.name().to_string())
+        .collect();
+    let find = |name: &str| {
+        migrations
+            .iter()
+            .find(|migration| migration.name() == name)
+            .expect("an applied migration has a file")
+    };
+    let rollback = applied.iter().rev().map(|name| (find(name), false));
+
+    let steps: Vec<(&Box<dyn MigrationTrait>, bool)> = match action {
+        Action::Up { to } => {
+            let steps = match to {
+                Some(name) => steps_to(conn, name).await? as usize,
+                None => usize::MAX,
+            };
+            migrations
+                .iter()
+                .filter(|migration| !applied.iter().any(|name| name == migration.name()))
+                .take(steps)
+                .map(|migration| (migration, true))
+                .collect()
+        }
+        Action::Down { n } => rollback.take(*n as usize).collect(),
+        Action::Fresh => {
+            println!("-- Drop every table of the database");
+            println!();
+            migrations
+                .iter()
+                .map(|migration| (migration, true))
+                .collect()
+        }
+        Action::Refresh => rollback
+            .chain(migrations.iter().map(|migration| (migration, true)))
+            .collect(),
+        Action::Status => unreachable!(),
+    };
+
+    let recorder = Recorder::default();
+    let statements = Arc::clone(&recorder.statements);
+    let proxy = Database::connect_proxy(
+        conn.get_database_backend(),
+        Arc::new(Mutex::new(Box::new(recorder))),
+    )
+    .await?;
+    let manager = SchemaManager::new(&proxy);
+
+    for (migration, up) in steps {
+        if up {
+            println!("-- Apply {}", migration.name());
+            migration.up(&manager).await?;
+        } else {
+            println!("-- Roll back {}", migration.name());
+            migration.down(&manager).await?;
+        }
+        for statement in statements.lock().unwrap().drain(..) {
+            println!("{};", statement);
+        }
+        println!();
+    }
 
     Ok(())
 }
+
+/// Connection keeping the statements it is given, queries read no rows.
+#[derive(Debug, Default)]
+struct Recorder {
+    statements: Arc<Mutex<Vec<String>>>,
+}
+
+impl ProxyDatabaseTrait for Recorder {
+    fn query(&self, statement: Statement) -> Result<Vec<ProxyRow>, DbErr> {
+        self.statements.lock().unwrap().push(statement.to_string());
+        Ok(vec![])
+    }
+
+    fn execute(&self, statement: Statement) -> Result<ProxyExecResult, DbErr> {
+        self.statements.lock().unwrap().push(statement.to_string());
+        Ok(ProxyExecResult::default())
+    }
+}
+
+fn confirm(what: &str) -> anyhow::Result<bool> {
+    print!("This will {}. Continue? [y/N] ", what);
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
@@ -1,7 +1,14 @@
+mod backup;
+mod config;
 mod create_admin;
+mod export_static;
 mod fake;
 mod hello;
+mod import;
 mod migrate;
+mod purge;
+mod reindex;
+mod restore;
 mod serve;
 
 use clap::{Parser, Subcommand};
@@ -20,6 +27,11 @@ pub struct Cmd {
     )]
     pub config: Option<String>,
 
+    /// Profile layered over the configuration file, read from
+    /// `<profile>.toml` next to it
+    #[arg(long, env = "LETTERS_PROFILE", default_value = "development")]
+    pub profile: String,
+
     #[command(subcommand)]
     pub subcmd: Option<SubCmd>,
 }
@@ -28,14 +40,28 @@ pub struct Cmd {
 pub enum SubCmd {
     /// Hello World!
     Hello(hello::Cmd),
+    /// Check or show the configuration
+    Config(config::Cmd),
     /// Start HTTP server
     Serve(serve::Cmd),
-    /// Run database migrations
+    /// Apply, roll back or list the database migrations
     Migrate(migrate::Cmd),
     // Create the default admin user
     CreateAdmin(create_admin::Cmd),
-    // Fake some database for test
+    /// Fill the database with fake users, articles, series and comments
     Fake(fake::Cmd),
+    /// Hard delete trashed rows older than the retention period
+    Purge(purge::Cmd),
+    /// Rebuild the full-text search index
+    Reindex(reindex::Cmd),
+    /// Render the public site into static html files
+    ExportStatic(export_static::Cmd),
+    /// Import the posts of Hugo, Hexo, Jekyll or WordPress
+    Import(import::Cmd),
+    /// Write the database and the media files into a single archive
+    Backup(backup::Cmd),
+    /// Load an archive written by `backup` into an empty database
+    Restore(restore::Cmd),
 }
 
 pub fn setup() -> anyhow::Result<Cmd> {
@@ -44,7 +70,15 @@ pub fn setup() -> anyhow::Result<Cmd> {
 }
 
 pub fn handle(cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
+    // `config` reports the problems itself.
+    if !matches!(cmd.subcmd, Some(SubCmd::Config(_))) {
+        conf.check()?;
+    }
+
     match &cmd.subcmd {
+        Some(SubCmd::Config(subcmd)) => {
+            config::handle(subcmd, conf)?;
+        }
         Some(SubCmd::Serve(subcmd)) => {
             serve::handle(subcmd, conf)?;
         }
@@ -60,6 +94,24 @@ pub fn handle(cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
         Some(SubCmd::Fake(subcmd)) => {
             fake::handle(subcmd, conf)?;
         }
+        Some(SubCmd::Purge(subcmd)) => {
+            purge::handle(subcmd, conf)?;
+        }
+        Some(SubCmd::Reindex(subcmd)) => {
+            reindex::handle(subcmd, conf)?;
+        }
+        Some(SubCmd::ExportStatic(subcmd)) => {
+            export_static::handle(subcmd, conf)?;
+        }
+        Some(SubCmd::Import(subcmd)) => {
+            import::handle(subcmd, conf)?;
+        }
+        Some(SubCmd::Backup(subcmd)) => {
+            backup::handle(subcmd, conf)?;
+        }
+        Some(SubCmd::Restore(subcmd)) => {
+            restore::handle(subcmd, conf)?;
+        }
         None => todo!(),
     }
 
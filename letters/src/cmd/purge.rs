@@ -0,0 +1,37 @@
+use chrono::{Duration, Utc};
+use clap::Args;
+use sea_orm::Database;
+
+use crate::{conf::Conf, repos::trash};
+
+#[derive(Debug, Args)]
+pub struct Cmd {
+    /// Purge rows trashed more than DAYS days ago, defaults to `trash.retention_days`
+    #[arg(short, long, value_name = "DAYS")]
+    days: Option<i64>,
+}
+
+pub fn handle(cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
+    let days = cmd.days.unwrap_or(conf.trash.retention_days);
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async move {
+            let conn = Database::connect(conf.database.url())
+                .await
+                .expect("Database connection failed");
+
+            let report = trash::purge(&conn, Utc::now() - Duration::days(days)).await?;
+
+            println!(
+                "Purged {} articles, {} categories, {} tags and {} users.",
+                report.articles, report.categories, report.tags, report.users
+            );
+
+            Ok::<(), anyhow::Error>(())
+        })?;
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use clap::Args;
+use sea_orm::Database;
+
+use crate::{conf::Conf, search::SearchIndex};
+
+#[derive(Debug, Args)]
+pub struct Cmd {}
+
+pub fn handle(_cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
+    // The index only accepts one writer, so the server has to be stopped.
+    let index = SearchIndex::open(&conf.search.index_dir)?;
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async move {
+            let conn = Database::connect(conf.database.url())
+                .await
+                .expect("Database connection failed");
+
+            let count = index.rebuild(&conn).await?;
+            println!("Indexed {} articles.", count);
+
+            Ok::<(), anyhow::Error>(())
+        })?;
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use sea_orm::Database;
+
+use crate::{backup, conf::Conf, media};
+
+#[derive(Debug, Args)]
+pub struct Cmd {
+    /// Archive written by the `backup` command
+    pub archive: PathBuf,
+}
+
+pub fn handle(cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
+    let storage = media::open_storage(conf)?;
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async move {
+            let conn = Database::connect(conf.database.url())
+                .await
+                .expect("Database connection failed");
+
+            let manifest = backup::restore(&conn, storage.as_ref(), &cmd.archive).await?;
+            let rows: u64 = manifest.tables.iter().map(|table| table.rows).sum();
+            println!(
+                "Restored {} rows and {} media files from the backup of {}.",
+                rows,
+                manifest.media.len(),
+                manifest.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+            );
+            println!("Run `reindex` to rebuild the search index.");
+
+            Ok::<(), anyhow::Error>(())
+        })?;
+
+    Ok(())
+}
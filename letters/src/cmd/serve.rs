@@ -4,28 +4,29 @@ use crate::{app, conf::Conf};
 
 #[derive(Debug, Args)]
 pub struct Cmd {
-    /// TCP port to listen on
-    #[arg(short, long, value_name = "PORT", default_value = "8000")]
+    /// TCP port to listen on when no address is bound
+    #[arg(short, long, value_name = "PORT")]
     port: Option<u16>,
+
+    /// Address to listen on, `host:port` or `unix:<path>`, in place of the
+    /// configured ones. Can be given several times
+    #[arg(short, long, value_name = "ADDRESS")]
+    bind: Vec<String>,
 }
 
 pub fn handle(cmd: &Cmd, conf: &Conf) -> anyhow::Result<()> {
-    let port = cmd.port.unwrap_or(conf.server.port);
-    start_server(port, conf)?;
-
-    Ok(())
-}
+    let mut conf = conf.clone();
+    if let Some(port) = cmd.port {
+        conf.server.port = port;
+    }
+    if !cmd.bind.is_empty() {
+        conf.server.bind = cmd.bind.clone();
+    }
+    conf.check()?;
 
-fn start_server(port: u16, conf: &Conf) -> anyhow::Result<()> {
     tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
         .unwrap()
-        .block_on(async move {
-            app::serve(port, conf).await;
-
-            Ok::<(), anyhow::Error>(())
-        })?;
-
-    std::process::exit(0);
+        .block_on(app::serve(&conf))
 }
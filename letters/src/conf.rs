@@ -1,57 +1,774 @@
+use std::path::Path;
+
 use config::{Config, Environment, File};
-use serde::Deserialize;
+use garde::Validate;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Characters escaped in a path segment.
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
 
-#[derive(Debug, Deserialize, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Validate, Clone)]
+#[serde(default)]
 pub struct Server {
+    #[garde(skip)]
     pub port: u16,
-    pub log_level: Option<String>,
+    /// `debug` or `release`
+    #[garde(inner(custom(is_mode)))]
+    pub mode: Option<String>,
+    /// Addresses to listen on, `host:port` or `unix:<path>`, only
+    /// `127.0.0.1:<port>` when empty
+    #[garde(inner(custom(is_address)))]
+    pub bind: Vec<String>,
+    /// Seconds the requests in flight are given to finish on shutdown
+    #[garde(skip)]
+    pub shutdown_timeout_seconds: u64,
+    /// Seconds a request may take before it is answered with a 408, 0 for
+    /// no limit
+    #[garde(skip)]
+    pub request_timeout_seconds: u64,
+    /// Largest request body, in bytes. The uploads are limited by
+    /// `media.max_size` instead
+    #[garde(range(min = 1))]
+    pub body_limit: usize,
+    /// Serve HTTPS instead of HTTP on every address
+    #[garde(dive)]
+    pub tls: Option<Tls>,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self {
+            port: 5000,
+            mode: None,
+            bind: Vec::new(),
+            shutdown_timeout_seconds: 30,
+            request_timeout_seconds: 60,
+            body_limit: 2 * 1024 * 1024,
+            tls: None,
+        }
+    }
+}
+
+impl Server {
+    pub fn is_debug(&self) -> bool {
+        self.mode.as_deref() == Some("debug")
+    }
+
+    /// The addresses to listen on, with the default one when none is set.
+    pub fn addresses(&self) -> Vec<String> {
+        if self.bind.is_empty() {
+            vec![format!("127.0.0.1:{}", self.port)]
+        } else {
+            self.bind.clone()
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Validate, Clone)]
+#[serde(default)]
+pub struct Tls {
+    /// PEM file of the certificate chain
+    #[garde(length(min = 1))]
+    pub cert: String,
+    /// PEM file of the private key
+    #[garde(length(min = 1))]
+    pub key: String,
+    /// Seconds between two checks of the files, which are loaded again
+    /// when they changed. 0 never loads them again
+    #[garde(skip)]
+    pub reload_interval_seconds: u64,
+}
+
+impl Default for Tls {
+    fn default() -> Self {
+        Self {
+            cert: "".to_string(),
+            key: "".to_string(),
+            reload_interval_seconds: 300,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, Default, Clone)]
 pub struct Database {
-    pub url: Option<String>,
+    #[serde(serialize_with = "expose_option")]
+    #[garde(required, inner(custom(not_empty)))]
+    pub url: Option<SecretString>,
 }
 
-#[derive(Debug, Deserialize, Default, Clone)]
+impl Database {
+    /// The url, empty when it is not set.
+    pub fn url(&self) -> &str {
+        self.url
+            .as_ref()
+            .map(|url| url.expose_secret().as_str())
+            .unwrap_or("")
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, Clone)]
 pub struct Auth {
-    pub secret: String,
+    /// Key signing the tokens
+    #[serde(serialize_with = "expose")]
+    #[garde(custom(is_long_secret))]
+    pub secret: SecretString,
+    #[garde(range(min = 1))]
     pub timeout_seconds: i64,
 }
 
-#[derive(Debug, Deserialize, Default, Clone)]
+impl Default for Auth {
+    fn default() -> Self {
+        Self {
+            secret: SecretString::new("".to_string()),
+            timeout_seconds: 0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, Clone)]
+#[serde(default)]
+pub struct Trash {
+    /// Days a trashed row is kept before it can be purged
+    #[garde(range(min = 0))]
+    pub retention_days: i64,
+    /// Hours between two purge runs of the server, 0 disables the job
+    #[garde(skip)]
+    pub purge_interval_hours: u64,
+}
+
+impl Default for Trash {
+    fn default() -> Self {
+        Self {
+            retention_days: 30,
+            purge_interval_hours: 24,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, Clone)]
+#[serde(default)]
+pub struct Search {
+    /// Directory of the full-text index
+    #[garde(length(min = 1))]
+    pub index_dir: String,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self {
+            index_dir: "./data/search".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, Clone)]
+#[serde(default)]
+pub struct Site {
+    /// Public url of the site, without the trailing slash
+    #[garde(url)]
+    pub base_url: String,
+    #[garde(skip)]
+    pub title: String,
+    #[garde(skip)]
+    pub description: String,
+    #[garde(length(min = 1))]
+    pub language: String,
+}
+
+impl Default for Site {
+    fn default() -> Self {
+        Self {
+            base_url: "http://127.0.0.1:5000".to_string(),
+            title: "Letters".to_string(),
+            description: "".to_string(),
+            language: "en".to_string(),
+        }
+    }
+}
+
+impl Site {
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url.trim_end_matches('/'), path)
+    }
+
+    pub fn article_url(&self, slug: &str) -> String {
+        self.url(&Self::article_path(slug))
+    }
+
+    pub fn article_path(slug: &str) -> String {
+        format!("/articles/{}", utf8_percent_encode(slug, SEGMENT))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, Clone)]
+#[serde(default)]
+pub struct Feed {
+    /// Put the whole article into the feed entries instead of the summary
+    #[garde(skip)]
+    pub full_content: bool,
+    /// Number of the latest articles in a feed
+    #[garde(range(min = 1))]
+    pub limit: u64,
+}
+
+impl Default for Feed {
+    fn default() -> Self {
+        Self {
+            full_content: false,
+            limit: 20,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, Clone)]
+#[serde(default)]
+pub struct Theme {
+    /// Directory of the templates of the html pages
+    #[garde(length(min = 1))]
+    pub dir: String,
+    /// Articles on a page of the index
+    #[garde(range(min = 1))]
+    pub per_page: u64,
+    /// Serve the html pages from the root of the site
+    #[garde(skip)]
+    pub frontend: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            dir: "./themes/default".to_string(),
+            per_page: 10,
+            frontend: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Seo {
+    /// Image shared on social networks when an article has no cover
+    pub default_image: Option<String>,
+    /// Twitter handle of the site, e.g. `@letters`
+    pub twitter_site: Option<String>,
+    pub robots_allow: Vec<String>,
+    pub robots_disallow: Vec<String>,
+}
+
+impl Default for Seo {
+    fn default() -> Self {
+        Self {
+            default_image: None,
+            twitter_site: None,
+            robots_allow: vec!["/".to_string()],
+            robots_disallow: vec![
+                "/api/".to_string(),
+                "/swagger-ui".to_string(),
+                "/rapidoc".to_string(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    #[default]
+    Local,
+    S3,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, Clone)]
+#[serde(default)]
+pub struct Media {
+    #[garde(skip)]
+    pub backend: StorageBackend,
+    /// Largest accepted upload, in bytes
+    #[garde(range(min = 1))]
+    pub max_size: u64,
+    /// Mime types accepted for upload
+    #[garde(skip)]
+    pub allowed_types: Vec<String>,
+    /// Root directory of the local backend
+    #[garde(length(min = 1))]
+    pub local_dir: String,
+    /// Url the stored files are reachable from, defaults to `/media` of the
+    /// site for the local backend and to `<endpoint>/<bucket>` for S3
+    #[garde(inner(url))]
+    pub public_url: Option<String>,
+    #[garde(custom(is_s3_ready(self.backend)))]
+    pub s3: S3,
+    #[garde(dive)]
+    pub images: Images,
+}
+
+impl Default for Media {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackend::Local,
+            max_size: 10 * 1024 * 1024,
+            allowed_types: [
+                "image/jpeg",
+                "image/png",
+                "image/gif",
+                "image/webp",
+                "image/avif",
+                "application/pdf",
+            ]
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+            local_dir: "./data/media".to_string(),
+            public_url: None,
+            s3: S3::default(),
+            images: Images::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, Clone)]
+#[serde(default)]
+pub struct Images {
+    /// Widths of the resized variants generated on upload
+    #[garde(skip)]
+    pub variant_widths: Vec<u32>,
+    /// Also generate the variants as WebP
+    #[garde(skip)]
+    pub webp: bool,
+    /// Also generate the variants as AVIF, needs the `avif` feature
+    #[garde(skip)]
+    pub avif: bool,
+    #[garde(range(min = 1, max = 100))]
+    pub quality: u8,
+    /// Widest image the resize endpoint produces
+    #[garde(range(min = 1))]
+    pub max_resize_width: u32,
+    /// Key signing the resize urls, the auth secret when empty
+    #[serde(serialize_with = "expose")]
+    #[garde(skip)]
+    pub signing_key: SecretString,
+}
+
+impl Default for Images {
+    fn default() -> Self {
+        Self {
+            variant_widths: vec![320, 640, 1280],
+            webp: true,
+            avif: false,
+            quality: 85,
+            max_resize_width: 2560,
+            signing_key: SecretString::new("".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct S3 {
+    pub bucket: String,
+    pub region: String,
+    /// Endpoint of an S3 compatible service, e.g. a local MinIO
+    pub endpoint: Option<String>,
+    pub access_key_id: String,
+    #[serde(serialize_with = "expose")]
+    pub secret_access_key: SecretString,
+    pub allow_http: bool,
+}
+
+impl Default for S3 {
+    fn default() -> Self {
+        Self {
+            bucket: "letters".to_string(),
+            region: "us-east-1".to_string(),
+            endpoint: None,
+            access_key_id: "".to_string(),
+            secret_access_key: SecretString::new("".to_string()),
+            allow_http: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, Clone)]
+#[serde(default)]
+pub struct Log {
+    /// Filter directives, e.g. `info,sqlx=warn`. `RUST_LOG` takes over when
+    /// it is set
+    #[garde(custom(is_filter))]
+    pub level: String,
+    /// Format of the console output
+    #[garde(skip)]
+    pub format: LogFormat,
+    /// Directory of the log files, none are written when unset
+    #[garde(inner(length(min = 1)))]
+    pub dir: Option<String>,
+    /// Format of the log files
+    #[garde(skip)]
+    pub file_format: LogFormat,
+    /// When a new file is started
+    #[garde(skip)]
+    pub rotation: LogRotation,
+    /// Files kept, the oldest are deleted past it. 0 keeps them all
+    #[garde(skip)]
+    pub max_files: usize,
+}
+
+impl Default for Log {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            format: LogFormat::Pretty,
+            dir: Some("./logs".to_string()),
+            file_format: LogFormat::Full,
+            rotation: LogRotation::Daily,
+            max_files: 5,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Multi-line, for reading in a terminal
+    Pretty,
+    /// One line per event, span fields included
+    Full,
+    /// One line per event, without the span fields
+    Compact,
+    /// One JSON object per line
+    Json,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Minutely,
+    Hourly,
+    Daily,
+    Never,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, Clone)]
+#[serde(default)]
+pub struct Cache {
+    /// Keep the public pages, listings and feeds rendered
+    #[garde(skip)]
+    pub enabled: bool,
+    #[garde(skip)]
+    pub backend: CacheBackend,
+    /// Seconds a response is kept, however long nothing it shows changes
+    #[garde(range(min = 1))]
+    pub ttl_seconds: u64,
+    /// Responses kept in memory, the closest to expire make room for the
+    /// new ones
+    #[garde(range(min = 1))]
+    pub max_entries: usize,
+    /// Largest body kept, in bytes
+    #[garde(skip)]
+    pub max_body_size: usize,
+    #[garde(custom(is_redis_ready(self.backend)))]
+    pub redis: Redis,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            backend: CacheBackend::Memory,
+            ttl_seconds: 300,
+            max_entries: 10000,
+            max_body_size: 1024 * 1024,
+            redis: Redis::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheBackend {
+    #[default]
+    Memory,
+    /// Shared by every server, needs the `redis` feature
+    Redis,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Redis {
+    /// e.g. `redis://127.0.0.1:6379/0`
+    #[serde(serialize_with = "expose_option")]
+    pub url: Option<SecretString>,
+    /// Prefix of every key, for servers sharing a database
+    pub prefix: String,
+}
+
+impl Default for Redis {
+    fn default() -> Self {
+        Self {
+            url: None,
+            prefix: "letters:".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, Clone)]
+#[serde(default)]
+pub struct Telemetry {
+    /// Base url of the OpenTelemetry collector the spans are exported to,
+    /// e.g. `http://127.0.0.1:4318`. Nothing is exported when unset
+    #[garde(inner(url))]
+    pub otlp_endpoint: Option<String>,
+    /// `http/protobuf` or `http/json`
+    #[garde(custom(is_otlp_protocol))]
+    pub otlp_protocol: String,
+    /// `service.name` of the exported spans
+    #[garde(length(min = 1))]
+    pub service_name: String,
+    /// Share of the traces started here which are exported, the ones
+    /// continued from a `traceparent` follow the caller's decision
+    #[garde(range(min = 0.0, max = 1.0))]
+    pub sample_ratio: f64,
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            otlp_protocol: "http/protobuf".to_string(),
+            service_name: "letters".to_string(),
+            sample_ratio: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct ConfInfo {
     pub location: Option<String>,
+    pub profile: Option<String>,
     pub env_prefix: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Validate, Default, Clone)]
 pub struct Conf {
     #[serde[default]]
+    #[garde(dive)]
     pub server: Server,
     #[serde[default]]
+    #[garde(dive)]
     pub database: Database,
     #[serde[default]]
+    #[garde(dive)]
     pub auth: Auth,
     #[serde[default]]
+    #[garde(dive)]
+    pub trash: Trash,
+    #[serde[default]]
+    #[garde(dive)]
+    pub search: Search,
+    #[serde[default]]
+    #[garde(dive)]
+    pub site: Site,
+    #[serde[default]]
+    #[garde(dive)]
+    pub feed: Feed,
+    #[serde[default]]
+    #[garde(dive)]
+    pub theme: Theme,
+    #[serde[default]]
+    #[garde(skip)]
+    pub seo: Seo,
+    #[serde[default]]
+    #[garde(dive)]
+    pub media: Media,
+    #[serde[default]]
+    #[garde(dive)]
+    pub cache: Cache,
+    #[serde[default]]
+    #[garde(dive)]
+    pub log: Log,
+    #[serde[default]]
+    #[garde(dive)]
+    pub telemetry: Telemetry,
+    #[serde[default]]
+    #[garde(skip)]
     pub info: ConfInfo,
 }
 
 impl Conf {
-    pub fn new(location: &str, env_prefix: &str) -> anyhow::Result<Self> {
-        //     let builder = Config::builder()
-        //         .add_source(File::with_name("config/default"))
-        //         .add_source(File::with_name(&format!("config/{}", run_mode)).required(false))
-        //         .add_source(File::with_name("config/local").required(false))
-        //         .add_source(Environment::with_prefix("letters"))
-        //         .build()?;
-
-        let builder = Config::builder()
-            .add_source(File::with_name(location))
-            .add_source(Environment::with_prefix(env_prefix).separator("_"))
+    /// Load the file at `location`, then the file of `profile` and
+    /// `local.toml` next to it when they exist, then the environment
+    /// variables starting with `env_prefix`. Each layer overrides the ones
+    /// before it.
+    ///
+    /// Sections are separated by `__` in the variables, as the keys have
+    /// `_` in them: `LETTERS_AUTH__TIMEOUT_SECONDS` sets `auth.timeout_seconds`.
+    pub fn new(location: &str, profile: &str, env_prefix: &str) -> anyhow::Result<Self> {
+        let mut builder = Config::builder();
+        for (name, required) in Self::layers(location, profile) {
+            builder = builder.add_source(File::with_name(&name).required(required));
+        }
+
+        let builder = builder
+            .add_source(
+                Environment::with_prefix(env_prefix)
+                    .prefix_separator("_")
+                    .separator("__"),
+            )
             .set_override("info.location", location)?
+            .set_override("info.profile", profile)?
             .set_override("info.env_prefix", env_prefix)?
             .build()?;
 
         Ok(builder.try_deserialize()?)
     }
+
+    /// The files `new` reads in order, with whether they must exist. The
+    /// layers take the extension of `location`, if any.
+    pub fn layers(location: &str, profile: &str) -> Vec<(String, bool)> {
+        let path = Path::new(location);
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let name = |stem: &str| match path.extension() {
+            Some(ext) => dir.join(format!("{}.{}", stem, ext.to_string_lossy())),
+            None => dir.join(stem),
+        };
+
+        vec![
+            (location.to_string(), true),
+            (name(profile).to_string_lossy().into_owned(), false),
+            (name("local").to_string_lossy().into_owned(), false),
+        ]
+    }
+
+    /// Check the values which would only fail once used, every problem is
+    /// listed with the key it comes from.
+    pub fn check(&self) -> anyhow::Result<()> {
+        self.validate(&())
+            .map_err(|report| anyhow::anyhow!("Invalid configuration:\n{}", report))
+    }
+
+    /// A copy with the secrets masked, for display.
+    pub fn redacted(&self) -> Self {
+        let mask = |secret: &SecretString| match secret.expose_secret().is_empty() {
+            true => SecretString::new("".to_string()),
+            false => SecretString::new(REDACTED.to_string()),
+        };
+
+        let mut conf = self.clone();
+        conf.database.url = conf.database.url.as_ref().map(mask);
+        conf.auth.secret = mask(&conf.auth.secret);
+        conf.media.s3.secret_access_key = mask(&conf.media.s3.secret_access_key);
+        conf.media.images.signing_key = mask(&conf.media.images.signing_key);
+        conf.cache.redis.url = conf.cache.redis.url.as_ref().map(mask);
+        conf
+    }
+}
+
+/// Shown in place of a secret by `Conf::redacted`.
+const REDACTED: &str = "********";
+
+/// Secrets are only written out on purpose, `Conf::redacted` masks them
+/// beforehand.
+fn expose<S: Serializer>(secret: &SecretString, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(secret.expose_secret())
+}
+
+fn expose_option<S: Serializer>(
+    secret: &Option<SecretString>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match secret {
+        Some(secret) => expose(secret, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn is_mode(mode: &str, _: &()) -> garde::Result {
+    match mode {
+        "debug" | "release" => Ok(()),
+        _ => Err(garde::Error::new("must be `debug` or `release`")),
+    }
+}
+
+fn is_filter(level: &str, _: &()) -> garde::Result {
+    tracing_subscriber::EnvFilter::try_new(level)
+        .map(|_| ())
+        .map_err(|err| garde::Error::new(err.to_string()))
+}
+
+fn is_otlp_protocol(protocol: &str, _: &()) -> garde::Result {
+    match protocol {
+        "http/protobuf" | "http/json" => Ok(()),
+        _ => Err(garde::Error::new("must be `http/protobuf` or `http/json`")),
+    }
+}
+
+/// `host:port`, or `unix:` followed by the path of a socket.
+fn is_address(address: &str, _: &()) -> garde::Result {
+    if let Some(path) = address.strip_prefix("unix:") {
+        return match path.is_empty() {
+            true => Err(garde::Error::new("the socket path is empty")),
+            false => Ok(()),
+        };
+    }
+    match address.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(()),
+        _ => Err(garde::Error::new("must be `host:port` or `unix:<path>`")),
+    }
+}
+
+fn not_empty(secret: &SecretString, _: &()) -> garde::Result {
+    match secret.expose_secret().is_empty() {
+        true => Err(garde::Error::new("must not be empty")),
+        false => Ok(()),
+    }
+}
+
+/// Tokens signed with a short secret can be forged by brute force.
+fn is_long_secret(secret: &SecretString, _: &()) -> garde::Result {
+    match secret.expose_secret().chars().count() {
+        0 => Err(garde::Error::new("must not be empty")),
+        1..=15 => Err(garde::Error::new("must be at least 16 characters long")),
+        _ => Ok(()),
+    }
+}
+
+/// The S3 settings are only used by the S3 backend.
+fn is_s3_ready(backend: StorageBackend) -> impl FnOnce(&S3, &()) -> garde::Result {
+    move |s3, _| {
+        if backend != StorageBackend::S3 {
+            return Ok(());
+        }
+        if s3.bucket.is_empty() {
+            return Err(garde::Error::new("bucket must not be empty"));
+        }
+        if s3.region.is_empty() && s3.endpoint.is_none() {
+            return Err(garde::Error::new("region or endpoint must be set"));
+        }
+        Ok(())
+    }
+}
+
+/// The Redis settings are only used by the Redis backend.
+fn is_redis_ready(backend: CacheBackend) -> impl FnOnce(&Redis, &()) -> garde::Result {
+    move |redis, _| {
+        if backend != CacheBackend::Redis {
+            return Ok(());
+        }
+        match redis.url.as_ref().map(|url| url.expose_secret().is_empty()) {
+            Some(false) => Ok(()),
+            _ => Err(garde::Error::new("url must be set")),
+        }
+    }
 }
@@ -0,0 +1,752 @@
+//! Fake users, categories, tags, articles, series and comments, for demos and
+//! load tests.
+//!
+//! Every value is drawn from a random generator seeded by the caller, so the
+//! same options give the same rows. The dates are spread over the two years
+//! before the current day, they only change from one day to the next.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use chrono::{Duration, Utc};
+use clap::ValueEnum;
+use fake::{
+    faker::{
+        job::raw::Field,
+        lorem::raw::{Paragraph, Sentence, Sentences, Word, Words},
+        name::raw::{FirstName, LastName},
+    },
+    locales::{Data, AR_SA, EN, FR_FR, JA_JP, PT_BR, ZH_CN, ZH_TW},
+    Fake,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use sea_orm::{
+    prelude::DateTimeUtc, DatabaseConnection, DatabaseTransaction, Set, TransactionTrait,
+};
+
+use crate::{
+    error::AppResult,
+    repos::{backup::reset_sequence, dataset},
+    utils::hash::generate_hash,
+};
+use entity::{
+    article as ArticleEntity, article_tag as ArticleTagEntity, category as CategoryEntity,
+    comment as CommentEntity, series as SeriesEntity, series_article as SeriesArticleEntity,
+    tag as TagEntity, user as UserEntity,
+};
+
+/// How far back the dates go.
+const DAYS: i64 = 730;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Locale {
+    En,
+    FrFr,
+    ZhCn,
+    ZhTw,
+    JaJp,
+    PtBr,
+    ArSa,
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub users: usize,
+    pub categories: usize,
+    pub tags: usize,
+    pub articles: usize,
+    pub series: usize,
+    pub comments: usize,
+    pub seed: u64,
+    /// Language of the names of the users, categories and tags, the texts
+    /// are lorem ipsum whatever the locale
+    pub locale: Locale,
+    /// Password of every user
+    pub password: String,
+    /// Rows inserted by a single statement
+    pub batch_size: usize,
+}
+
+/// Rows inserted by `generate`.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub users: usize,
+    pub categories: usize,
+    pub tags: usize,
+    pub articles: usize,
+    pub article_tags: usize,
+    pub series: usize,
+    pub chapters: usize,
+    pub comments: usize,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Users:      {}", self.users)?;
+        writeln!(f, "Categories: {}", self.categories)?;
+        writeln!(f, "Tags:       {}", self.tags)?;
+        writeln!(
+            f,
+            "Articles:   {} with {} tags",
+            self.articles, self.article_tags
+        )?;
+        writeln!(
+            f,
+            "Series:     {} with {} chapters",
+            self.series, self.chapters
+        )?;
+        writeln!(f, "Comments:   {}", self.comments)
+    }
+}
+
+/// Insert the dataset described by `options` next to the rows already in
+/// the database, in a single transaction.
+///
+/// Articles are spread over the authors and the categories, a few of them
+/// getting most, and about one in ten is a draft. Series are runs of the
+/// articles of their author in the order they were written. A comment
+/// answers another one of the same article about a third of the time.
+pub async fn generate(dbc: &DatabaseConnection, options: &Options) -> AppResult<Report> {
+    let tx = dbc.begin().await?;
+    let report = match options.locale {
+        Locale::En => Generator::new(&tx, options, EN).run().await?,
+        Locale::FrFr => Generator::new(&tx, options, FR_FR).run().await?,
+        Locale::ZhCn => Generator::new(&tx, options, ZH_CN).run().await?,
+        Locale::ZhTw => Generator::new(&tx, options, ZH_TW).run().await?,
+        Locale::JaJp => Generator::new(&tx, options, JA_JP).run().await?,
+        Locale::PtBr => Generator::new(&tx, options, PT_BR).run().await?,
+        Locale::ArSa => Generator::new(&tx, options, AR_SA).run().await?,
+    };
+    tx.commit().await?;
+
+    Ok(report)
+}
+
+/// What the series and the comments need to know of an article.
+struct Article {
+    id: i32,
+    user: usize,
+    published: bool,
+    created_at: DateTimeUtc,
+    updated_at: DateTimeUtc,
+}
+
+struct Generator<'a, L> {
+    tx: &'a DatabaseTransaction,
+    options: &'a Options,
+    locale: L,
+    rng: StdRng,
+    until: DateTimeUtc,
+    report: Report,
+}
+
+impl<'a, L: Data + Copy> Generator<'a, L> {
+    fn new(tx: &'a DatabaseTransaction, options: &'a Options, locale: L) -> Self {
+        let until = Utc::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc();
+
+        Self {
+            tx,
+            options,
+            locale,
+            rng: StdRng::seed_from_u64(options.seed),
+            until,
+            report: Report::default(),
+        }
+    }
+
+    async fn run(mut self) -> AppResult<Report> {
+        let users = self.users().await?;
+        let categories = self.categories().await?;
+        let tags = self.tags().await?;
+        if users.is_empty() || categories.is_empty() {
+            return Ok(self.report);
+        }
+
+        let articles = self.articles(&users, &categories, &tags).await?;
+        self.series(&users, &articles).await?;
+        self.comments(&users, &articles).await?;
+
+        reset_sequence::<UserEntity::Entity, _>(self.tx).await?;
+        reset_sequence::<CategoryEntity::Entity, _>(self.tx).await?;
+        reset_sequence::<TagEntity::Entity, _>(self.tx).await?;
+        reset_sequence::<ArticleEntity::Entity, _>(self.tx).await?;
+        reset_sequence::<ArticleTagEntity::Entity, _>(self.tx).await?;
+        reset_sequence::<SeriesEntity::Entity, _>(self.tx).await?;
+        reset_sequence::<SeriesArticleEntity::Entity, _>(self.tx).await?;
+        reset_sequence::<CommentEntity::Entity, _>(self.tx).await?;
+
+        Ok(self.report)
+    }
+
+    async fn users(&mut self) -> AppResult<Vec<i32>> {
+        let tx = self.tx;
+        let start = dataset::next_id::<UserEntity::Entity, _>(tx, UserEntity::Column::Id).await?;
+        let mut usernames =
+            dataset::read_names::<UserEntity::Entity, _>(tx, UserEntity::Column::Username).await?;
+        let mut emails =
+            dataset::read_names::<UserEntity::Entity, _>(tx, UserEntity::Column::Email).await?;
+        // Hashing is slow on purpose, every user shares the same hash.
+        let password_hash = generate_hash(&self.options.password)?;
+
+        let ids: Vec<i32> = (0..self.options.users).map(|i| start + i as i32).collect();
+        for chunk in ids.chunks(self.options.batch_size) {
+            let mut models = Vec::with_capacity(chunk.len());
+            for &id in chunk {
+                let first: String = FirstName(self.locale).fake_with_rng(&mut self.rng);
+                let last: String = LastName(self.locale).fake_with_rng(&mut self.rng);
+                let name: String = format!("{}{}", first, last)
+                    .to_lowercase()
+                    .chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .collect();
+                let username = unique(name, ".", &mut usernames);
+                let email = if username.is_ascii() {
+                    format!("{}@example.com", username)
+                } else {
+                    format!("user{}@example.com", id)
+                };
+                let created_at = self.since();
+
+                models.push(UserEntity::ActiveModel {
+                    id: Set(id),
+                    username: Set(username),
+                    email: Set(unique(email, "+", &mut emails)),
+                    password_hash: Set(password_hash.clone()),
+                    bio: Set(Some(self.sentence(6..16))),
+                    avatar: Set(None),
+                    created_at: Set(created_at),
+                    updated_at: Set(created_at),
+                    deleted_at: Set(None),
+                });
+            }
+            dataset::insert(tx, models).await?;
+        }
+
+        self.report.users = ids.len();
+        Ok(ids)
+    }
+
+    async fn categories(&mut self) -> AppResult<Vec<i32>> {
+        let tx = self.tx;
+        let start =
+            dataset::next_id::<CategoryEntity::Entity, _>(tx, CategoryEntity::Column::Id).await?;
+        let mut names =
+            dataset::read_names::<CategoryEntity::Entity, _>(tx, CategoryEntity::Column::Name)
+                .await?;
+
+        let ids: Vec<i32> = (0..self.options.categories)
+            .map(|i| start + i as i32)
+            .collect();
+        for chunk in ids.chunks(self.options.batch_size) {
+            let mut models = Vec::with_capacity(chunk.len());
+            for &id in chunk {
+                let name: String = Field(self.locale).fake_with_rng(&mut self.rng);
+                models.push(CategoryEntity::ActiveModel {
+                    id: Set(id),
+                    name: Set(unique(name, " ", &mut names)),
+                    status: Set(1),
+                    description: Set(Some(self.sentence(6..16))),
+                    created_at: Set(self.since()),
+                    updated_at: Set(self.since()),
+                    deleted_at: Set(None),
+                });
+            }
+            dataset::insert(tx, models).await?;
+        }
+
+        self.report.categories = ids.len();
+        Ok(ids)
+    }
+
+    async fn tags(&mut self) -> AppResult<Vec<i32>> {
+        let tx = self.tx;
+        let start = dataset::next_id::<TagEntity::Entity, _>(tx, TagEntity::Column::Id).await?;
+        let mut names =
+            dataset::read_names::<TagEntity::Entity, _>(tx, TagEntity::Column::Name).await?;
+
+        let ids: Vec<i32> = (0..self.options.tags).map(|i| start + i as i32).collect();
+        for chunk in ids.chunks(self.options.batch_size) {
+            let mut models = Vec::with_capacity(chunk.len());
+            for &id in chunk {
+                let name: String = Word(self.locale).fake_with_rng(&mut self.rng);
+                models.push(TagEntity::ActiveModel {
+                    id: Set(id),
+                    name: Set(unique(name, "-", &mut names)),
+                    r#type: Set(0),
+                    status: Set(1),
+                    description: Set(None),
+                    created_at: Set(self.since()),
+                    updated_at: Set(self.since()),
+                    deleted_at: Set(None),
+                });
+            }
+            dataset::insert(tx, models).await?;
+        }
+
+        self.report.tags = ids.len();
+        Ok(ids)
+    }
+
+    async fn articles(
+        &mut self,
+        users: &[i32],
+        categories: &[i32],
+        tags: &[i32],
+    ) -> AppResult<Vec<Article>> {
+        let tx = self.tx;
+        let start =
+            dataset::next_id::<ArticleEntity::Entity, _>(tx, ArticleEntity::Column::Id).await?;
+        let mut tag_id =
+            dataset::next_id::<ArticleTagEntity::Entity, _>(tx, ArticleTagEntity::Column::Id)
+                .await?;
+        let mut slugs = HashSet::new();
+        let mut articles = Vec::with_capacity(self.options.articles);
+
+        let ids: Vec<i32> = (0..self.options.articles)
+            .map(|i| start + i as i32)
+            .collect();
+        for chunk in ids.chunks(self.options.batch_size) {
+            let mut models = Vec::with_capacity(chunk.len());
+            let mut article_tags = Vec::new();
+            for &id in chunk {
+                let user = skewed(&mut self.rng, users.len());
+                let category = categories[skewed(&mut self.rng, categories.len())];
+                let title = self.sentence(3..9);
+                let slug: Vec<String> = title
+                    .split_whitespace()
+                    .map(|word| {
+                        word.chars()
+                            .filter(|c| c.is_alphanumeric())
+                            .collect::<String>()
+                            .to_lowercase()
+                    })
+                    .filter(|word| !word.is_empty())
+                    .collect();
+                let published = self.rng.gen_bool(0.9);
+                let span = Duration::days(DAYS).num_seconds();
+                let created_at = self.since() + Duration::seconds(self.rng.gen_range(0..span));
+                let updated_at = if self.rng.gen_bool(0.3) {
+                    self.date_after(created_at)
+                } else {
+                    created_at
+                };
+
+                let count = self.rng.gen_range(0..=5).min(tags.len());
+                let mut chosen = HashSet::new();
+                while chosen.len() < count {
+                    chosen.insert(tags[skewed(&mut self.rng, tags.len())]);
+                }
+                let mut chosen: Vec<i32> = chosen.into_iter().collect();
+                chosen.sort_unstable();
+                for tag in chosen {
+                    article_tags.push(ArticleTagEntity::ActiveModel {
+                        id: Set(tag_id),
+                        created_at: Set(created_at),
+                        updated_at: Set(created_at),
+                        deleted_at: Set(None),
+                        article_id: Set(id),
+                        tag_id: Set(tag),
+                    });
+                    tag_id += 1;
+                }
+
+                models.push(ArticleEntity::ActiveModel {
+                    id: Set(id),
+                    slug: Set(unique(slug.join("-"), "-", &mut slugs)),
+                    title: Set(title),
+                    cover: Set("".to_string()),
+                    content: Set(self.content()),
+                    summary: Set(self.sentence(10..24)),
+                    password_hash: Set("".to_string()),
+                    source: Set(0),
+                    source_url: Set("".to_string()),
+                    topping: Set(self.rng.gen_bool(0.01) as i16),
+                    status: Set(published as i16),
+                    created_at: Set(created_at),
+                    updated_at: Set(updated_at),
+                    deleted_at: Set(None),
+                    user_id: Set(users[user]),
+                    category_id: Set(category),
+                });
+                articles.push(Article {
+                    id,
+                    user,
+                    published,
+                    created_at,
+                    updated_at,
+                });
+            }
+
+            self.report.article_tags += article_tags.len();
+            dataset::insert(tx, models).await?;
+            for chunk in article_tags.chunks(self.options.batch_size) {
+                dataset::insert(tx, chunk.to_vec()).await?;
+            }
+        }
+
+        self.report.articles = articles.len();
+        Ok(articles)
+    }
+
+    async fn series(&mut self, users: &[i32], articles: &[Article]) -> AppResult<()> {
+        let tx = self.tx;
+        let start =
+            dataset::next_id::<SeriesEntity::Entity, _>(tx, SeriesEntity::Column::Id).await?;
+        let mut chapter_id =
+            dataset::next_id::<SeriesArticleEntity::Entity, _>(tx, SeriesArticleEntity::Column::Id)
+                .await?;
+        let mut names =
+            dataset::read_names::<SeriesEntity::Entity, _>(tx, SeriesEntity::Column::Name).await?;
+
+        // The articles of each author from the oldest, a series takes the
+        // next ones so that none is a chapter twice.
+        let mut by_user: Vec<Vec<&Article>> = vec![Vec::new(); users.len()];
+        for article in articles {
+            by_user[article.user].push(article);
+        }
+        for articles in &mut by_user {
+            articles.sort_by_key(|article| (article.created_at, article.id));
+        }
+        let mut next = vec![0; users.len()];
+
+        let mut models = Vec::new();
+        let mut chapters = Vec::new();
+        for i in 0..self.options.series {
+            let authors: Vec<usize> = (0..users.len())
+                .filter(|&user| by_user[user].len() - next[user] >= 2)
+                .collect();
+            if authors.is_empty() {
+                break;
+            }
+            let user = authors[self.rng.gen_range(0..authors.len())];
+            let left = by_user[user].len() - next[user];
+            let count = self.rng.gen_range(2..=left.min(8));
+            let run = &by_user[user][next[user]..next[user] + count];
+            next[user] += count;
+
+            let id = start + i as i32;
+            let name = self.sentence(2..5);
+            models.push(SeriesEntity::ActiveModel {
+                id: Set(id),
+                name: Set(unique(name, " ", &mut names)),
+                description: Set(Some(self.sentence(8..20))),
+                cover: Set("".to_string()),
+                status: Set(1),
+                nums: Set(count as i32),
+                r#type: Set(0),
+                published_at: Set(run[0].created_at),
+                created_at: Set(run[0].created_at),
+                updated_at: Set(run.iter().map(|a| a.updated_at).max().unwrap()),
+                user_id: Set(users[user]),
+            });
+            for (position, article) in run.iter().enumerate() {
+                chapters.push(SeriesArticleEntity::ActiveModel {
+                    id: Set(chapter_id),
+                    created_at: Set(article.created_at),
+                    updated_at: Set(article.created_at),
+                    series_id: Set(id),
+                    article_id: Set(article.id),
+                    position: Set(position as i32 + 1),
+                });
+                chapter_id += 1;
+            }
+        }
+
+        self.report.series = models.len();
+        self.report.chapters = chapters.len();
+        for chunk in models.chunks(self.options.batch_size) {
+            dataset::insert(tx, chunk.to_vec()).await?;
+        }
+        for chunk in chapters.chunks(self.options.batch_size) {
+            dataset::insert(tx, chunk.to_vec()).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn comments(&mut self, users: &[i32], articles: &[Article]) -> AppResult<()> {
+        let tx = self.tx;
+        let published: Vec<&Article> = articles.iter().filter(|a| a.published).collect();
+        if published.is_empty() {
+            return Ok(());
+        }
+        let start =
+            dataset::next_id::<CommentEntity::Entity, _>(tx, CommentEntity::Column::Id).await?;
+
+        // Id, top comment id and date of the comments of each article.
+        let mut threads: HashMap<i32, Vec<(i32, i32, DateTimeUtc)>> = HashMap::new();
+        let mut models = Vec::with_capacity(self.options.batch_size);
+        for i in 0..self.options.comments {
+            let id = start + i as i32;
+            let article = published[skewed(&mut self.rng, published.len())];
+            let thread = threads.entry(article.id).or_default();
+
+            let parent = if !thread.is_empty() && self.rng.gen_bool(0.35) {
+                Some(thread[self.rng.gen_range(0..thread.len())])
+            } else {
+                None
+            };
+            let (top, parent, after) = match parent {
+                Some((parent, 0, at)) => (parent, parent, at),
+                Some((parent, top, at)) => (top, parent, at),
+                None => (0, 0, article.created_at),
+            };
+            let created_at = self.date_after(after);
+            thread.push((id, top, created_at));
+
+            let content: Vec<String> = Sentences(self.locale, 1..4).fake_with_rng(&mut self.rng);
+            models.push(CommentEntity::ActiveModel {
+                id: Set(id),
+                content: Set(content.join(" ")),
+                top_comment_id: Set(top),
+                parent_comment_id: Set(parent),
+                created_at: Set(created_at),
+                updated_at: Set(created_at),
+                deleted_at: Set(None),
+                article_id: Set(article.id),
+                user_id: Set(users[self.rng.gen_range(0..users.len())]),
+            });
+            if models.len() == self.options.batch_size {
+                dataset::insert(tx, std::mem::take(&mut models)).await?;
+            }
+        }
+        dataset::insert(tx, models).await?;
+
+        self.report.comments = self.options.comments;
+        Ok(())
+    }
+
+    /// Markdown paragraphs, with a heading above some of them.
+    fn content(&mut self) -> String {
+        let count = self.rng.gen_range(3..10);
+        let mut blocks = Vec::with_capacity(count * 2);
+        for i in 0..count {
+            if i > 0 && self.rng.gen_bool(0.25) {
+                let words: Vec<String> = Words(self.locale, 2..5).fake_with_rng(&mut self.rng);
+                blocks.push(format!("## {}", capitalize(&words.join(" "))));
+            }
+            blocks.push(Paragraph(self.locale, 3..8).fake_with_rng(&mut self.rng));
+        }
+        blocks.join("\n\n")
+    }
+
+    /// A capitalized sentence of `words` words, without its final dot.
+    fn sentence(&mut self, words: std::ops::Range<usize>) -> String {
+        let sentence: String = Sentence(self.locale, words).fake_with_rng(&mut self.rng);
+        capitalize(sentence.trim_end_matches('.'))
+    }
+
+    /// Start of the dataset, the users, categories and tags are as old.
+    fn since(&self) -> DateTimeUtc {
+        self.until - Duration::days(DAYS)
+    }
+
+    /// A date between `after` and the end of the dataset, most of them in
+    /// the weeks following `after`.
+    fn date_after(&mut self, after: DateTimeUtc) -> DateTimeUtc {
+        let span = (self.until - after).num_seconds().max(0);
+        let weeks = Duration::weeks(4).num_seconds().min(span);
+        let seconds = if span > weeks && self.rng.gen_bool(0.5) {
+            self.rng.gen_range(0..=span)
+        } else {
+            self.rng.gen_range(0..=weeks)
+        };
+        after + Duration::seconds(seconds)
+    }
+}
+
+/// An index below `len`, the first ones drawn far more often than the last
+/// ones so that a few authors, categories and tags get most of the articles.
+fn skewed(rng: &mut StdRng, len: usize) -> usize {
+    let x: f64 = rng.gen();
+    ((x * x * len as f64) as usize).min(len - 1)
+}
+
+/// `name`, followed by a number when it is already taken.
+fn unique(name: String, separator: &str, taken: &mut HashSet<String>) -> String {
+    if !name.is_empty() && taken.insert(name.clone()) {
+        return name;
+    }
+
+    let stem = if name.is_empty() { "x" } else { &name };
+    (2..)
+        .map(|n| match stem.split_once('@') {
+            Some((local, domain)) => format!("{}{}{}@{}", local, separator, n, domain),
+            None => format!("{}{}{}", stem, separator, n),
+        })
+        .find(|candidate| taken.insert(candidate.clone()))
+        .unwrap()
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{Database, EntityTrait, QueryOrder};
+
+    use super::*;
+
+    fn options(seed: u64) -> Options {
+        Options {
+            users: 3,
+            categories: 2,
+            tags: 5,
+            articles: 24,
+            series: 3,
+            comments: 40,
+            seed,
+            locale: Locale::En,
+            password: "Pa$$wd123".to_string(),
+            batch_size: 7,
+        }
+    }
+
+    /// Rows of every table generated with `options` into a fresh database,
+    /// `times` in a row. Password hashes are salted, they are left out.
+    async fn dataset(options: &Options, times: usize) -> (String, serde_json::Value) {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!(
+            "sqlite://{}?mode=rwc",
+            dir.path().join("letters.db").display()
+        );
+        let dbc = Database::connect(url).await.unwrap();
+        Migrator::up(&dbc, None).await.unwrap();
+
+        let mut report = String::new();
+        for _ in 0..times {
+            report = generate(&dbc, options).await.unwrap().to_string();
+        }
+
+        let mut users = UserEntity::Entity::find()
+            .order_by_asc(UserEntity::Column::Id)
+            .all(&dbc)
+            .await
+            .unwrap();
+        users.iter_mut().for_each(|user| user.password_hash.clear());
+        let rows = serde_json::json!({
+            "users": users,
+            "categories": CategoryEntity::Entity::find()
+                .order_by_asc(CategoryEntity::Column::Id)
+                .all(&dbc)
+                .await
+                .unwrap(),
+            "tags": TagEntity::Entity::find()
+                .order_by_asc(TagEntity::Column::Id)
+                .all(&dbc)
+                .await
+                .unwrap(),
+            "articles": ArticleEntity::Entity::find()
+                .order_by_asc(ArticleEntity::Column::Id)
+                .all(&dbc)
+                .await
+                .unwrap(),
+            "article_tags": ArticleTagEntity::Entity::find()
+                .order_by_asc(ArticleTagEntity::Column::Id)
+                .all(&dbc)
+                .await
+                .unwrap(),
+            "series": SeriesEntity::Entity::find()
+                .order_by_asc(SeriesEntity::Column::Id)
+                .all(&dbc)
+                .await
+                .unwrap(),
+            "chapters": SeriesArticleEntity::Entity::find()
+                .order_by_asc(SeriesArticleEntity::Column::Id)
+                .all(&dbc)
+                .await
+                .unwrap(),
+            "comments": CommentEntity::Entity::find()
+                .order_by_asc(CommentEntity::Column::Id)
+                .all(&dbc)
+                .await
+                .unwrap(),
+        });
+        (report, rows)
+    }
+
+    #[tokio::test]
+    async fn the_same_seed_gives_the_same_rows() {
+        let (report, rows) = dataset(&options(42), 1).await;
+        let (again, same) = dataset(&options(42), 1).await;
+
+        assert_eq!(report, again);
+        assert_eq!(rows, same);
+        assert_eq!(rows["users"].as_array().unwrap().len(), 3);
+        assert_eq!(rows["articles"].as_array().unwrap().len(), 24);
+        assert_eq!(rows["comments"].as_array().unwrap().len(), 40);
+    }
+
+    #[tokio::test]
+    async fn another_seed_gives_other_rows() {
+        let (_, rows) = dataset(&options(42), 1).await;
+        let (_, other) = dataset(&options(7), 1).await;
+
+        assert_ne!(rows["articles"], other["articles"]);
+    }
+
+    #[tokio::test]
+    async fn a_second_run_goes_next_to_the_first_one() {
+        let (report, rows) = dataset(&options(42), 2).await;
+
+        assert_eq!(report, dataset(&options(42), 1).await.0);
+        let users = rows["users"].as_array().unwrap();
+        assert_eq!(users.len(), 6);
+        let names: HashSet<_> = users.iter().map(|user| &user["username"]).collect();
+        assert_eq!(names.len(), 6);
+        let emails: HashSet<_> = users.iter().map(|user| &user["email"]).collect();
+        assert_eq!(emails.len(), 6);
+        assert_eq!(rows["articles"].as_array().unwrap().len(), 48);
+    }
+
+    #[test]
+    fn taken_names_get_a_number() {
+        let mut taken = HashSet::new();
+
+        assert_eq!(unique("rust".to_string(), "-", &mut taken), "rust");
+        assert_eq!(unique("rust".to_string(), "-", &mut taken), "rust-2");
+        assert_eq!(unique("rust".to_string(), "-", &mut taken), "rust-3");
+        assert_eq!(unique(String::new(), "-", &mut taken), "x-2");
+        assert_eq!(
+            unique("a@example.com".to_string(), "+", &mut taken),
+            "a@example.com"
+        );
+        assert_eq!(
+            unique("a@example.com".to_string(), "+", &mut taken),
+            "a+2@example.com"
+        );
+    }
+
+    #[test]
+    fn skewed_indexes_favour_the_first_ones() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut counts = [0; 4];
+        for _ in 0..1000 {
+            counts[skewed(&mut rng, counts.len())] += 1;
+        }
+
+        // The first of four is drawn half of the time, every one is drawn.
+        assert!((450..550).contains(&counts[0]), "{:?}", counts);
+        assert!(counts[1..].iter().all(|&count| count > 100), "{:?}", counts);
+        assert_eq!(skewed(&mut rng, 1), 0);
+    }
+
+    #[test]
+    fn capitalize_the_first_letter() {
+        assert_eq!(capitalize("lorem ipsum"), "Lorem ipsum");
+        assert_eq!(capitalize("éte"), "Éte");
+        assert_eq!(capitalize(""), "");
+    }
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LogLevel {
+    /// Filter directives, e.g. `info,letters=debug,sqlx=warn`
+    pub level: String,
+}
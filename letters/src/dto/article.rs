@@ -1,11 +1,11 @@
 use fake::faker::lorem::en::Word;
 use fake::faker::lorem::zh_cn::{Paragraph, Sentence};
 use fake::Dummy;
-use sea_orm::FromQueryResult;
+use sea_orm::{prelude::DateTimeUtc, FromQueryResult};
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
-use super::{category::ArticleCategory, user::UserInfo};
+use super::{category::ArticleCategory, series::ArticleSeries, tag::ArticleTag, user::UserInfo};
 
 #[derive(Deserialize, IntoParams, ToSchema, Dummy)]
 pub struct ArticleRequest {
@@ -24,6 +24,12 @@ pub struct ArticleRequest {
     pub topping: Option<u8>,
     pub status: Option<u8>,
     pub category_id: Option<i32>,
+    #[dummy(default)]
+    pub tag_ids: Option<Vec<i32>>,
+    #[dummy(default)]
+    pub tag_names: Option<Vec<String>>,
+    #[dummy(default)]
+    pub series_ids: Option<Vec<i32>>,
 }
 
 #[derive(Deserialize, IntoParams, ToSchema)]
@@ -39,6 +45,9 @@ pub struct UpdateArticleRequest {
     pub topping: Option<u8>,
     pub status: Option<u8>,
     pub category_id: Option<i32>,
+    pub tag_ids: Option<Vec<i32>>,
+    pub tag_names: Option<Vec<String>>,
+    pub series_ids: Option<Vec<i32>>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -63,17 +72,46 @@ impl From<entity::article::Model> for PreviewArticleResponse {
             slug: value.slug,
             cover: value.cover,
             summary: value.summary,
-            source: Some(value.source),
-            topping: Some(value.topping),
-            status: Some(value.status),
+            source: Some(value.source as u8),
+            topping: Some(value.topping as u8),
+            status: Some(value.status as u8),
             category_id: value.category_id,
             user_id: value.user_id,
         }
     }
 }
 
+#[derive(Clone, Serialize, ToSchema)]
+pub struct RelatedArticleResponse {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub cover: String,
+    pub summary: String,
+    pub score: f32,
+}
+
+impl From<entity::article::Model> for RelatedArticleResponse {
+    fn from(value: entity::article::Model) -> Self {
+        Self {
+            id: value.id,
+            title: value.title,
+            slug: value.slug,
+            cover: value.cover,
+            summary: value.summary,
+            score: 0.0,
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct RelatedQueryParam {
+    pub limit: Option<usize>,
+}
+
 #[derive(Serialize, ToSchema)]
 pub struct ArticleResponse {
+    pub id: i32,
     pub title: String,
     pub cover: String,
     pub content: String,
@@ -83,35 +121,37 @@ pub struct ArticleResponse {
     pub status: Option<u8>,
     pub category: ArticleCategory,
     pub author: UserInfo,
-    pub tags: Vec<String>,
+    pub tags: Vec<ArticleTag>,
+    pub series: Vec<ArticleSeries>,
 }
 
 #[derive(Serialize, FromQueryResult, ToSchema)]
 pub struct ArticleForQuery {
+    pub id: i32,
     pub title: String,
     pub cover: String,
     pub content: String,
-    pub source: Option<u8>,
+    pub source: Option<i16>,
     pub source_url: Option<String>,
-    pub topping: Option<u8>,
-    pub status: Option<u8>,
+    pub topping: Option<i16>,
+    pub status: Option<i16>,
     pub author_id: i32,
     pub author_name: String,
     pub category_id: i32,
     pub category_name: String,
-    pub tag_names: Option<String>,
 }
 
-impl From<ArticleForQuery> for ArticleResponse {
-    fn from(value: ArticleForQuery) -> Self {
+impl From<(ArticleForQuery, Vec<ArticleTag>, Vec<ArticleSeries>)> for ArticleResponse {
+    fn from((value, tags, series): (ArticleForQuery, Vec<ArticleTag>, Vec<ArticleSeries>)) -> Self {
         Self {
+            id: value.id,
             title: value.title,
             cover: value.cover,
             content: value.content,
-            source: value.source,
+            source: value.source.map(|source| source as u8),
             source_url: value.source_url,
-            topping: value.topping,
-            status: value.status,
+            topping: value.topping.map(|topping| topping as u8),
+            status: value.status.map(|status| status as u8),
             author: UserInfo {
                 id: value.author_id,
                 username: value.author_name,
@@ -120,16 +160,32 @@ impl From<ArticleForQuery> for ArticleResponse {
                 id: value.category_id,
                 name: value.category_name,
             },
-            tags: match value.tag_names {
-                Some(names) => names.split(' ').map(|v| v.to_string()).collect(),
-                None => Vec::new(),
-            },
+            tags,
+            series,
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, FromQueryResult)]
 pub struct ArticleInfo {
     pub id: i32,
     pub title: String,
+    pub slug: String,
+}
+
+/// A published article with the names of its author and category.
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct PublishedArticle {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub cover: String,
+    pub summary: String,
+    pub content: String,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+    pub user_id: i32,
+    pub author_name: Option<String>,
+    pub category_id: i32,
+    pub category_name: Option<String>,
 }
@@ -0,0 +1,52 @@
+use serde::Deserialize;
+
+/// Which articles go into a feed.
+#[derive(Debug, Clone, Copy)]
+pub enum FeedScope {
+    All,
+    Category(i32),
+    Tag(i32),
+    Series(i32),
+    Author(i32),
+}
+
+impl FeedScope {
+    /// Path of the page the feed belongs to.
+    pub fn path(&self) -> String {
+        match self {
+            FeedScope::All => "".to_string(),
+            FeedScope::Category(id) => format!("/categories/{}", id),
+            FeedScope::Tag(id) => format!("/tags/{}", id),
+            FeedScope::Series(id) => format!("/series/{}", id),
+            FeedScope::Author(id) => format!("/authors/{}", id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum FeedFormat {
+    #[serde(rename = "feed.xml")]
+    Rss,
+    #[serde(rename = "atom.xml")]
+    Atom,
+    #[serde(rename = "feed.json")]
+    Json,
+}
+
+impl FeedFormat {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "feed.xml",
+            FeedFormat::Atom => "atom.xml",
+            FeedFormat::Json => "feed.json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+            FeedFormat::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use sea_orm::prelude::DateTimeUtc;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Serialize, ToSchema)]
+pub struct MediaResponse {
+    pub id: i32,
+    pub url: String,
+    pub file_name: String,
+    pub mime: String,
+    pub size: i64,
+    pub hash: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub blurhash: Option<String>,
+    pub variants: Vec<MediaVariantResponse>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MediaVariantResponse {
+    pub width: i32,
+    pub height: i32,
+    pub mime: String,
+    pub size: i64,
+    pub url: String,
+}
+
+impl From<(entity::media::Model, String, Vec<MediaVariantResponse>)> for MediaResponse {
+    fn from(
+        (value, url, variants): (entity::media::Model, String, Vec<MediaVariantResponse>),
+    ) -> Self {
+        Self {
+            id: value.id,
+            url,
+            file_name: value.file_name,
+            mime: value.mime,
+            size: value.size,
+            hash: value.hash,
+            width: value.width,
+            height: value.height,
+            blurhash: value.blurhash,
+            variants,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Webp,
+    Avif,
+}
+
+impl ImageFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpeg",
+            ImageFormat::Png => "png",
+            ImageFormat::Webp => "webp",
+            ImageFormat::Avif => "avif",
+        }
+    }
+
+    pub fn mime(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png => "image/png",
+            ImageFormat::Webp => "image/webp",
+            ImageFormat::Avif => "image/avif",
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ResizeQueryParam {
+    /// Width in pixels
+    pub w: u32,
+    pub f: ImageFormat,
+    /// Signature of the url
+    pub s: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct SignedUrlQueryParam {
+    pub width: u32,
+    pub format: ImageFormat,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SignedUrlResponse {
+    pub url: String,
+}
@@ -1,11 +1,17 @@
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
+pub mod admin;
 pub mod article;
 pub mod auth;
 pub mod category;
+pub mod feed;
+pub mod media;
+pub mod search;
+pub mod seo;
 pub mod series;
 pub mod tag;
+pub mod trash;
 pub mod user;
 
 #[derive(Debug, Deserialize, ToSchema, IntoParams)]
@@ -0,0 +1,40 @@
+use sea_orm::prelude::DateTimeUtc;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct SearchQueryParam {
+    pub q: String,
+    pub category_id: Option<i32>,
+    pub tag_id: Option<i32>,
+    pub author_id: Option<i32>,
+    pub from: Option<DateTimeUtc>,
+    pub to: Option<DateTimeUtc>,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SearchHit {
+    pub id: i32,
+    pub title: String,
+    pub summary: String,
+    pub score: f32,
+    pub title_highlight: String,
+    pub highlight: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SearchResponse {
+    pub total: usize,
+    pub page: u64,
+    pub per_page: u64,
+    pub hits: Vec<SearchHit>,
+}
+
+/// Query of the search page of the html frontend.
+#[derive(Debug, Deserialize)]
+pub struct SearchPageQueryParam {
+    pub q: Option<String>,
+    pub page: Option<u64>,
+}
@@ -0,0 +1,47 @@
+use sea_orm::prelude::DateTimeUtc;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// One `<url>` of the sitemap.
+#[derive(Debug)]
+pub struct SitemapEntry {
+    pub path: String,
+    pub lastmod: Option<DateTimeUtc>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SeoResponse {
+    pub canonical_url: String,
+    pub title: String,
+    pub description: String,
+    pub open_graph: OpenGraph,
+    pub twitter: TwitterCard,
+    /// schema.org `BlogPosting`, ready for a `application/ld+json` script tag
+    #[schema(value_type = Object)]
+    pub json_ld: serde_json::Value,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OpenGraph {
+    pub r#type: String,
+    pub title: String,
+    pub description: String,
+    pub url: String,
+    pub site_name: String,
+    pub locale: String,
+    pub image: Option<String>,
+    pub published_time: DateTimeUtc,
+    pub modified_time: DateTimeUtc,
+    pub author: Option<String>,
+    pub section: Option<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TwitterCard {
+    pub card: String,
+    pub title: String,
+    pub description: String,
+    pub image: Option<String>,
+    pub site: Option<String>,
+}
@@ -3,6 +3,8 @@ use fake::Dummy;
 use sea_orm::prelude::DateTimeUtc;
 use serde::{Deserialize, Serialize};
 
+use super::article::ArticleInfo;
+
 #[derive(Debug, Deserialize, Dummy)]
 pub struct SeriesRequest {
     #[dummy(faker = "Sentence(8..32)")]
@@ -11,7 +13,6 @@ pub struct SeriesRequest {
     pub description: Option<String>,
     pub cover: Option<String>,
     pub status: u8,
-    pub nums: u32,
     pub r#type: u8,
 }
 
@@ -21,10 +22,20 @@ pub struct UpdateSeriesRequest {
     pub description: Option<String>,
     pub cover: Option<String>,
     pub status: Option<u8>,
-    pub nums: Option<u32>,
     pub r#type: Option<u8>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct AddSeriesArticleRequest {
+    pub article_id: i32,
+    pub position: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReorderSeriesArticlesRequest {
+    pub article_ids: Vec<i32>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct SeriesResponse {
     pub name: String,
@@ -39,15 +50,25 @@ pub struct SeriesResponse {
     pub user_id: i32,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ArticleSeries {
+    pub id: i32,
+    pub name: String,
+    pub nums: u32,
+    pub position: u32,
+    pub prev: Option<ArticleInfo>,
+    pub next: Option<ArticleInfo>,
+}
+
 impl From<entity::series::Model> for SeriesResponse {
     fn from(value: entity::series::Model) -> Self {
         Self {
             name: value.name,
             description: value.description,
             cover: Some(value.cover),
-            status: value.status,
-            nums: value.nums,
-            r#type: value.r#type,
+            status: value.status as u8,
+            nums: value.nums as u32,
+            r#type: value.r#type as u8,
             published_at: value.published_at,
             created_at: value.created_at,
             updated_at: value.updated_at,
@@ -31,7 +31,7 @@ pub struct TagResponse {
     pub updated_at: DateTimeUtc,
 }
 
-#[derive(Serialize, FromQueryResult)]
+#[derive(Clone, Serialize, FromQueryResult)]
 pub struct ArticleTag {
     pub id: i32,
     pub name: String,
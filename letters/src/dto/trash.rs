@@ -0,0 +1,67 @@
+use sea_orm::prelude::DateTimeUtc;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TrashResource {
+    Articles,
+    Categories,
+    Tags,
+    Users,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TrashItem {
+    pub id: i32,
+    pub name: String,
+    pub deleted_at: Option<DateTimeUtc>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TrashResponse {
+    pub articles: Vec<TrashItem>,
+    pub categories: Vec<TrashItem>,
+    pub tags: Vec<TrashItem>,
+    pub users: Vec<TrashItem>,
+}
+
+impl From<entity::article::Model> for TrashItem {
+    fn from(value: entity::article::Model) -> Self {
+        Self {
+            id: value.id,
+            name: value.title,
+            deleted_at: value.deleted_at,
+        }
+    }
+}
+
+impl From<entity::category::Model> for TrashItem {
+    fn from(value: entity::category::Model) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            deleted_at: value.deleted_at,
+        }
+    }
+}
+
+impl From<entity::tag::Model> for TrashItem {
+    fn from(value: entity::tag::Model) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            deleted_at: value.deleted_at,
+        }
+    }
+}
+
+impl From<entity::user::Model> for TrashItem {
+    fn from(value: entity::user::Model) -> Self {
+        Self {
+            id: value.id,
+            name: value.username,
+            deleted_at: value.deleted_at,
+        }
+    }
+}
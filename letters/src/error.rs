@@ -9,6 +9,8 @@ use serde::Serialize;
 use strum::EnumString;
 use utoipa::ToSchema;
 
+use crate::telemetry;
+
 pub type AppResult<T = ()> = std::result::Result<T, AppError>;
 
 #[derive(thiserror::Error, ToSchema)]
@@ -26,6 +28,18 @@ pub enum AppError {
     #[error("{0}")]
     InvalidInput(String),
     #[error(transparent)]
+    Search(#[from] tantivy::TantivyError),
+    #[error(transparent)]
+    Storage(#[from] object_store::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Template(#[from] minijinja::Error),
+    #[error("{0}")]
+    PayloadTooLarge(String),
+    #[error("{0}")]
+    UnsupportedMediaType(String),
+    #[error(transparent)]
     TypeHeader(#[from] axum_extra::typed_header::TypedHeaderRejection),
     #[error(transparent)]
     Unexpected(#[from] anyhow::Error),
@@ -72,6 +86,42 @@ impl AppError {
                     String::from("Hash error."),
                 ),
             ),
+            AppError::Search(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorResponse::new(
+                    "".to_string(),
+                    self.to_string(),
+                    String::from("Search error."),
+                ),
+            ),
+            AppError::Storage(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorResponse::new(
+                    "".to_string(),
+                    self.to_string(),
+                    String::from("Storage error."),
+                ),
+            ),
+            AppError::Io(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorResponse::new("".to_string(), self.to_string(), String::from("IO error.")),
+            ),
+            AppError::Template(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorResponse::new(
+                    "".to_string(),
+                    self.to_string(),
+                    String::from("Template error."),
+                ),
+            ),
+            AppError::PayloadTooLarge(_) => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                ErrorResponse::new("".to_string(), self.to_string(), self.to_string()),
+            ),
+            AppError::UnsupportedMediaType(_) => (
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                ErrorResponse::new("".to_string(), self.to_string(), self.to_string()),
+            ),
             AppError::InvalidInput(_) => (
                 StatusCode::BAD_REQUEST,
                 ErrorResponse::new("".to_string(), self.to_string(), self.to_string()),
@@ -123,6 +173,9 @@ pub struct ErrorResponse {
     pub code: String,
     pub error: String,
     pub message: String,
+    /// Id of the request, to find it in the logs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl ErrorResponse {
@@ -131,6 +184,7 @@ impl ErrorResponse {
             code,
             error,
             message,
+            request_id: telemetry::request_id(),
         }
     }
 }
@@ -156,4 +210,5 @@ pub enum ResourceType {
     Article,
     Series,
     Comment,
+    Media,
 }
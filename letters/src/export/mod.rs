@@ -0,0 +1,210 @@
+//! Articles as markdown files with a YAML front matter, zipped together with
+//! the media they link to. `import::markdown` reads the files back.
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fs::File,
+    io::{self, Seek, Write},
+};
+
+use axum::{
+    body::Body,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use sea_orm::{prelude::DateTimeUtc, DatabaseConnection};
+use serde::Serialize;
+use tokio_util::io::ReaderStream;
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    error::AppResult,
+    media::Storage,
+    repos::{article, category, series},
+};
+use entity::article as ArticleEntity;
+
+/// Articles read from the database at once.
+const PAGE_SIZE: u64 = 100;
+
+/// Front matter of an exported article, with the keys `import::markdown`
+/// looks for.
+#[derive(Debug, Serialize)]
+struct FrontMatter<'a> {
+    title: &'a str,
+    slug: &'a str,
+    date: DateTimeUtc,
+    lastmod: DateTimeUtc,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    summary: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    cover: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    series: Vec<&'a str>,
+    /// Position in the exported series, or in the only series of the article
+    #[serde(skip_serializing_if = "Option::is_none")]
+    series_position: Option<i32>,
+    draft: bool,
+}
+
+/// Write the articles of `user_id`, or only its chapters of `series_id`, into
+/// a zip in a temporary file and hand the file back, rewound.
+///
+/// Articles are read page by page and written as they come. The media are
+/// stored under `media/` with the path they have in the storage, the links
+/// of the articles are left as they are.
+pub async fn write(
+    dbc: &DatabaseConnection,
+    storage: &dyn Storage,
+    user_id: i32,
+    series_id: Option<i32>,
+) -> AppResult<File> {
+    let mut zip = ZipWriter::new(tempfile::tempfile()?);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    // Without a public url every slash would look like a link to a media.
+    let media_prefix = Some(storage.url("")).filter(|url| !url.trim_end_matches('/').is_empty());
+    let mut media_keys = BTreeSet::new();
+    let mut categories: HashMap<i32, String> = HashMap::new();
+    let mut names = HashSet::new();
+
+    let mut page = 0;
+    loop {
+        let articles = article::read_by_author(dbc, user_id, series_id, page, PAGE_SIZE).await?;
+        if articles.is_empty() {
+            break;
+        }
+        page += 1;
+
+        let ids: Vec<i32> = articles.iter().map(|article| article.id).collect();
+        let mut tags = article::read_tags_by_articles(dbc, &ids).await?;
+        let mut chapters = series::read_chapters_by_articles(dbc, &ids).await?;
+        let missing: Vec<i32> = articles
+            .iter()
+            .map(|article| article.category_id)
+            .filter(|id| !categories.contains_key(id))
+            .collect();
+        if !missing.is_empty() {
+            for model in category::read_by_ids(dbc, &missing).await? {
+                categories.insert(model.id, model.name);
+            }
+        }
+
+        for model in &articles {
+            let tags = tags.remove(&model.id).unwrap_or_default();
+            let chapters = chapters.remove(&model.id).unwrap_or_default();
+            let position = match series_id {
+                Some(series_id) => chapters
+                    .iter()
+                    .find(|(id, _, _)| *id == series_id)
+                    .map(|(_, _, position)| *position),
+                None if chapters.len() == 1 => Some(chapters[0].2),
+                None => None,
+            };
+            let front_matter = FrontMatter {
+                title: &model.title,
+                slug: &model.slug,
+                date: model.created_at,
+                lastmod: model.updated_at,
+                summary: &model.summary,
+                cover: &model.cover,
+                category: categories.get(&model.category_id).map(String::as_str),
+                tags: tags.iter().map(|tag| tag.name.as_str()).collect(),
+                series: chapters.iter().map(|(_, name, _)| name.as_str()).collect(),
+                series_position: position,
+                draft: model.status != 1,
+            };
+
+            let name = file_name(model, series_id.and(position), &mut names);
+            zip.start_file(name, options).map_err(io::Error::from)?;
+            zip.write_all(render(&front_matter, &model.content)?.as_bytes())?;
+
+            if let Some(prefix) = &media_prefix {
+                for text in [&model.content, &model.cover] {
+                    media_keys.extend(media_links(text, prefix));
+                }
+            }
+        }
+    }
+
+    for key in media_keys {
+        let Some(data) = storage.get(&key).await? else {
+            continue;
+        };
+        zip.start_file(format!("media/{}", key), options)
+            .map_err(io::Error::from)?;
+        zip.write_all(&data)?;
+    }
+
+    let mut file = zip.finish().map_err(io::Error::from)?;
+    file.rewind()?;
+    Ok(file)
+}
+
+/// Stream a zip written by `write` as a download named `file_name`, the
+/// temporary file goes away with the response.
+pub fn respond(file: File, file_name: &str) -> Response {
+    let body = Body::from_stream(ReaderStream::new(tokio::fs::File::from_std(file)));
+    (
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+fn render(front_matter: &FrontMatter, content: &str) -> AppResult<String> {
+    let yaml = serde_yaml::to_string(front_matter).map_err(anyhow::Error::from)?;
+    Ok(format!("---\n{}---\n\n{}\n", yaml, content.trim_end()))
+}
+
+/// `{slug}.md`, prefixed by the position in the series when there is one.
+/// Slugs which can not be used as they are fall back to the article id.
+fn file_name(
+    model: &ArticleEntity::Model,
+    position: Option<i32>,
+    names: &mut HashSet<String>,
+) -> String {
+    let usable = !model.slug.is_empty()
+        && !model.slug.starts_with('.')
+        && !model.slug.contains(['/', '\\', '\0']);
+    let stem = if usable {
+        model.slug.clone()
+    } else {
+        format!("article-{}", model.id)
+    };
+    let stem = match position {
+        Some(position) => format!("{:03}-{}", position, stem),
+        None => stem,
+    };
+
+    let mut name = format!("{}.md", stem);
+    if !names.insert(name.clone()) {
+        name = format!("{}-{}.md", stem, model.id);
+        names.insert(name.clone());
+    }
+    name
+}
+
+/// Storage keys of the media `text` links to, found by the public url of
+/// the storage.
+fn media_links<'a>(text: &'a str, prefix: &'a str) -> impl Iterator<Item = String> + 'a {
+    text.match_indices(prefix).filter_map(move |(start, _)| {
+        let rest = &text[start + prefix.len()..];
+        let end = rest
+            .find(|c: char| c.is_whitespace() || "()[]<>\"'?#".contains(c))
+            .unwrap_or(rest.len());
+        let key = &rest[..end];
+        (!key.is_empty() && !key.split('/').any(|part| part.is_empty() || part == ".."))
+            .then(|| key.to_string())
+    })
+}
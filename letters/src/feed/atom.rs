@@ -0,0 +1,76 @@
+use atom_syndication::{
+    CategoryBuilder, ContentBuilder, EntryBuilder, FeedBuilder, LinkBuilder, PersonBuilder, Text,
+};
+use chrono::Utc;
+
+use super::Channel;
+
+pub fn render(channel: &Channel) -> String {
+    let entries: Vec<atom_syndication::Entry> = channel
+        .entries
+        .iter()
+        .map(|entry| {
+            EntryBuilder::default()
+                .id(entry.url.clone())
+                .title(Text::plain(entry.title.clone()))
+                .link(
+                    LinkBuilder::default()
+                        .href(entry.url.clone())
+                        .rel("alternate")
+                        .mime_type(Some("text/html".to_string()))
+                        .build(),
+                )
+                .summary(Some(Text::plain(entry.summary.clone())))
+                .content(entry.content_html.as_ref().map(|html| {
+                    ContentBuilder::default()
+                        .value(Some(html.clone()))
+                        .content_type(Some("html".to_string()))
+                        .build()
+                }))
+                .authors(
+                    entry
+                        .author
+                        .iter()
+                        .map(|name| PersonBuilder::default().name(name.clone()).build())
+                        .collect::<Vec<_>>(),
+                )
+                .categories(
+                    entry
+                        .categories
+                        .iter()
+                        .map(|term| CategoryBuilder::default().term(term.clone()).build())
+                        .collect::<Vec<_>>(),
+                )
+                .published(Some(entry.published.fixed_offset()))
+                .updated(entry.updated.fixed_offset())
+                .build()
+        })
+        .collect();
+
+    FeedBuilder::default()
+        .id(channel.feed_url.clone())
+        .title(Text::plain(channel.title.clone()))
+        .subtitle(
+            (!channel.description.is_empty()).then(|| Text::plain(channel.description.clone())),
+        )
+        .lang(Some(channel.language.clone()))
+        .link(
+            LinkBuilder::default()
+                .href(channel.home_url.clone())
+                .rel("alternate")
+                .mime_type(Some("text/html".to_string()))
+                .build(),
+        )
+        .link(
+            LinkBuilder::default()
+                .href(channel.feed_url.clone())
+                .rel("self")
+                .mime_type(Some("application/atom+xml".to_string()))
+                .build(),
+        )
+        // Atom requires the date even when there is no entry.
+        .updated(channel.updated.unwrap_or_else(Utc::now).fixed_offset())
+        .entries(entries)
+        .build()
+        .to_string()
+}
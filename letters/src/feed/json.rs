@@ -0,0 +1,76 @@
+use sea_orm::prelude::DateTimeUtc;
+use serde::Serialize;
+
+use super::Channel;
+
+/// JSON Feed 1.1, https://www.jsonfeed.org/version/1.1/
+#[derive(Serialize)]
+struct JsonFeed<'a> {
+    version: &'static str,
+    title: &'a str,
+    home_page_url: &'a str,
+    feed_url: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    description: &'a str,
+    language: &'a str,
+    items: Vec<JsonFeedItem<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedItem<'a> {
+    id: &'a str,
+    url: &'a str,
+    title: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_html: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_text: Option<&'a str>,
+    summary: &'a str,
+    date_published: DateTimeUtc,
+    date_modified: DateTimeUtc,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    authors: Vec<JsonFeedAuthor<'a>>,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    tags: &'a [String],
+}
+
+#[derive(Serialize)]
+struct JsonFeedAuthor<'a> {
+    name: &'a str,
+}
+
+pub fn render(channel: &Channel) -> String {
+    let feed = JsonFeed {
+        version: "https://jsonfeed.org/version/1.1",
+        title: &channel.title,
+        home_page_url: &channel.home_url,
+        feed_url: &channel.feed_url,
+        description: &channel.description,
+        language: &channel.language,
+        items: channel
+            .entries
+            .iter()
+            .map(|entry| JsonFeedItem {
+                id: &entry.url,
+                url: &entry.url,
+                title: &entry.title,
+                content_html: entry.content_html.as_deref(),
+                content_text: entry
+                    .content_html
+                    .is_none()
+                    .then_some(entry.summary.as_str()),
+                summary: &entry.summary,
+                date_published: entry.published,
+                date_modified: entry.updated,
+                authors: entry
+                    .author
+                    .iter()
+                    .map(|name| JsonFeedAuthor { name })
+                    .collect(),
+                tags: &entry.categories,
+            })
+            .collect(),
+    };
+
+    serde_json::to_string(&feed).unwrap_or_default()
+}
@@ -0,0 +1,225 @@
+mod atom;
+mod json;
+mod rss;
+
+use sea_orm::prelude::DateTimeUtc;
+
+use crate::{
+    conf::{Feed as FeedConf, Site},
+    dto::{article::PublishedArticle, feed::FeedFormat, tag::ArticleTag},
+    utils::markdown,
+};
+
+/// Everything needed to render a feed, independent of its format.
+pub struct Channel {
+    pub title: String,
+    pub description: String,
+    pub language: String,
+    /// Page the feed belongs to
+    pub home_url: String,
+    /// Url the feed itself is served from
+    pub feed_url: String,
+    /// Latest change of the entries, none for an empty feed
+    pub updated: Option<DateTimeUtc>,
+    pub entries: Vec<Entry>,
+}
+
+pub struct Entry {
+    pub url: String,
+    pub title: String,
+    pub summary: String,
+    /// Html of the whole article, only set when feeds carry full content
+    pub content_html: Option<String>,
+    pub author: Option<String>,
+    pub categories: Vec<String>,
+    pub published: DateTimeUtc,
+    pub updated: DateTimeUtc,
+}
+
+impl Channel {
+    pub fn new(
+        site: &Site,
+        conf: &FeedConf,
+        title: String,
+        home_path: &str,
+        feed_url: String,
+        articles: Vec<(PublishedArticle, Vec<ArticleTag>)>,
+    ) -> Self {
+        let entries: Vec<Entry> = articles
+            .into_iter()
+            .map(|(article, tags)| Entry {
+                url: site.article_url(&article.slug),
+                title: article.title,
+                summary: article.summary,
+                content_html: conf
+                    .full_content
+                    .then(|| markdown::to_html(&article.content)),
+                author: article.author_name,
+                categories: article
+                    .category_name
+                    .into_iter()
+                    .chain(tags.into_iter().map(|tag| tag.name))
+                    .collect(),
+                published: article.created_at,
+                updated: article.updated_at,
+            })
+            .collect();
+
+        Self {
+            title,
+            description: site.description.clone(),
+            language: site.language.clone(),
+            home_url: site.url(if home_path.is_empty() { "/" } else { home_path }),
+            feed_url,
+            updated: entries.iter().map(|entry| entry.updated).max(),
+            entries,
+        }
+    }
+
+    pub fn render(&self, format: FeedFormat) -> String {
+        match format {
+            FeedFormat::Rss => rss::render(self),
+            FeedFormat::Atom => atom::render(self),
+            FeedFormat::Json => json::render(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn article(id: i32, title: &str, day: u32) -> PublishedArticle {
+        PublishedArticle {
+            id,
+            title: title.to_string(),
+            slug: title.to_lowercase(),
+            cover: String::new(),
+            summary: format!("About {}", title),
+            content: format!("# {}\n\nSome **content**", title),
+            created_at: Utc.with_ymd_and_hms(2024, 5, day, 8, 0, 0).unwrap(),
+            updated_at: Utc.with_ymd_and_hms(2024, 5, day + 1, 8, 0, 0).unwrap(),
+            user_id: 1,
+            author_name: Some("alice".to_string()),
+            category_id: 1,
+            category_name: Some("rust".to_string()),
+        }
+    }
+
+    fn channel(full_content: bool, articles: Vec<PublishedArticle>) -> Channel {
+        let site = Site {
+            base_url: "https://example.com".to_string(),
+            title: "Letters".to_string(),
+            description: "A blog".to_string(),
+            language: "en".to_string(),
+        };
+        let conf = FeedConf {
+            full_content,
+            limit: 20,
+        };
+        let tags = vec![ArticleTag {
+            id: 1,
+            name: "async".to_string(),
+        }];
+        Channel::new(
+            &site,
+            &conf,
+            "Letters".to_string(),
+            "",
+            site.url("/feed.xml"),
+            articles
+                .into_iter()
+                .map(|article| (article, tags.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn channel_is_updated_with_its_latest_entry() {
+        let channel = channel(
+            false,
+            vec![article(1, "Pinning", 3), article(2, "Traits", 1)],
+        );
+
+        assert_eq!(channel.home_url, "https://example.com/");
+        assert_eq!(
+            channel.entries[0].url,
+            "https://example.com/articles/pinning"
+        );
+        assert_eq!(channel.entries[0].categories, ["rust", "async"]);
+        assert_eq!(
+            channel.updated,
+            Some(Utc.with_ymd_and_hms(2024, 5, 4, 8, 0, 0).unwrap())
+        );
+        assert!(channel.entries[0].content_html.is_none());
+    }
+
+    #[test]
+    fn an_empty_channel_has_no_date() {
+        assert_eq!(channel(false, vec![]).updated, None);
+    }
+
+    #[test]
+    fn full_content_is_rendered_to_html() {
+        let channel = channel(true, vec![article(1, "Pinning", 3)]);
+
+        let html = channel.entries[0].content_html.as_deref().unwrap();
+        assert!(html.contains("<strong>content</strong>"));
+    }
+
+    #[test]
+    fn render_rss() {
+        let body = channel(false, vec![article(1, "Pinning", 3)]).render(FeedFormat::Rss);
+
+        let rss = ::rss::Channel::read_from(body.as_bytes()).unwrap();
+        assert_eq!(rss.title(), "Letters");
+        assert_eq!(rss.link(), "https://example.com/");
+        assert_eq!(
+            rss.last_build_date(),
+            Some("Sat, 4 May 2024 08:00:00 +0000")
+        );
+        let item = &rss.items()[0];
+        assert_eq!(item.title(), Some("Pinning"));
+        assert_eq!(item.description(), Some("About Pinning"));
+        assert!(item.guid().unwrap().is_permalink());
+        assert_eq!(item.categories().len(), 2);
+        assert_eq!(item.dublin_core_ext().unwrap().creators(), ["alice"]);
+    }
+
+    #[test]
+    fn render_atom() {
+        let body = channel(true, vec![article(1, "Pinning", 3)]).render(FeedFormat::Atom);
+
+        let feed = atom_syndication::Feed::read_from(body.as_bytes()).unwrap();
+        assert_eq!(feed.title().as_str(), "Letters");
+        assert_eq!(feed.id(), "https://example.com/feed.xml");
+        assert_eq!(feed.updated().to_rfc3339(), "2024-05-04T08:00:00+00:00");
+        let entry = &feed.entries()[0];
+        assert_eq!(entry.title().as_str(), "Pinning");
+        assert_eq!(entry.authors()[0].name(), "alice");
+        assert!(entry
+            .content()
+            .unwrap()
+            .value()
+            .unwrap()
+            .contains("<strong>content</strong>"));
+    }
+
+    #[test]
+    fn render_json_feed() {
+        let body = channel(false, vec![article(1, "Pinning", 3)]).render(FeedFormat::Json);
+
+        let feed: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(feed["version"], "https://jsonfeed.org/version/1.1");
+        assert_eq!(feed["feed_url"], "https://example.com/feed.xml");
+        let item = &feed["items"][0];
+        assert_eq!(item["id"], "https://example.com/articles/pinning");
+        assert_eq!(item["content_text"], "About Pinning");
+        assert!(item.get("content_html").is_none());
+        assert_eq!(item["date_modified"], "2024-05-04T08:00:00Z");
+        assert_eq!(item["authors"][0]["name"], "alice");
+        assert_eq!(item["tags"], serde_json::json!(["rust", "async"]));
+    }
+}
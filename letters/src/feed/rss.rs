@@ -0,0 +1,57 @@
+use rss::{
+    extension::{atom::AtomExtension, dublincore::DublinCoreExtension},
+    CategoryBuilder, ChannelBuilder, GuidBuilder, ItemBuilder,
+};
+
+use super::Channel;
+
+pub fn render(channel: &Channel) -> String {
+    let items: Vec<rss::Item> = channel
+        .entries
+        .iter()
+        .map(|entry| {
+            ItemBuilder::default()
+                .title(Some(entry.title.clone()))
+                .link(Some(entry.url.clone()))
+                .guid(Some(
+                    GuidBuilder::default()
+                        .value(entry.url.clone())
+                        .permalink(true)
+                        .build(),
+                ))
+                .description(Some(entry.summary.clone()))
+                .content(entry.content_html.clone())
+                .pub_date(Some(entry.published.to_rfc2822()))
+                .categories(
+                    entry
+                        .categories
+                        .iter()
+                        .map(|name| CategoryBuilder::default().name(name.clone()).build())
+                        .collect::<Vec<_>>(),
+                )
+                .dublin_core_ext(entry.author.as_ref().map(|author| DublinCoreExtension {
+                    creators: vec![author.clone()],
+                    ..Default::default()
+                }))
+                .build()
+        })
+        .collect();
+
+    ChannelBuilder::default()
+        .title(channel.title.clone())
+        .link(channel.home_url.clone())
+        .description(channel.description.clone())
+        .language(Some(channel.language.clone()))
+        .last_build_date(channel.updated.map(|updated| updated.to_rfc2822()))
+        .atom_ext(Some(AtomExtension {
+            links: vec![rss::extension::atom::Link {
+                href: channel.feed_url.clone(),
+                rel: "self".to_string(),
+                mime_type: Some("application/rss+xml".to_string()),
+                ..Default::default()
+            }],
+        }))
+        .items(items)
+        .build()
+        .to_string()
+}
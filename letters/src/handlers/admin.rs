@@ -0,0 +1,42 @@
+use crate::{dto::admin::LogLevel, error::AppResult, log, utils::jwt::AuthClaims};
+use axum::Json;
+
+/// Get the log level in use.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/log-level",
+    responses(
+        (status = 200, description = "Success get log level", body = [LogLevel]),
+        (status = 401, description = "Unauthorized user", body = [AppError]),
+        (status = 500, description = "Internal server error", body = [AppError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn get_log_level(_claims: AuthClaims) -> AppResult<Json<LogLevel>> {
+    Ok(Json(LogLevel {
+        level: log::level()?,
+    }))
+}
+
+/// Change the log level until the server restarts.
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/log-level",
+    request_body = LogLevel,
+    responses(
+        (status = 200, description = "Success change log level", body = [LogLevel]),
+        (status = 400, description = "Invalid filter directives", body = [AppError]),
+        (status = 401, description = "Unauthorized user", body = [AppError]),
+        (status = 500, description = "Internal server error", body = [AppError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn set_log_level(
+    _claims: AuthClaims,
+    Json(req): Json<LogLevel>,
+) -> AppResult<Json<LogLevel>> {
+    log::set_level(&req.level)?;
+    Ok(Json(LogLevel {
+        level: log::level()?,
+    }))
+}
@@ -1,16 +1,22 @@
 use crate::{
     app::AppState,
+    cache,
     dto::{
-        article::{ArticleRequest, ArticleResponse, PreviewArticleResponse, UpdateArticleRequest},
+        article::{
+            ArticleRequest, ArticleResponse, PreviewArticleResponse, RelatedArticleResponse,
+            RelatedQueryParam, UpdateArticleRequest,
+        },
         PageQueryParam,
     },
     error::{AppError, AppResult, Resource, ResourceType},
-    repos::article,
+    export,
+    repos::{article, series},
     utils::jwt::AuthClaims,
 };
 use axum::{
     extract::{Path, Query, State},
-    Json,
+    response::Response,
+    Extension, Json,
 };
 use std::sync::Arc;
 
@@ -32,7 +38,11 @@ pub async fn create_article(
     State(state): State<Arc<AppState>>,
     Json(req): Json<ArticleRequest>,
 ) -> AppResult<Json<()>> {
-    article::create(&state.dbc, claims.user_id, &req).await?;
+    let article_id = article::create(&state.dbc, claims.user_id, &req).await?;
+    if req.status == Some(1) {
+        state.metrics.article_published();
+    }
+    state.article_changed(article_id).await;
     Ok(Json(()))
 }
 
@@ -42,7 +52,15 @@ pub async fn update_article(
     Path(article_id): Path<i32>,
     Json(req): Json<UpdateArticleRequest>,
 ) -> AppResult<Json<()>> {
+    let was_published = article::read_model_by_id(&state.dbc, article_id)
+        .await?
+        .is_some_and(|model| model.status == 1);
+    state.article_changing(article_id).await;
     article::update(&state.dbc, article_id, &req).await?;
+    if !was_published && req.status == Some(1) {
+        state.metrics.article_published();
+    }
+    state.article_changed(article_id).await;
     Ok(Json(()))
 }
 
@@ -72,6 +90,25 @@ pub async fn get_articles(
     Ok(Json(resp))
 }
 
+/// Download the articles of the current user as markdown files
+#[utoipa::path(
+    get,
+    path = "/api/v1/articles/export",
+    responses(
+        (status = 200, description = "Zip of the articles and of the media they link to", content_type = "application/zip", body = Vec<u8>),
+        (status = 401, description = "Unauthorized user", body = [AppError]),
+        (status = 500, description = "Internal server error", body = [AppError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn export_articles(
+    claims: AuthClaims,
+    State(state): State<Arc<AppState>>,
+) -> AppResult<Response> {
+    let file = export::write(&state.dbc, state.storage.as_ref(), claims.user_id, None).await?;
+    Ok(export::respond(file, "articles.zip"))
+}
+
 // Get article by id
 #[utoipa::path(
     get,
@@ -86,7 +123,7 @@ pub async fn get_articles(
 pub async fn get_article_by_id(
     State(state): State<Arc<AppState>>,
     Path(article_id): Path<i32>,
-) -> AppResult<Json<ArticleResponse>> {
+) -> AppResult<(Extension<cache::Scopes>, Json<ArticleResponse>)> {
     let model = article::read_by_id(&state.dbc, article_id)
         .await?
         .ok_or_else(|| {
@@ -95,8 +132,37 @@ pub async fn get_article_by_id(
                 detail: "Not found this article.".to_string(),
             })
         })?;
+    let tags = article::read_tags(&state.dbc, article_id).await?;
+    let series = series::read_by_article(&state.dbc, article_id, false).await?;
+    let scopes = cache::Scopes::series(series.iter().map(|series| series.id));
 
-    Ok(Json(ArticleResponse::from(model)))
+    Ok((
+        Extension(scopes),
+        Json(ArticleResponse::from((model, tags, series))),
+    ))
+}
+
+/// Get the articles related to an article
+#[utoipa::path(
+    get,
+    path = "/api/v1/articles/{article_id}/related",
+    params(
+        RelatedQueryParam,
+    ),
+    responses(
+        (status = 200, description = "Success get related articles", body = [Vec<RelatedArticleResponse>]),
+        (status = 404, description = "Article not found", body = [AppError]),
+        (status = 500, description = "Internal server error", body = [AppError])
+    ),
+)]
+pub async fn get_related_articles(
+    State(state): State<Arc<AppState>>,
+    Path(article_id): Path<i32>,
+    Query(param): Query<RelatedQueryParam>,
+) -> AppResult<Json<Vec<RelatedArticleResponse>>> {
+    let limit = param.limit.unwrap_or(5);
+    let resp = state.related.read(&state.dbc, article_id, limit).await?;
+    Ok(Json(resp))
 }
 
 // pub async fn _get_article_by_slug(
@@ -120,5 +186,8 @@ pub async fn delete_article(
     State(state): State<Arc<AppState>>,
     Path(article_id): Path<i32>,
 ) -> AppResult<Json<()>> {
-    Ok(Json(article::delete_by_id(&state.dbc, article_id).await?))
+    state.article_changing(article_id).await;
+    article::delete_by_id(&state.dbc, article_id).await?;
+    state.article_changed(article_id).await;
+    Ok(Json(()))
 }
@@ -6,6 +6,7 @@ use crate::{
     utils::{hash::verify_password, jwt::AuthClaims},
 };
 use axum::{extract::State, Json};
+use secrecy::ExposeSecret;
 use std::sync::Arc;
 
 // Authorize.
@@ -27,16 +28,18 @@ pub async fn authorize(
     let user = repos::user::read_by_email(&state.dbc, &payload.email)
         .await?
         .ok_or_else(|| {
+            state.metrics.login_failed();
             AppError::NotFound(Resource {
                 r#type: ResourceType::User,
                 detail: "Not found this user.".to_string(),
             })
         })?;
 
-    verify_password(&payload.password, &user.password_hash)?;
+    verify_password(&payload.password, &user.password_hash)
+        .inspect_err(|_| state.metrics.login_failed())?;
 
     let token = AuthClaims::new(user.id, state.conf.auth.timeout_seconds)
-        .encode(&state.conf.auth.secret)?;
+        .encode(state.conf.auth.secret.expose_secret())?;
 
     Ok(Json(AuthResponse {
         access_token: token,
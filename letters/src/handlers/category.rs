@@ -42,6 +42,7 @@ pub async fn create_category(
     }
 
     category::create(&state.dbc, &req).await?;
+    state.listings_changed().await;
     Ok(Json(()))
 }
 
@@ -62,6 +63,7 @@ pub async fn update_category(
     }
 
     category::update(&state.dbc, category_id, &req).await?;
+    state.names_changed().await;
     Ok(Json(()))
 }
 
@@ -94,5 +96,7 @@ pub async fn delete_category(
     State(state): State<Arc<AppState>>,
     Path(category_id): Path<i32>,
 ) -> AppResult<Json<()>> {
-    Ok(Json(category::delete_by_id(&state.dbc, category_id).await?))
+    category::delete_by_id(&state.dbc, category_id).await?;
+    state.names_changed().await;
+    Ok(Json(()))
 }
@@ -0,0 +1,140 @@
+use crate::{
+    app::AppState,
+    dto::feed::{FeedFormat, FeedScope},
+    error::{AppError, AppResult, Resource, ResourceType},
+    feed::Channel,
+    repos::{article, category, series, tag, user},
+    utils::conditional,
+};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    response::Response,
+};
+use std::sync::Arc;
+
+pub async fn rss(State(state): State<Arc<AppState>>, headers: HeaderMap) -> AppResult<Response> {
+    render(&state, &headers, FeedScope::All, FeedFormat::Rss).await
+}
+
+pub async fn atom(State(state): State<Arc<AppState>>, headers: HeaderMap) -> AppResult<Response> {
+    render(&state, &headers, FeedScope::All, FeedFormat::Atom).await
+}
+
+pub async fn json(State(state): State<Arc<AppState>>, headers: HeaderMap) -> AppResult<Response> {
+    render(&state, &headers, FeedScope::All, FeedFormat::Json).await
+}
+
+pub async fn category_feed(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((category_id, format)): Path<(i32, FeedFormat)>,
+) -> AppResult<Response> {
+    render(&state, &headers, FeedScope::Category(category_id), format).await
+}
+
+pub async fn tag_feed(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((tag_id, format)): Path<(i32, FeedFormat)>,
+) -> AppResult<Response> {
+    render(&state, &headers, FeedScope::Tag(tag_id), format).await
+}
+
+pub async fn series_feed(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((series_id, format)): Path<(i32, FeedFormat)>,
+) -> AppResult<Response> {
+    render(&state, &headers, FeedScope::Series(series_id), format).await
+}
+
+pub async fn author_feed(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((user_id, format)): Path<(i32, FeedFormat)>,
+) -> AppResult<Response> {
+    render(&state, &headers, FeedScope::Author(user_id), format).await
+}
+
+async fn render(
+    state: &AppState,
+    headers: &HeaderMap,
+    scope: FeedScope,
+    format: FeedFormat,
+) -> AppResult<Response> {
+    let site = &state.conf.site;
+    let title = match scope_name(state, scope).await? {
+        Some(name) => format!("{} - {}", site.title, name),
+        None => site.title.clone(),
+    };
+
+    let articles = article::read_feed(&state.dbc, scope, state.conf.feed.limit).await?;
+    let ids: Vec<i32> = articles.iter().map(|article| article.id).collect();
+    let mut tags = article::read_tags_by_articles(&state.dbc, &ids).await?;
+    let articles = articles
+        .into_iter()
+        .map(|article| {
+            let tags = tags.remove(&article.id).unwrap_or_default();
+            (article, tags)
+        })
+        .collect();
+
+    let home_path = scope.path();
+    let feed_url = site.url(&format!("{}/{}", home_path, format.file_name()));
+    let channel = Channel::new(
+        site,
+        &state.conf.feed,
+        title,
+        &home_path,
+        feed_url,
+        articles,
+    );
+
+    Ok(conditional::respond(
+        headers,
+        format.content_type(),
+        channel.updated,
+        channel.render(format),
+    ))
+}
+
+/// Name of the category, tag, series or author the feed is scoped to.
+async fn scope_name(state: &AppState, scope: FeedScope) -> AppResult<Option<String>> {
+    let not_found = |r#type: ResourceType, detail: &str| {
+        AppError::NotFound(Resource {
+            r#type,
+            detail: detail.to_string(),
+        })
+    };
+
+    let name = match scope {
+        FeedScope::All => return Ok(None),
+        FeedScope::Category(id) => {
+            category::read_by_id(&state.dbc, id)
+                .await?
+                .ok_or_else(|| not_found(ResourceType::Category, "Not found this category."))?
+                .name
+        }
+        FeedScope::Tag(id) => {
+            tag::read_by_id(&state.dbc, id)
+                .await?
+                .ok_or_else(|| not_found(ResourceType::Tag, "Not found this tag."))?
+                .name
+        }
+        FeedScope::Series(id) => {
+            series::read_by_id(&state.dbc, id)
+                .await?
+                .ok_or_else(|| not_found(ResourceType::Series, "Not found this series."))?
+                .name
+        }
+        FeedScope::Author(id) => {
+            user::read_by_id(&state.dbc, id)
+                .await?
+                .ok_or_else(|| not_found(ResourceType::User, "Not found this user."))?
+                .username
+        }
+    };
+
+    Ok(Some(name))
+}
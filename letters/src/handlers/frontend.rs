@@ -0,0 +1,291 @@
+//! Server rendered html pages of the reader frontend.
+
+use crate::{
+    app::AppState,
+    cache,
+    dto::{
+        article::PublishedArticle,
+        feed::FeedScope,
+        search::{SearchPageQueryParam, SearchQueryParam},
+        tag::ArticleTag,
+    },
+    error::{AppError, AppResult, Resource, ResourceType},
+    render::pages::{
+        ArchivePage, ArticleItem, ArticlePage, ListPage, NotFoundPage, Pagination, SearchItem,
+        SearchPage, SiteContext,
+    },
+    repos::{article, category, series, tag, user},
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+    Extension,
+};
+use serde::Serialize;
+use std::{collections::HashMap, sync::Arc};
+
+pub async fn index(State(state): State<Arc<AppState>>) -> Response {
+    page(&state, list(&state, FeedScope::All, 1).await)
+}
+
+pub async fn index_page(State(state): State<Arc<AppState>>, Path(page_num): Path<u64>) -> Response {
+    page(&state, list(&state, FeedScope::All, page_num).await)
+}
+
+pub async fn article(State(state): State<Arc<AppState>>, Path(slug): Path<String>) -> Response {
+    page(&state, article_page(&state, &slug).await)
+}
+
+pub async fn category(
+    State(state): State<Arc<AppState>>,
+    Path(category_id): Path<i32>,
+) -> Response {
+    page(
+        &state,
+        list(&state, FeedScope::Category(category_id), 1).await,
+    )
+}
+
+pub async fn category_page(
+    State(state): State<Arc<AppState>>,
+    Path((category_id, page_num)): Path<(i32, u64)>,
+) -> Response {
+    page(
+        &state,
+        list(&state, FeedScope::Category(category_id), page_num).await,
+    )
+}
+
+pub async fn tag(State(state): State<Arc<AppState>>, Path(tag_id): Path<i32>) -> Response {
+    page(&state, list(&state, FeedScope::Tag(tag_id), 1).await)
+}
+
+pub async fn tag_page(
+    State(state): State<Arc<AppState>>,
+    Path((tag_id, page_num)): Path<(i32, u64)>,
+) -> Response {
+    page(&state, list(&state, FeedScope::Tag(tag_id), page_num).await)
+}
+
+pub async fn series(State(state): State<Arc<AppState>>, Path(series_id): Path<i32>) -> Response {
+    page(&state, list(&state, FeedScope::Series(series_id), 1).await)
+}
+
+pub async fn series_page(
+    State(state): State<Arc<AppState>>,
+    Path((series_id, page_num)): Path<(i32, u64)>,
+) -> Response {
+    page(
+        &state,
+        list(&state, FeedScope::Series(series_id), page_num).await,
+    )
+}
+
+pub async fn author(State(state): State<Arc<AppState>>, Path(user_id): Path<i32>) -> Response {
+    page(&state, list(&state, FeedScope::Author(user_id), 1).await)
+}
+
+pub async fn author_page(
+    State(state): State<Arc<AppState>>,
+    Path((user_id, page_num)): Path<(i32, u64)>,
+) -> Response {
+    page(
+        &state,
+        list(&state, FeedScope::Author(user_id), page_num).await,
+    )
+}
+
+pub async fn archive(State(state): State<Arc<AppState>>) -> Response {
+    page(&state, archive_page(&state).await)
+}
+
+pub async fn search(
+    State(state): State<Arc<AppState>>,
+    Query(param): Query<SearchPageQueryParam>,
+) -> Response {
+    page(&state, search_page(&state, param).await)
+}
+
+async fn list(state: &AppState, scope: FeedScope, page_num: u64) -> AppResult<Response> {
+    let site = &state.conf.site;
+    let (title, description) = match scope {
+        FeedScope::All => (site.title.clone(), Some(site.description.clone())),
+        FeedScope::Category(id) => {
+            let model = category::read_by_id(&state.dbc, id)
+                .await?
+                .ok_or_else(|| missing(ResourceType::Category, "Not found this category."))?;
+            (model.name, model.description)
+        }
+        FeedScope::Tag(id) => {
+            let model = tag::read_by_id(&state.dbc, id)
+                .await?
+                .ok_or_else(|| missing(ResourceType::Tag, "Not found this tag."))?;
+            (model.name, model.description)
+        }
+        FeedScope::Series(id) => {
+            let model = series::read_by_id(&state.dbc, id)
+                .await?
+                .ok_or_else(|| missing(ResourceType::Series, "Not found this series."))?;
+            (model.name, model.description)
+        }
+        FeedScope::Author(id) => {
+            let model = user::read_by_id(&state.dbc, id)
+                .await?
+                .ok_or_else(|| missing(ResourceType::User, "Not found this user."))?;
+            (model.username, model.bio)
+        }
+    };
+
+    let per_page = state.conf.theme.per_page;
+    let (articles, total_pages) =
+        article::read_published_page(&state.dbc, scope, page_num, per_page).await?;
+    if page_num == 0 || page_num > total_pages.max(1) {
+        return Err(missing(ResourceType::Article, "Not found this page."));
+    }
+
+    let base = scope.path();
+    let ctx = ListPage {
+        site: site_context(state),
+        title,
+        description: description.filter(|description| !description.is_empty()),
+        feed_url: Some(format!("{}/feed.xml", base)),
+        articles: items(state, &articles).await?,
+        pagination: Some(Pagination::new(&base, page_num, total_pages.max(1))),
+    };
+    let template = match scope {
+        FeedScope::All => "index.html",
+        _ => "list.html",
+    };
+    render(state, template, ctx)
+}
+
+async fn article_page(state: &AppState, slug: &str) -> AppResult<Response> {
+    let model = article::read_published_by_slug(&state.dbc, slug)
+        .await?
+        .ok_or_else(|| missing(ResourceType::Article, "Not found this article."))?;
+    let tags = article::read_tags(&state.dbc, model.id).await?;
+    let series = series::read_by_article(&state.dbc, model.id, true).await?;
+    let scopes = cache::Scopes::series(series.iter().map(|series| series.id));
+
+    let ctx = ArticlePage {
+        site: site_context(state),
+        article: ArticleItem::new(&model, &tags),
+        content: model.content,
+        series: series.into_iter().map(Into::into).collect(),
+    };
+    Ok((Extension(scopes), render(state, "article.html", ctx)?).into_response())
+}
+
+async fn archive_page(state: &AppState) -> AppResult<Response> {
+    let articles = article::read_all_published(&state.dbc).await?;
+    let ctx = ArchivePage::new(site_context(state), items(state, &articles).await?);
+    render(state, "archive.html", ctx)
+}
+
+async fn search_page(state: &AppState, param: SearchPageQueryParam) -> AppResult<Response> {
+    let q = param.q.unwrap_or_default().trim().to_string();
+    let mut ctx = SearchPage {
+        site: site_context(state),
+        q,
+        total: 0,
+        results: vec![],
+        pagination: None,
+    };
+    if ctx.q.is_empty() {
+        return render(state, "search.html", ctx);
+    }
+
+    let last_page = crate::search::last_page(state.conf.theme.per_page);
+    let page_num = param.page.unwrap_or(1).clamp(1, last_page);
+    let resp = state.search.search(&SearchQueryParam {
+        q: ctx.q.clone(),
+        category_id: None,
+        tag_id: None,
+        author_id: None,
+        from: None,
+        to: None,
+        page: Some(page_num),
+        per_page: Some(state.conf.theme.per_page),
+    })?;
+    let ids: Vec<i32> = resp.hits.iter().map(|hit| hit.id).collect();
+    let articles = article::read_published_by_ids(&state.dbc, &ids).await?;
+    let mut items: HashMap<i32, ArticleItem> = items(state, &articles)
+        .await?
+        .into_iter()
+        .map(|item| (item.id, item))
+        .collect();
+
+    let total_pages = (resp.total as u64)
+        .div_ceil(resp.per_page)
+        .clamp(1, last_page);
+    ctx.total = resp.total;
+    ctx.results = resp
+        .hits
+        .iter()
+        .filter_map(|hit| Some(SearchItem::new(items.remove(&hit.id)?, hit)))
+        .collect();
+    ctx.pagination = Some(Pagination::search(&ctx.q, page_num, total_pages));
+    render(state, "search.html", ctx)
+}
+
+/// Turn the result of a page into a response, with the 404 page for
+/// whatever was not found.
+fn page(state: &AppState, result: AppResult<Response>) -> Response {
+    match result {
+        Ok(resp) => resp,
+        Err(AppError::NotFound(_)) => not_found_page(state),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// The 404 page of the theme.
+pub fn not_found_page(state: &AppState) -> Response {
+    let ctx = NotFoundPage {
+        site: site_context(state),
+        title: "Page not found".to_string(),
+    };
+    match render(state, "404.html", ctx) {
+        Ok(resp) => (StatusCode::NOT_FOUND, resp).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+fn render<S: Serialize>(state: &AppState, template: &str, ctx: S) -> AppResult<Response> {
+    let theme = state
+        .theme
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("The html frontend is disabled"))?
+        .get()?;
+    Ok(Html(theme.render(template, ctx)?).into_response())
+}
+
+fn site_context(state: &AppState) -> SiteContext {
+    SiteContext {
+        search: true,
+        ..SiteContext::from(&state.conf.site)
+    }
+}
+
+async fn items(state: &AppState, articles: &[PublishedArticle]) -> AppResult<Vec<ArticleItem>> {
+    let ids: Vec<i32> = articles.iter().map(|article| article.id).collect();
+    let mut tags: HashMap<i32, Vec<ArticleTag>> = HashMap::new();
+    for chunk in ids.chunks(500) {
+        tags.extend(article::read_tags_by_articles(&state.dbc, chunk).await?);
+    }
+
+    Ok(articles
+        .iter()
+        .map(|article| {
+            let tags = tags.get(&article.id).map(Vec::as_slice).unwrap_or_default();
+            ArticleItem::new(article, tags)
+        })
+        .collect())
+}
+
+fn missing(r#type: ResourceType, detail: &str) -> AppError {
+    AppError::NotFound(Resource {
+        r#type,
+        detail: detail.to_string(),
+    })
+}
@@ -0,0 +1,100 @@
+use crate::{app::AppState, error::AppResult, metrics::Pool, repos::comment};
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend};
+use serde_json::json;
+use std::sync::Arc;
+
+/// Answers as long as the process does.
+pub async fn healthz() -> &'static str {
+    "ok"
+}
+
+/// Ready once the database answers and every migration is applied, a 503
+/// says which one is not. The errors are logged, the endpoint is public.
+pub async fn readyz(State(state): State<Arc<AppState>>) -> Response {
+    let database = match state.dbc.ping().await {
+        Ok(()) => "ok".to_string(),
+        Err(err) => {
+            tracing::warn!("the database is unavailable: {}", err);
+            "unavailable".to_string()
+        }
+    };
+    let migrations = if database != "ok" {
+        "unknown".to_string()
+    } else {
+        match Migrator::get_pending_migrations(state.dbc.as_ref()).await {
+            Ok(pending) if pending.is_empty() => "ok".to_string(),
+            Ok(pending) => format!("{} pending", pending.len()),
+            Err(err) => {
+                tracing::warn!("the migrations can not be read: {}", err);
+                "error".to_string()
+            }
+        }
+    };
+
+    let status = if database == "ok" && migrations == "ok" {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(json!({ "database": database, "migrations": migrations })),
+    )
+        .into_response()
+}
+
+pub async fn metrics(State(state): State<Arc<AppState>>) -> AppResult<Response> {
+    let comments = comment::count(&state.dbc).await?;
+    let body = state.metrics.render(pool(&state.dbc), comments);
+
+    Ok((
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        body,
+    )
+        .into_response())
+}
+
+/// Connections of the pool, for the backends the binary is built with.
+#[allow(unreachable_patterns)]
+fn pool(dbc: &DatabaseConnection) -> Option<Pool> {
+    match dbc.get_database_backend() {
+        #[cfg(feature = "mysql")]
+        DbBackend::MySql => {
+            let pool = dbc.get_mysql_connection_pool();
+            Some(Pool {
+                size: pool.size(),
+                idle: pool.num_idle(),
+                max: pool.options().get_max_connections(),
+            })
+        }
+        #[cfg(feature = "postgres")]
+        DbBackend::Postgres => {
+            let pool = dbc.get_postgres_connection_pool();
+            Some(Pool {
+                size: pool.size(),
+                idle: pool.num_idle(),
+                max: pool.options().get_max_connections(),
+            })
+        }
+        #[cfg(feature = "sqlite")]
+        DbBackend::Sqlite => {
+            let pool = dbc.get_sqlite_connection_pool();
+            Some(Pool {
+                size: pool.size(),
+                idle: pool.num_idle(),
+                max: pool.options().get_max_connections(),
+            })
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,275 @@
+use crate::{
+    app::AppState,
+    dto::{
+        media::{
+            ImageFormat, MediaResponse, ResizeQueryParam, SignedUrlQueryParam, SignedUrlResponse,
+        },
+        PageQueryParam,
+    },
+    error::{AppError, AppResult, Resource, ResourceType},
+    media::{self, image},
+    repos::media as media_repo,
+    utils::jwt::AuthClaims,
+};
+use axum::{
+    extract::{Multipart, Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use bytes::{Bytes, BytesMut};
+use std::sync::Arc;
+
+/// Upload a file, sent as the `file` field of a multipart form.
+#[utoipa::path(
+    post,
+    path = "/api/v1/media",
+    request_body(content = Vec<u8>, content_type = "multipart/form-data", description = "The file, in the `file` field"),
+    responses(
+        (status = 200, description = "Success upload file", body = [MediaResponse]),
+        (status = 400, description = "Invalid data input", body = [AppError]),
+        (status = 401, description = "Unauthorized user", body = [AppError]),
+        (status = 413, description = "File too large", body = [AppError]),
+        (status = 415, description = "File type not allowed", body = [AppError]),
+        (status = 500, description = "Internal server error", body = [AppError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn upload_media(
+    claims: AuthClaims,
+    State(state): State<Arc<AppState>>,
+    mut multipart: Multipart,
+) -> AppResult<Json<MediaResponse>> {
+    let max_size = state.conf.media.max_size as usize;
+    let invalid =
+        |err: axum::extract::multipart::MultipartError| AppError::InvalidInput(err.body_text());
+
+    while let Some(mut field) = multipart.next_field().await.map_err(invalid)? {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let file_name = field.file_name().unwrap_or("").to_string();
+        let mime = field.content_type().map(str::to_string);
+
+        // Read chunk by chunk to give up as soon as the limit is crossed.
+        let mut data = BytesMut::new();
+        while let Some(chunk) = field.chunk().await.map_err(invalid)? {
+            if data.len() + chunk.len() > max_size {
+                return Err(AppError::PayloadTooLarge(format!(
+                    "The file is larger than {} bytes",
+                    max_size
+                )));
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        let model = media::upload(
+            &state.dbc,
+            state.storage.as_ref(),
+            &state.conf,
+            claims.user_id,
+            &file_name,
+            mime.as_deref(),
+            data.freeze(),
+        )
+        .await?;
+        let resp = media::responses(&state.dbc, state.storage.as_ref(), vec![model]).await?;
+        return Ok(Json(
+            resp.into_iter().next().expect("one media in, one out"),
+        ));
+    }
+
+    Err(AppError::InvalidInput(
+        "The file field is missing".to_string(),
+    ))
+}
+
+/// Get the media of the current user
+#[utoipa::path(
+    get,
+    path = "/api/v1/media",
+    params(
+        PageQueryParam,
+    ),
+    responses(
+        (status = 200, description = "Success get media", body = [Vec<MediaResponse>]),
+        (status = 401, description = "Unauthorized user", body = [AppError]),
+        (status = 500, description = "Internal server error", body = [AppError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn get_media(
+    claims: AuthClaims,
+    State(state): State<Arc<AppState>>,
+    Query(param): Query<PageQueryParam>,
+) -> AppResult<Json<Vec<MediaResponse>>> {
+    let models = media_repo::read_all_by_user(&state.dbc, claims.user_id, &param).await?;
+    let resp = media::responses(&state.dbc, state.storage.as_ref(), models).await?;
+    Ok(Json(resp))
+}
+
+pub async fn get_media_by_id(
+    claims: AuthClaims,
+    State(state): State<Arc<AppState>>,
+    Path(media_id): Path<i32>,
+) -> AppResult<Json<MediaResponse>> {
+    let model = media_repo::read_by_id(&state.dbc, claims.user_id, media_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(Resource {
+                r#type: ResourceType::Media,
+                detail: "Not found this media.".to_string(),
+            })
+        })?;
+    let resp = media::responses(&state.dbc, state.storage.as_ref(), vec![model]).await?;
+    Ok(Json(
+        resp.into_iter().next().expect("one media in, one out"),
+    ))
+}
+
+/// Get a signed url resizing an image of the current user
+#[utoipa::path(
+    get,
+    path = "/api/v1/media/{media_id}/signed-url",
+    params(
+        SignedUrlQueryParam,
+    ),
+    responses(
+        (status = 200, description = "Success sign url", body = [SignedUrlResponse]),
+        (status = 400, description = "Invalid data input", body = [AppError]),
+        (status = 401, description = "Unauthorized user", body = [AppError]),
+        (status = 404, description = "Media not found", body = [AppError]),
+        (status = 500, description = "Internal server error", body = [AppError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn get_signed_url(
+    claims: AuthClaims,
+    State(state): State<Arc<AppState>>,
+    Path(media_id): Path<i32>,
+    Query(param): Query<SignedUrlQueryParam>,
+) -> AppResult<Json<SignedUrlResponse>> {
+    let model = media_repo::read_by_id(&state.dbc, claims.user_id, media_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(Resource {
+                r#type: ResourceType::Media,
+                detail: "Not found this media.".to_string(),
+            })
+        })?;
+    check_resize(&state, &model, param.width, param.format)?;
+
+    Ok(Json(SignedUrlResponse {
+        url: media::resize_url(&state.conf, media_id, param.width, param.format),
+    }))
+}
+
+/// Resize an image on the fly, the url must be signed by `get_signed_url`.
+pub async fn resize_image(
+    State(state): State<Arc<AppState>>,
+    Path(media_id): Path<i32>,
+    Query(param): Query<ResizeQueryParam>,
+) -> AppResult<Response> {
+    if !image::verify(
+        media::signing_key(&state.conf),
+        media_id,
+        param.w,
+        param.f,
+        &param.s,
+    ) {
+        return Ok(StatusCode::FORBIDDEN.into_response());
+    }
+
+    let model = media_repo::read_public_by_id(&state.dbc, media_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(Resource {
+                r#type: ResourceType::Media,
+                detail: "Not found this media.".to_string(),
+            })
+        })?;
+    check_resize(&state, &model, param.w, param.f)?;
+
+    let key = media::resized_key(&model.hash, param.w, param.f);
+    let data = match state.storage.get(&key).await? {
+        Some(data) => data,
+        None => {
+            let original = state
+                .storage
+                .get(&model.storage_key)
+                .await?
+                .ok_or_else(|| {
+                    AppError::NotFound(Resource {
+                        r#type: ResourceType::Media,
+                        detail: "Not found the file of this media.".to_string(),
+                    })
+                })?;
+            let (width, format, quality) = (param.w, param.f, state.conf.media.images.quality);
+            let resized = tokio::task::spawn_blocking(move || {
+                image::resize(&original, width, format, quality)
+            })
+            .await
+            .map_err(|err| AppError::Unexpected(err.into()))??;
+
+            let data = Bytes::from(resized.data);
+            state.storage.put(&key, data.clone(), format.mime()).await?;
+            data
+        }
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, param.f.mime()),
+            (header::CACHE_CONTROL, "public, max-age=31536000, immutable"),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+fn check_resize(
+    state: &AppState,
+    model: &entity::media::Model,
+    width: u32,
+    format: ImageFormat,
+) -> AppResult<()> {
+    if image::source_format(&model.mime).is_none() {
+        return Err(AppError::InvalidInput(
+            "The media is not a resizable image".to_string(),
+        ));
+    }
+    if width == 0 || width > state.conf.media.images.max_resize_width {
+        return Err(AppError::InvalidInput(format!(
+            "The width must be between 1 and {}",
+            state.conf.media.images.max_resize_width
+        )));
+    }
+    if format == ImageFormat::Avif && !cfg!(feature = "avif") {
+        return Err(AppError::InvalidInput(
+            "AVIF is not supported by this server".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Delete a media of the current user
+#[utoipa::path(
+    delete,
+    path = "/api/v1/media/{media_id}",
+    responses(
+        (status = 200, description = "Success delete media", body = [()]),
+        (status = 401, description = "Unauthorized user", body = [AppError]),
+        (status = 404, description = "Media not found", body = [AppError]),
+        (status = 500, description = "Internal server error", body = [AppError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn delete_media(
+    claims: AuthClaims,
+    State(state): State<Arc<AppState>>,
+    Path(media_id): Path<i32>,
+) -> AppResult<Json<()>> {
+    media::delete(&state.dbc, state.storage.as_ref(), claims.user_id, media_id).await?;
+    Ok(Json(()))
+}
@@ -1,7 +1,16 @@
+pub mod admin;
 pub mod article;
 pub mod auth;
 pub mod category;
+pub mod feed;
+pub mod frontend;
+pub mod health;
+pub mod media;
 pub mod openapi;
+pub mod redirect;
+pub mod search;
+pub mod seo;
 pub mod series;
 pub mod tag;
+pub mod trash;
 pub mod user;
@@ -1,7 +1,12 @@
+use crate::dto::admin::*;
 use crate::dto::article::*;
 use crate::dto::auth::*;
 use crate::dto::category::*;
+use crate::dto::media::*;
+use crate::dto::search::*;
+use crate::dto::seo::*;
 use crate::dto::tag::*;
+use crate::dto::trash::*;
 use crate::dto::PageQueryParam;
 use crate::error::{AppError, ErrorResponse};
 use crate::handlers;
@@ -19,9 +24,21 @@ use utoipa::{Modify, OpenApi};
         handlers::auth::authorize,
         handlers::article::create_article,
         handlers::article::get_articles,
+        handlers::article::export_articles,
         handlers::article::get_article_by_id,
+        handlers::article::get_related_articles,
+        handlers::seo::get_article_seo,
+        handlers::media::upload_media,
+        handlers::media::get_media,
+        handlers::media::delete_media,
+        handlers::media::get_signed_url,
         handlers::category::create_category,
         handlers::tag::create_tag,
+        handlers::search::search,
+        handlers::trash::get_trash,
+        handlers::trash::restore,
+        handlers::admin::get_log_level,
+        handlers::admin::set_log_level,
     ),
     components(
         schemas(
@@ -33,10 +50,25 @@ use utoipa::{Modify, OpenApi};
             ArticleRequest,
             ArticleResponse,
             PreviewArticleResponse,
+            RelatedArticleResponse,
             UpdateArticleRequest,
             PageQueryParam,
             CategoryRequest,
             TagRequest,
+            SearchQueryParam,
+            SearchHit,
+            SearchResponse,
+            SeoResponse,
+            OpenGraph,
+            TwitterCard,
+            TrashResource,
+            TrashItem,
+            TrashResponse,
+            MediaResponse,
+            MediaVariantResponse,
+            ImageFormat,
+            SignedUrlResponse,
+            LogLevel,
         )
     ),
     modifiers(&SecurityAddon),
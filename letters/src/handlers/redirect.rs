@@ -0,0 +1,52 @@
+use crate::{
+    app::AppState,
+    conf::Site,
+    error::AppResult,
+    handlers::frontend,
+    repos::{article, redirect},
+};
+use axum::{
+    extract::{OriginalUri, State},
+    http::{StatusCode, Uri},
+    response::{IntoResponse, Redirect, Response},
+};
+use std::sync::Arc;
+
+/// Fallback of the whole site: old urls of imported articles are redirected
+/// to them, anything else is not found.
+pub async fn fallback(
+    State(state): State<Arc<AppState>>,
+    OriginalUri(uri): OriginalUri,
+) -> Response {
+    if uri.path() == "/api" || uri.path().starts_with("/api/") {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    match find(&state, &uri).await {
+        Ok(Some(path)) => return Redirect::permanent(&path).into_response(),
+        Ok(None) => {}
+        Err(err) => return err.into_response(),
+    }
+
+    match state.theme {
+        Some(_) => frontend::not_found_page(&state),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Path of the article `uri` used to point to.
+async fn find(state: &AppState, uri: &Uri) -> AppResult<Option<String>> {
+    let mut candidates = vec![uri.path()];
+    if let Some(path_and_query) = uri.path_and_query() {
+        candidates.insert(0, path_and_query.as_str());
+    }
+
+    for path in candidates {
+        if let Some(model) = redirect::read_by_path(&state.dbc, path).await? {
+            let article = article::read_model_by_id(&state.dbc, model.article_id).await?;
+            return Ok(article.map(|article| Site::article_path(&article.slug)));
+        }
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,30 @@
+use crate::{
+    app::AppState,
+    dto::search::{SearchQueryParam, SearchResponse},
+    error::AppResult,
+};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use std::sync::Arc;
+
+/// Full-text search over the published articles.
+#[utoipa::path(
+    get,
+    path = "/api/v1/search",
+    params(
+        SearchQueryParam,
+    ),
+    responses(
+        (status = 200, description = "Success search articles", body = [SearchResponse]),
+        (status = 400, description = "Invalid data input", body = [AppError]),
+        (status = 500, description = "Internal server error", body = [AppError])
+    ),
+)]
+pub async fn search(
+    State(state): State<Arc<AppState>>,
+    Query(param): Query<SearchQueryParam>,
+) -> AppResult<Json<SearchResponse>> {
+    Ok(Json(state.search.search(&param)?))
+}
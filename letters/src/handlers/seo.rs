@@ -0,0 +1,99 @@
+use crate::{
+    app::AppState,
+    dto::seo::SeoResponse,
+    error::{AppError, AppResult, Resource, ResourceType},
+    repos::{article, category, sitemap, user},
+    seo::{self, sitemap::MAX_URLS},
+    utils::conditional,
+};
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::sync::Arc;
+
+const XML: &str = "application/xml; charset=utf-8";
+
+/// The sitemap, or a sitemap index once there are more urls than a single
+/// sitemap may hold.
+pub async fn sitemap(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let entries = sitemap::read_entries(&state.dbc).await?;
+    let lastmod = entries.iter().filter_map(|entry| entry.lastmod).max();
+
+    let body = if entries.len() > MAX_URLS {
+        seo::sitemap::render_index(&state.conf.site, &entries)
+    } else {
+        seo::sitemap::render_urlset(&state.conf.site, &entries)
+    };
+
+    Ok(conditional::respond(&headers, XML, lastmod, body))
+}
+
+/// One chunk of a split sitemap, `/sitemaps/1.xml` being the first.
+pub async fn sitemap_page(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(file): Path<String>,
+) -> AppResult<Response> {
+    let entries = sitemap::read_entries(&state.dbc).await?;
+    let Some(chunk) = file
+        .strip_suffix(".xml")
+        .and_then(|page| page.parse::<usize>().ok())
+        .and_then(|page| page.checked_sub(1))
+        .and_then(|index| entries.chunks(MAX_URLS).nth(index))
+    else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+    let lastmod = chunk.iter().filter_map(|entry| entry.lastmod).max();
+
+    let body = seo::sitemap::render_urlset(&state.conf.site, chunk);
+    Ok(conditional::respond(&headers, XML, lastmod, body))
+}
+
+pub async fn robots(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        seo::robots(&state.conf.site, &state.conf.seo),
+    )
+}
+
+/// Get the SEO metadata of an article
+#[utoipa::path(
+    get,
+    path = "/api/v1/articles/{article_id}/seo",
+    responses(
+        (status = 200, description = "Success get SEO metadata", body = [SeoResponse]),
+        (status = 404, description = "Article not found", body = [AppError]),
+        (status = 500, description = "Internal server error", body = [AppError])
+    ),
+)]
+pub async fn get_article_seo(
+    State(state): State<Arc<AppState>>,
+    Path(article_id): Path<i32>,
+) -> AppResult<Json<SeoResponse>> {
+    let model = article::read_model_by_id(&state.dbc, article_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(Resource {
+                r#type: ResourceType::Article,
+                detail: "Not found this article.".to_string(),
+            })
+        })?;
+    let author = user::read_by_id(&state.dbc, model.user_id).await?;
+    let category = category::read_by_id(&state.dbc, model.category_id).await?;
+    let tags = article::read_tags(&state.dbc, article_id).await?;
+
+    Ok(Json(seo::article_seo(
+        &state.conf.site,
+        &state.conf.seo,
+        &model,
+        author.as_ref(),
+        category.as_ref().map(|category| category.name.as_str()),
+        &tags,
+    )))
+}
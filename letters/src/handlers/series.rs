@@ -2,15 +2,20 @@ use crate::{
     app::AppState,
     dto::{
         article::PreviewArticleResponse,
-        series::{SeriesRequest, SeriesResponse, UpdateSeriesRequest},
+        series::{
+            AddSeriesArticleRequest, ReorderSeriesArticlesRequest, SeriesRequest, SeriesResponse,
+            UpdateSeriesRequest,
+        },
         PageQueryParam,
     },
     error::{AppError, AppResult, Resource, ResourceType},
+    export,
     repos::{article, series},
     utils::jwt::AuthClaims,
 };
 use axum::{
     extract::{Path, Query, State},
+    response::Response,
     Json,
 };
 use std::sync::Arc;
@@ -33,6 +38,7 @@ pub async fn create_series(
     Json(req): Json<SeriesRequest>,
 ) -> AppResult<Json<()>> {
     series::create(&state.dbc, claims.user_id, &req).await?;
+    state.listings_changed().await;
     Ok(Json(()))
 }
 
@@ -43,6 +49,7 @@ pub async fn update_series(
     Json(req): Json<UpdateSeriesRequest>,
 ) -> AppResult<Json<()>> {
     series::update(&state.dbc, series_id, &req).await?;
+    state.names_changed().await;
     Ok(Json(()))
 }
 
@@ -71,6 +78,42 @@ pub async fn get_series_articles(
     Ok(Json(resp))
 }
 
+pub async fn add_series_article(
+    _claims: AuthClaims,
+    State(state): State<Arc<AppState>>,
+    Path(series_id): Path<i32>,
+    Json(req): Json<AddSeriesArticleRequest>,
+) -> AppResult<Json<()>> {
+    // Past the last chapter it is appended, whatever the position.
+    let position = req
+        .position
+        .map(|position| i32::try_from(position).unwrap_or(i32::MAX));
+    series::add_article(&state.dbc, series_id, req.article_id, position).await?;
+    state.names_changed().await;
+    Ok(Json(()))
+}
+
+pub async fn reorder_series_articles(
+    _claims: AuthClaims,
+    State(state): State<Arc<AppState>>,
+    Path(series_id): Path<i32>,
+    Json(req): Json<ReorderSeriesArticlesRequest>,
+) -> AppResult<Json<()>> {
+    series::reorder_articles(&state.dbc, series_id, &req.article_ids).await?;
+    state.names_changed().await;
+    Ok(Json(()))
+}
+
+pub async fn remove_series_article(
+    _claims: AuthClaims,
+    State(state): State<Arc<AppState>>,
+    Path((series_id, article_id)): Path<(i32, i32)>,
+) -> AppResult<Json<()>> {
+    series::remove_article(&state.dbc, series_id, article_id).await?;
+    state.names_changed().await;
+    Ok(Json(()))
+}
+
 pub async fn get_series_by_id(
     State(state): State<Arc<AppState>>,
     Path(series_id): Path<i32>,
@@ -86,10 +129,38 @@ pub async fn get_series_by_id(
     Ok(Json(SeriesResponse::from(model)))
 }
 
+/// Download the chapters of a series written by the current user as
+/// markdown files, prefixed by their position.
+pub async fn export_series(
+    claims: AuthClaims,
+    State(state): State<Arc<AppState>>,
+    Path(series_id): Path<i32>,
+) -> AppResult<Response> {
+    series::read_by_id(&state.dbc, series_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(Resource {
+                r#type: ResourceType::Series,
+                detail: "Not found this series.".to_string(),
+            })
+        })?;
+
+    let file = export::write(
+        &state.dbc,
+        state.storage.as_ref(),
+        claims.user_id,
+        Some(series_id),
+    )
+    .await?;
+    Ok(export::respond(file, &format!("series-{}.zip", series_id)))
+}
+
 pub async fn delete_series(
     _claims: AuthClaims,
     State(state): State<Arc<AppState>>,
     Path(series_id): Path<i32>,
 ) -> AppResult<Json<()>> {
-    Ok(Json(series::delete_by_id(&state.dbc, series_id).await?))
+    series::delete_by_id(&state.dbc, series_id).await?;
+    state.names_changed().await;
+    Ok(Json(()))
 }
@@ -42,6 +42,7 @@ pub async fn create_tag(
     }
 
     tag::create(&state.dbc, &req).await?;
+    state.listings_changed().await;
     Ok(Json(()))
 }
 
@@ -62,6 +63,7 @@ pub async fn update_tag(
     }
 
     tag::update(&state.dbc, tag_id, &req).await?;
+    state.names_changed().await;
     Ok(Json(()))
 }
 
@@ -92,5 +94,7 @@ pub async fn delete_tag(
     State(state): State<Arc<AppState>>,
     Path(tag_id): Path<i32>,
 ) -> AppResult<Json<()>> {
-    Ok(Json(tag::delete_by_id(&state.dbc, tag_id).await?))
+    tag::delete_by_id(&state.dbc, tag_id).await?;
+    state.names_changed().await;
+    Ok(Json(()))
 }
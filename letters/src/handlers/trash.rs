@@ -0,0 +1,82 @@
+use crate::{
+    app::AppState,
+    dto::trash::{TrashItem, TrashResource, TrashResponse},
+    error::AppResult,
+    repos::{article, category, tag, user},
+    utils::jwt::AuthClaims,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+
+/// Get everything in the trash.
+#[utoipa::path(
+    get,
+    path = "/api/v1/trash",
+    responses(
+        (status = 200, description = "Success get trashed resources", body = [TrashResponse]),
+        (status = 401, description = "Unauthorized user", body = [AppError]),
+        (status = 500, description = "Internal server error", body = [AppError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn get_trash(
+    _claims: AuthClaims,
+    State(state): State<Arc<AppState>>,
+) -> AppResult<Json<TrashResponse>> {
+    let articles = article::read_trash(&state.dbc).await?;
+    let categories = category::read_trash(&state.dbc).await?;
+    let tags = tag::read_trash(&state.dbc).await?;
+    let users = user::read_trash(&state.dbc).await?;
+
+    Ok(Json(TrashResponse {
+        articles: articles.into_iter().map(TrashItem::from).collect(),
+        categories: categories.into_iter().map(TrashItem::from).collect(),
+        tags: tags.into_iter().map(TrashItem::from).collect(),
+        users: users.into_iter().map(TrashItem::from).collect(),
+    }))
+}
+
+/// Restore a resource from the trash.
+#[utoipa::path(
+    post,
+    path = "/api/v1/trash/{resource}/{id}/restore",
+    params(
+        ("resource" = TrashResource, Path, description = "articles, categories, tags or users"),
+        ("id" = i32, Path, description = "Resource id"),
+    ),
+    responses(
+        (status = 200, description = "Success restore resource", body = [()]),
+        (status = 401, description = "Unauthorized user", body = [AppError]),
+        (status = 404, description = "Resource not found in trash", body = [AppError]),
+        (status = 500, description = "Internal server error", body = [AppError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn restore(
+    _claims: AuthClaims,
+    State(state): State<Arc<AppState>>,
+    Path((resource, id)): Path<(TrashResource, i32)>,
+) -> AppResult<Json<()>> {
+    match resource {
+        TrashResource::Articles => {
+            article::restore_by_id(&state.dbc, id).await?;
+            state.article_changed(id).await;
+        }
+        TrashResource::Categories => {
+            category::restore_by_id(&state.dbc, id).await?;
+            state.names_changed().await;
+        }
+        TrashResource::Tags => {
+            tag::restore_by_id(&state.dbc, id).await?;
+            state.names_changed().await;
+        }
+        TrashResource::Users => {
+            user::restore_by_id(&state.dbc, id).await?;
+            state.names_changed().await;
+        }
+    }
+    Ok(Json(()))
+}
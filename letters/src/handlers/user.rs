@@ -1,7 +1,7 @@
 use crate::{
     app::AppState,
     dto::user::{ResetPassword, UpdateUserProfile, UserProfile},
-    error::AppResult,
+    error::{AppError, AppResult, Resource, ResourceType},
     repos::user,
     utils::{hash::verify_password, jwt::AuthClaims},
 };
@@ -23,7 +23,14 @@ pub async fn get_profile(
     claims: AuthClaims,
     State(state): State<Arc<AppState>>,
 ) -> AppResult<Json<UserProfile>> {
-    let model = user::read_by_id(&state.dbc, claims.user_id).await?.unwrap();
+    let model = user::read_by_id(&state.dbc, claims.user_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(Resource {
+                r#type: ResourceType::User,
+                detail: "Not found this user.".to_string(),
+            })
+        })?;
     Ok(Json(UserProfile::from(model)))
 }
 
@@ -46,6 +53,7 @@ pub async fn update_profile(
     Json(req): Json<UpdateUserProfile>,
 ) -> AppResult<Json<()>> {
     user::update(&state.dbc, claims.user_id, &req).await?;
+    state.names_changed().await;
     Ok(Json(()))
 }
 
@@ -66,10 +74,38 @@ pub async fn reset_password(
     State(state): State<Arc<AppState>>,
     Json(req): Json<ResetPassword>,
 ) -> AppResult<Json<()>> {
-    let model = user::read_by_id(&state.dbc, claims.user_id).await?.unwrap();
+    let model = user::read_by_id(&state.dbc, claims.user_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(Resource {
+                r#type: ResourceType::User,
+                detail: "Not found this user.".to_string(),
+            })
+        })?;
     verify_password(&req.old_password, &model.password_hash)?;
 
     Ok(Json(
         user::update_password(&state.dbc, claims.user_id, &req.new_password).await?,
     ))
 }
+
+/// Delete user account, the account is moved to the trash.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/profile",
+    responses(
+        (status = 200, description = "Success delete user account", body = [()]),
+        (status = 401, description = "Unauthorized user", body = [AppError]),
+        (status = 404, description = "User not found", body = [AppError]),
+        (status = 500, description = "Internal server error", body = [AppError])
+    ),
+    security(("jwt" = []))
+)]
+pub async fn delete_profile(
+    claims: AuthClaims,
+    State(state): State<Arc<AppState>>,
+) -> AppResult<Json<()>> {
+    user::delete_by_id(&state.dbc, claims.user_id).await?;
+    state.names_changed().await;
+    Ok(Json(()))
+}
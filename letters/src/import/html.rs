@@ -0,0 +1,436 @@
+//! Conversion of the html of blog posts into markdown.
+//!
+//! Covers what editors produce: paragraphs, headings, emphasis, links,
+//! images, lists, quotes and code. Anything markdown has no syntax for, like
+//! tables or embeds, is kept as html, which markdown allows.
+
+use scraper::{node::Node, ElementRef, Html};
+
+/// Tags rendered as blocks of their own.
+const BLOCKS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+/// Tags kept as they are.
+const RAW: &[&str] = &[
+    "audio", "iframe", "object", "table", "video", "embed", "details",
+];
+
+pub fn to_markdown(html: &str) -> String {
+    let fragment = Html::parse_fragment(&autop(html));
+    let mut out = Vec::new();
+    blocks(fragment.root_element(), &mut out);
+    out.join("\n\n")
+}
+
+/// Wrap the text separated by blank lines in paragraphs, as WordPress does
+/// for the content written in its classic editor. `pre` blocks are left
+/// untouched.
+fn autop(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while !rest.is_empty() {
+        let (text, pre, next) = match rest.find("<pre") {
+            Some(start) => {
+                let end = rest[start..]
+                    .find("</pre>")
+                    .map(|end| start + end + "</pre>".len())
+                    .unwrap_or(rest.len());
+                (&rest[..start], &rest[start..end], &rest[end..])
+            }
+            None => (rest, "", ""),
+        };
+
+        let text = text.replace("\r\n", "\n");
+        for chunk in text.split("\n\n") {
+            let chunk = chunk.trim();
+            if chunk.is_empty() {
+                continue;
+            }
+            if starts_with_block(chunk) {
+                out.push_str(chunk);
+            } else {
+                out.push_str("<p>");
+                out.push_str(&chunk.replace('\n', "<br>\n"));
+                out.push_str("</p>");
+            }
+            out.push('\n');
+        }
+        out.push_str(pre);
+        rest = next;
+    }
+    out
+}
+
+fn starts_with_block(chunk: &str) -> bool {
+    let Some(tag) = chunk.strip_prefix('<') else {
+        return false;
+    };
+    if tag.starts_with("!--") || tag.starts_with('/') {
+        return true;
+    }
+    let name: String = tag
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    BLOCKS.contains(&name.as_str()) || RAW.contains(&name.as_str())
+}
+
+/// Markdown blocks of the children of `parent`, inline content between
+/// blocks becomes paragraphs.
+fn blocks(parent: ElementRef, out: &mut Vec<String>) {
+    let mut run = String::new();
+    for child in parent.children() {
+        match child.value() {
+            Node::Text(text) => run.push_str(&escape(&collapse(text))),
+            Node::Element(element) => {
+                let Some(child) = ElementRef::wrap(child) else {
+                    continue;
+                };
+                if BLOCKS.contains(&element.name()) || RAW.contains(&element.name()) {
+                    flush(&mut run, out);
+                    block(child, out);
+                } else {
+                    run.push_str(&inline(child));
+                }
+            }
+            _ => {}
+        }
+    }
+    flush(&mut run, out);
+}
+
+fn flush(run: &mut String, out: &mut Vec<String>) {
+    let text = trim_lines(run);
+    if !text.is_empty() {
+        out.push(text);
+    }
+    run.clear();
+}
+
+fn block(element: ElementRef, out: &mut Vec<String>) {
+    let name = element.value().name();
+    if RAW.contains(&name) {
+        out.push(element.html());
+        return;
+    }
+
+    match name {
+        "p" | "dt" | "dd" | "figcaption" => {
+            let text = trim_lines(&inline_children(element));
+            if !text.is_empty() {
+                out.push(text);
+            }
+        }
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let level = name[1..].parse().unwrap_or(1);
+            let text = collapse(&inline_children(element));
+            if !text.trim().is_empty() {
+                out.push(format!("{} {}", "#".repeat(level), text.trim()));
+            }
+        }
+        "hr" => out.push("---".to_string()),
+        "pre" => out.push(code_block(element)),
+        "ul" | "ol" => out.push(list(element, name == "ol")),
+        "blockquote" => {
+            let mut inner = Vec::new();
+            blocks(element, &mut inner);
+            let quote = inner
+                .join("\n\n")
+                .lines()
+                .map(|line| match line {
+                    "" => ">".to_string(),
+                    line => format!("> {}", line),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            if !quote.is_empty() {
+                out.push(quote);
+            }
+        }
+        _ => blocks(element, out),
+    }
+}
+
+fn code_block(element: ElementRef) -> String {
+    let code = element
+        .children()
+        .filter_map(ElementRef::wrap)
+        .find(|child| child.value().name() == "code");
+    let language = code
+        .into_iter()
+        .chain([element])
+        .flat_map(|element| element.value().classes())
+        .find_map(|class| {
+            class
+                .strip_prefix("language-")
+                .or_else(|| class.strip_prefix("lang-"))
+        })
+        .unwrap_or_default();
+
+    let text: String = element.text().collect();
+    let text = text.trim_matches('\n');
+    let fence = if text.contains("```") { "~~~" } else { "```" };
+    format!("{}{}\n{}\n{}", fence, language, text, fence)
+}
+
+fn list(element: ElementRef, ordered: bool) -> String {
+    let mut items = Vec::new();
+    let start: usize = element
+        .value()
+        .attr("start")
+        .and_then(|start| start.parse().ok())
+        .unwrap_or(1);
+
+    for (i, item) in element
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|child| child.value().name() == "li")
+        .enumerate()
+    {
+        let marker = if ordered {
+            format!("{}. ", start + i)
+        } else {
+            "- ".to_string()
+        };
+        let mut inner = Vec::new();
+        blocks(item, &mut inner);
+        let indent = " ".repeat(marker.len());
+        let body = inner
+            .join("\n\n")
+            .lines()
+            .enumerate()
+            .map(|(j, line)| match (j, line) {
+                (0, line) => format!("{}{}", marker, line),
+                (_, "") => String::new(),
+                (_, line) => format!("{}{}", indent, line),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        items.push(if body.is_empty() {
+            marker.trim_end().to_string()
+        } else {
+            body
+        });
+    }
+
+    items.join("\n")
+}
+
+fn inline_children(element: ElementRef) -> String {
+    let mut text = String::new();
+    for child in element.children() {
+        match child.value() {
+            Node::Text(value) => text.push_str(&escape(&collapse(value))),
+            Node::Element(_) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    text.push_str(&inline(child));
+                }
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+fn inline(element: ElementRef) -> String {
+    let value = element.value();
+    let wrap = |marker: &str| {
+        let text = inline_children(element);
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return text;
+        }
+        // Keep the spaces around the markers, `** a**` is no emphasis.
+        let start = &text[..text.len() - text.trim_start().len()];
+        let end = &text[text.trim_end().len()..];
+        format!("{}{}{}{}{}", start, marker, trimmed, marker, end)
+    };
+
+    match value.name() {
+        "strong" | "b" => wrap("**"),
+        "em" | "i" | "cite" => wrap("*"),
+        "del" | "s" | "strike" => wrap("~~"),
+        "code" | "kbd" | "tt" => {
+            let text: String = element.text().collect();
+            let fence = if text.contains('`') { "``" } else { "`" };
+            format!("{}{}{}", fence, text, fence)
+        }
+        "br" => "\n".to_string(),
+        "img" => format!(
+            "![{}]({})",
+            escape(value.attr("alt").unwrap_or_default()),
+            value.attr("src").unwrap_or_default()
+        ),
+        "a" => {
+            let text = inline_children(element);
+            match value.attr("href") {
+                Some(href) if !href.is_empty() => format!("[{}]({})", text.trim(), href),
+                _ => text,
+            }
+        }
+        "script" | "style" => String::new(),
+        name if RAW.contains(&name) => element.html(),
+        _ => inline_children(element),
+    }
+}
+
+/// Whitespace of html text is not significant, runs of it are one space.
+fn collapse(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !space {
+                out.push(' ');
+            }
+            space = true;
+        } else {
+            out.push(c);
+            space = false;
+        }
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Trim every line, the lines come from `<br>`, which markdown writes as a
+/// backslash at the end of the line.
+fn trim_lines(text: &str) -> String {
+    text.trim()
+        .split('\n')
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paragraphs_and_inline_markup() {
+        assert_eq!(
+            to_markdown(
+                "<p>Some <strong>bold</strong>, <em>italic</em> and <del>gone</del> text.</p>\
+                 <p>A <a href=\"https://example.com\"> link </a> and <code>x * y</code>.</p>"
+            ),
+            "Some **bold**, *italic* and ~~gone~~ text.\n\n\
+             A [link](https://example.com) and `x * y`."
+        );
+    }
+
+    #[test]
+    fn classic_editor_text_is_wrapped_in_paragraphs() {
+        assert_eq!(
+            to_markdown("First line\nsecond line\r\n\r\nNext paragraph"),
+            "First line\\\nsecond line\n\nNext paragraph"
+        );
+    }
+
+    #[test]
+    fn headings_rules_and_images() {
+        assert_eq!(
+            to_markdown("<h2>Title</h2><hr><p><img src=\"/a.png\" alt=\"A [b]\"></p>"),
+            "## Title\n\n---\n\n![A \\[b\\]](/a.png)"
+        );
+    }
+
+    #[test]
+    fn markdown_characters_of_the_text_are_escaped() {
+        assert_eq!(
+            to_markdown("<p>2 * 3 = snake_case</p>"),
+            "2 \\* 3 = snake\\_case"
+        );
+    }
+
+    #[test]
+    fn lists_are_numbered_from_their_start() {
+        assert_eq!(
+            to_markdown("<ul><li>one</li><li>two<ul><li>nested</li></ul></li></ul>"),
+            "- one\n- two\n\n  - nested"
+        );
+        assert_eq!(
+            to_markdown("<ol start=\"3\"><li>three</li><li>four</li></ol>"),
+            "3. three\n4. four"
+        );
+    }
+
+    #[test]
+    fn quotes_are_prefixed() {
+        assert_eq!(
+            to_markdown("<blockquote><p>One</p><p>Two</p></blockquote>"),
+            "> One\n>\n> Two"
+        );
+    }
+
+    #[test]
+    fn code_blocks_keep_their_text_and_language() {
+        assert_eq!(
+            to_markdown(
+                "<pre><code class=\"language-rust\">fn main() {\n\n    let _a = 1;\n}</code></pre>"
+            ),
+            "```rust\nfn main() {\n\n    let _a = 1;\n}\n```"
+        );
+        assert_eq!(
+            to_markdown("<pre>```\nfenced\n```</pre>"),
+            "~~~\n```\nfenced\n```\n~~~"
+        );
+    }
+
+    #[test]
+    fn tables_and_embeds_are_kept_as_html() {
+        assert_eq!(
+            to_markdown("<table><tbody><tr><td>a</td></tr></tbody></table>"),
+            "<table><tbody><tr><td>a</td></tr></tbody></table>"
+        );
+        assert_eq!(
+            to_markdown("<p>Watch <iframe src=\"https://example.com/v\"></iframe></p>"),
+            "Watch <iframe src=\"https://example.com/v\"></iframe>"
+        );
+    }
+
+    #[test]
+    fn scripts_are_dropped() {
+        assert_eq!(to_markdown("<p>Text<script>alert(1)</script></p>"), "Text");
+    }
+}
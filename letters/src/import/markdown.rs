@@ -0,0 +1,373 @@
+//! Markdown files with a YAML (`---`) or TOML (`+++`) front matter, as
+//! written for Hugo, Hexo and Jekyll.
+
+use std::path::Path;
+
+use sea_orm::prelude::DateTimeUtc;
+use serde_json::Value;
+use walkdir::WalkDir;
+
+use super::{parse_date, slugify, Export, Post};
+use crate::utils::markdown;
+
+/// Marker of the end of the summary in Hugo and Hexo posts.
+const MORE: &str = "<!--more-->";
+
+/// Read every markdown file under `dir`.
+pub fn read_dir(dir: &Path) -> Export {
+    let mut posts = Vec::new();
+    let mut errors = Vec::new();
+
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                errors.push((dir.display().to_string(), err.to_string()));
+                continue;
+            }
+        };
+        let path = entry.path();
+        let is_markdown = path
+            .extension()
+            .is_some_and(|ext| ext == "md" || ext == "markdown");
+        if !entry.file_type().is_file() || !is_markdown {
+            continue;
+        }
+
+        let source = path.strip_prefix(dir).unwrap_or(path);
+        let read = std::fs::read_to_string(path).and_then(|text| {
+            let modified = entry.metadata()?.modified()?;
+            Ok((text, modified))
+        });
+        match read
+            .map_err(|err| err.to_string())
+            .and_then(|(text, modified)| parse(source, &text, modified.into()))
+        {
+            Ok(post) => posts.push(post),
+            Err(err) => errors.push((source.display().to_string(), err)),
+        }
+    }
+
+    Export {
+        posts,
+        errors,
+        ..Default::default()
+    }
+}
+
+/// Parse a post, `path` is relative to the root of the site and `modified`
+/// is the date of the post when neither its front matter nor its file name
+/// has one.
+pub fn parse(path: &Path, text: &str, modified: DateTimeUtc) -> Result<Post, String> {
+    let (front_matter, body) = split(text.trim_start_matches('\u{feff}'))?;
+
+    // Jekyll posts are named `YYYY-MM-DD-title.md`, page bundles `index.md`.
+    let mut stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if stem == "index" || stem == "_index" {
+        if let Some(dir) = path.parent().and_then(Path::file_name) {
+            stem = dir.to_string_lossy().to_string();
+        }
+    }
+    let mut name_date = None;
+    if let Some((date, rest)) = stem.get(..10).zip(stem.get(11..)) {
+        if let Some(date) = parse_date(date) {
+            name_date = Some(date);
+            stem = rest.to_string();
+        }
+    }
+
+    let title = text_of(&front_matter, &["title"]).unwrap_or_else(|| stem.clone());
+    let slug = text_of(&front_matter, &["slug"])
+        .map(|slug| slug.trim_matches('/').to_string())
+        .filter(|slug| !slug.is_empty())
+        .unwrap_or_else(|| slugify(&stem));
+    if slug.is_empty() {
+        return Err("Can not make a slug out of the file name".to_string());
+    }
+
+    let date = |keys: &[&str]| -> Result<Option<DateTimeUtc>, String> {
+        match text_of(&front_matter, keys) {
+            Some(value) => parse_date(&value)
+                .map(Some)
+                .ok_or_else(|| format!("Invalid date `{}`", value)),
+            None => Ok(None),
+        }
+    };
+    let created_at = date(&["date"])?.or(name_date).unwrap_or(modified);
+    let updated_at = date(&["lastmod", "updated", "last_modified_at"])?.unwrap_or(created_at);
+
+    let draft = flag_of(&front_matter, "draft") == Some(true)
+        || flag_of(&front_matter, "published") == Some(false)
+        || path.components().any(|part| part.as_os_str() == "_drafts");
+
+    let summary = text_of(&front_matter, &["summary", "description", "excerpt"])
+        .or_else(|| {
+            body.split_once(MORE)
+                .map(|(summary, _)| summary.to_string())
+        })
+        .map(|summary| markdown::excerpt(&summary, 255))
+        .unwrap_or_default();
+
+    Ok(Post {
+        source: path.display().to_string(),
+        title,
+        slug,
+        content: body.trim().to_string(),
+        summary,
+        created_at,
+        updated_at,
+        tags: list_of(&front_matter, &["tags"]),
+        categories: list_of(&front_matter, &["categories", "category"]),
+        series: list_of(&front_matter, &["series"]),
+        draft,
+        ..Default::default()
+    })
+}
+
+/// Split the front matter, as json, from the body of the post.
+fn split(text: &str) -> Result<(Value, &str), String> {
+    let Some((delimiter, rest)) = ["---", "+++"].into_iter().find_map(|delimiter| {
+        let rest = text.strip_prefix(delimiter)?;
+        let rest = rest
+            .strip_prefix("\r\n")
+            .or_else(|| rest.strip_prefix('\n'))?;
+        Some((delimiter, rest))
+    }) else {
+        return Ok((Value::Null, text));
+    };
+
+    // The front matter ends at the first line made of the delimiter alone.
+    let mut offset = 0;
+    let mut end = None;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == delimiter {
+            end = Some((offset, offset + line.len()));
+            break;
+        }
+        offset += line.len();
+    }
+    let Some((end, body_start)) = end else {
+        return Err("The front matter is not closed".to_string());
+    };
+
+    let raw = &rest[..end];
+    let front_matter = match delimiter {
+        "---" => serde_yaml::from_str(raw).map_err(|err| format!("Invalid YAML: {}", err))?,
+        _ => toml::from_str::<toml::Value>(raw)
+            .map(toml_to_json)
+            .map_err(|err| format!("Invalid TOML: {}", err))?,
+    };
+
+    Ok((front_matter, &rest[body_start..]))
+}
+
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(value) => Value::String(value),
+        toml::Value::Integer(value) => value.into(),
+        toml::Value::Float(value) => value.into(),
+        toml::Value::Boolean(value) => value.into(),
+        toml::Value::Datetime(value) => Value::String(value.to_string()),
+        toml::Value::Array(values) => values.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => table
+            .into_iter()
+            .map(|(key, value)| (key, toml_to_json(value)))
+            .collect(),
+    }
+}
+
+/// The first of `keys` holding a scalar, as text.
+fn text_of(front_matter: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| scalar(front_matter.get(key)?))
+        .find(|text| !text.trim().is_empty())
+}
+
+/// Every scalar under the first of `keys`, nested lists are flattened.
+fn list_of(front_matter: &Value, keys: &[&str]) -> Vec<String> {
+    fn collect(value: &Value, list: &mut Vec<String>) {
+        match value {
+            Value::Array(values) => values.iter().for_each(|value| collect(value, list)),
+            value => list.extend(scalar(value).filter(|text| !text.trim().is_empty())),
+        }
+    }
+
+    let mut list = Vec::new();
+    if let Some(value) = keys.iter().find_map(|key| front_matter.get(key)) {
+        collect(value, &mut list);
+    }
+    list.iter_mut()
+        .for_each(|name| *name = name.trim().to_string());
+    list.dedup();
+    list
+}
+
+fn flag_of(front_matter: &Value, key: &str) -> Option<bool> {
+    match front_matter.get(key)? {
+        Value::Bool(value) => Some(*value),
+        Value::String(value) => value.parse().ok(),
+        _ => None,
+    }
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn modified() -> DateTimeUtc {
+        Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap()
+    }
+
+    fn post(path: &str, text: &str) -> Post {
+        parse(Path::new(path), text, modified()).unwrap()
+    }
+
+    #[test]
+    fn yaml_front_matter() {
+        let post = post(
+            "posts/pinning.md",
+            "---\ntitle: Pinning\nslug: /pin/\ndate: 2024-05-04 08:00:00 +0200\n\
+             lastmod: 2024-06-01\ntags: [rust, async, rust]\ncategory: Notes\n\
+             series: Futures\n---\nSelf referential futures.\n",
+        );
+
+        assert_eq!(post.title, "Pinning");
+        assert_eq!(post.slug, "pin");
+        assert_eq!(post.content, "Self referential futures.");
+        assert_eq!(
+            post.created_at,
+            Utc.with_ymd_and_hms(2024, 5, 4, 6, 0, 0).unwrap()
+        );
+        assert_eq!(
+            post.updated_at,
+            Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap()
+        );
+        assert_eq!(post.tags, ["rust", "async", "rust"]);
+        assert_eq!(post.categories, ["Notes"]);
+        assert_eq!(post.series, ["Futures"]);
+        assert!(!post.draft);
+    }
+
+    #[test]
+    fn toml_front_matter() {
+        let post = post(
+            "content/posts/pinning.md",
+            "+++\r\ntitle = \"Pinning\"\r\ndate = 2024-05-04T08:00:00Z\r\n\
+             draft = true\r\ncategories = [\"Notes\", \"Rust\"]\r\n+++\r\nBody\r\n",
+        );
+
+        assert_eq!(post.title, "Pinning");
+        assert_eq!(post.slug, "pinning");
+        assert_eq!(post.content, "Body");
+        assert_eq!(
+            post.created_at,
+            Utc.with_ymd_and_hms(2024, 5, 4, 8, 0, 0).unwrap()
+        );
+        assert_eq!(post.updated_at, post.created_at);
+        assert_eq!(post.categories, ["Notes", "Rust"]);
+        assert!(post.draft);
+    }
+
+    #[test]
+    fn without_front_matter_the_file_name_is_used() {
+        let post = post("_posts/2023-03-09-Hello World.md", "# Hello\n");
+
+        assert_eq!(post.title, "Hello World");
+        assert_eq!(post.slug, "hello-world");
+        assert_eq!(post.content, "# Hello");
+        assert_eq!(
+            post.created_at,
+            Utc.with_ymd_and_hms(2023, 3, 9, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn page_bundles_are_named_after_their_directory() {
+        let post = post("posts/my-bundle/index.md", "---\n---\nBody");
+
+        assert_eq!(post.title, "my-bundle");
+        assert_eq!(post.slug, "my-bundle");
+        assert_eq!(post.created_at, modified());
+    }
+
+    #[test]
+    fn drafts_are_flagged() {
+        assert!(post("_drafts/idea.md", "Body").draft);
+        assert!(post("idea.md", "---\npublished: false\n---\nBody").draft);
+        assert!(post("idea.md", "---\ndraft: \"true\"\n---\nBody").draft);
+        assert!(!post("idea.md", "---\npublished: true\n---\nBody").draft);
+    }
+
+    #[test]
+    fn summary_from_the_front_matter_or_the_more_marker() {
+        let post_with_more = post("more.md", "Intro text\n<!--more-->\nThe rest");
+        assert_eq!(post_with_more.summary, "Intro text");
+
+        let described = post(
+            "described.md",
+            "---\ndescription: From the front matter\n---\nIntro\n<!--more-->\nRest",
+        );
+        assert_eq!(described.summary, "From the front matter");
+
+        assert_eq!(post("plain.md", "No marker").summary, "");
+    }
+
+    #[test]
+    fn nested_lists_are_flattened_and_trimmed() {
+        let post = post(
+            "list.md",
+            "---\ntags:\n  - [ rust , async]\n  - async\n  - 2024\n  - \"\"\n---\n",
+        );
+
+        assert_eq!(post.tags, ["rust", "async", "2024"]);
+    }
+
+    #[test]
+    fn byte_order_mark_is_skipped() {
+        assert_eq!(
+            post("bom.md", "\u{feff}---\ntitle: Bom\n---\n").title,
+            "Bom"
+        );
+    }
+
+    #[test]
+    fn unclosed_front_matter_is_rejected() {
+        let err = parse(Path::new("open.md"), "---\ntitle: Open\n", modified()).unwrap_err();
+        assert_eq!(err, "The front matter is not closed");
+    }
+
+    #[test]
+    fn invalid_front_matter_is_rejected() {
+        let yaml = parse(Path::new("bad.md"), "---\ntitle: [\n---\n", modified()).unwrap_err();
+        assert!(yaml.starts_with("Invalid YAML"), "{}", yaml);
+
+        let toml = parse(Path::new("bad.md"), "+++\ntitle = \n+++\n", modified()).unwrap_err();
+        assert!(toml.starts_with("Invalid TOML"), "{}", toml);
+    }
+
+    #[test]
+    fn invalid_dates_are_rejected() {
+        let err = parse(Path::new("d.md"), "---\ndate: someday\n---\n", modified()).unwrap_err();
+        assert_eq!(err, "Invalid date `someday`");
+    }
+
+    #[test]
+    fn a_slug_is_required() {
+        let err = parse(Path::new("!!!.md"), "Body", modified()).unwrap_err();
+        assert_eq!(err, "Can not make a slug out of the file name");
+    }
+}
@@ -0,0 +1,512 @@
+//! Import of articles written with other blogging tools.
+
+mod html;
+pub mod markdown;
+pub mod wxr;
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use sea_orm::{prelude::DateTimeUtc, DatabaseConnection};
+
+use crate::{
+    conf::Site,
+    dto::{article::ArticleRequest, user::NewUser},
+    error::AppResult,
+    repos::{
+        article, category, comment,
+        import::{self, ARTICLE, COMMENT},
+        redirect, series, tag, user,
+    },
+    utils::hash::random_password,
+};
+
+/// Posts read from an export, with its authors.
+#[derive(Debug, Default)]
+pub struct Export {
+    /// Identifies the exported site, the ids of its posts and comments are
+    /// recorded under it so that an import can be run again
+    pub source: Option<String>,
+    pub authors: Vec<Author>,
+    pub posts: Vec<Post>,
+    /// Source and reason of what could not be read
+    pub errors: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Author {
+    pub login: String,
+    pub email: String,
+    pub display_name: String,
+}
+
+/// An article read from an export, before it is written to the database.
+#[derive(Debug, Default)]
+pub struct Post {
+    /// Where the post comes from, for the reports
+    pub source: String,
+    /// Id of the post in the export
+    pub external_id: Option<String>,
+    /// Login of the author, the importing user when unknown
+    pub author: Option<String>,
+    pub title: String,
+    pub slug: String,
+    pub content: String,
+    pub summary: String,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+    pub tags: Vec<String>,
+    /// Only the first one is kept, an article has a single category
+    pub categories: Vec<String>,
+    pub series: Vec<String>,
+    pub draft: bool,
+    /// Paths the post used to be served from, redirected to the article
+    pub old_paths: Vec<String>,
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Comment {
+    pub external_id: String,
+    /// Id of the answered comment in the export
+    pub parent: Option<String>,
+    /// Login of the author when the comment was written by one
+    pub author: Option<String>,
+    pub name: String,
+    pub email: Option<String>,
+    pub content: String,
+    pub created_at: DateTimeUtc,
+}
+
+/// What an import is going to do, checked against the database.
+#[derive(Debug, Default)]
+pub struct Plan {
+    source: Option<String>,
+    authors: Vec<Author>,
+    /// Oldest first, so that the chapters of a series follow their dates
+    pub posts: Vec<Post>,
+    /// New comments of the articles imported by a previous run
+    pub comments: Vec<(i32, Vec<Comment>)>,
+    /// Posts imported by a previous run
+    pub imported: usize,
+    /// Source and reason of the posts which are left out
+    pub skipped: Vec<(String, String)>,
+    pub new_users: BTreeSet<String>,
+    pub new_tags: BTreeSet<String>,
+    pub new_categories: BTreeSet<String>,
+    pub new_series: BTreeSet<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub articles: usize,
+    pub comments: usize,
+    pub users: usize,
+    pub redirects: usize,
+}
+
+impl Plan {
+    /// Check the export against the database: posts imported by a previous
+    /// run or whose slug is taken are skipped, and the users, tags,
+    /// categories and series to create are listed.
+    pub async fn new(
+        dbc: &DatabaseConnection,
+        export: Export,
+        default_category: &str,
+    ) -> AppResult<Self> {
+        let Export {
+            source,
+            authors,
+            mut posts,
+            errors,
+        } = export;
+        posts.sort_by_key(|post| post.created_at);
+
+        let mut plan = Plan {
+            source,
+            authors,
+            skipped: errors,
+            ..Default::default()
+        };
+        let mut slugs = HashSet::new();
+        for mut post in posts {
+            if let Some(article_id) = plan.imported_id(dbc, ARTICLE, &post.external_id).await? {
+                let mut comments = Vec::new();
+                for comment in post.comments {
+                    let id = Some(comment.external_id.clone());
+                    if plan.imported_id(dbc, COMMENT, &id).await?.is_none() {
+                        comments.push(comment);
+                    }
+                }
+                if !comments.is_empty() {
+                    plan.comments.push((article_id, comments));
+                }
+                plan.imported += 1;
+                continue;
+            }
+            if !slugs.insert(post.slug.clone()) {
+                plan.skipped
+                    .push((post.source, format!("Duplicated slug `{}`", post.slug)));
+                continue;
+            }
+            if article::_read_by_slug(dbc, &post.slug).await?.is_some() {
+                plan.skipped
+                    .push((post.source, format!("Slug `{}` already exists", post.slug)));
+                continue;
+            }
+
+            post.categories.truncate(1);
+            if post.categories.is_empty() {
+                post.categories.push(default_category.to_string());
+            }
+            plan.posts.push(post);
+        }
+
+        let names = |f: fn(&Post) -> &[String]| -> BTreeSet<String> {
+            plan.posts.iter().flat_map(f).cloned().collect()
+        };
+        let (tags, categories, series_names) = (
+            names(|post| &post.tags),
+            names(|post| &post.categories),
+            names(|post| &post.series),
+        );
+        for name in tags {
+            if tag::check_name_exist(dbc, &name).await?.is_none() {
+                plan.new_tags.insert(name);
+            }
+        }
+        for name in categories {
+            if category::check_name_exist(dbc, &name).await?.is_none() {
+                plan.new_categories.insert(name);
+            }
+        }
+        for name in series_names {
+            if series::read_by_name(dbc, &name).await?.is_none() {
+                plan.new_series.insert(name);
+            }
+        }
+        let logins: HashSet<&str> = plan
+            .posts
+            .iter()
+            .filter_map(|post| post.author.as_deref())
+            .collect();
+        for author in plan
+            .authors
+            .iter()
+            .filter(|a| logins.contains(a.login.as_str()))
+        {
+            if user::read_by_email(dbc, &author.email).await?.is_none() {
+                plan.new_users.insert(author.login.clone());
+            }
+        }
+
+        Ok(plan)
+    }
+
+    /// Write the posts as articles, those without a known author are given to
+    /// `user_id`.
+    pub async fn apply(&self, dbc: &DatabaseConnection, user_id: i32) -> AppResult<Report> {
+        let mut report = Report::default();
+        let mut users = Users::default();
+        let logins: HashSet<&str> = self
+            .posts
+            .iter()
+            .filter_map(|post| post.author.as_deref())
+            .collect();
+        for author in self
+            .authors
+            .iter()
+            .filter(|a| logins.contains(a.login.as_str()))
+        {
+            let id = users
+                .find_or_create(dbc, &author.email, &author.display_name, &mut report)
+                .await?;
+            users.by_login.insert(author.login.clone(), id);
+        }
+
+        let mut categories: HashMap<&str, i32> = HashMap::new();
+        let mut series_ids: HashMap<&str, i32> = HashMap::new();
+        for post in &self.posts {
+            let name = post.categories[0].as_str();
+            let category_id = match categories.get(name) {
+                Some(id) => *id,
+                None => {
+                    let id = category::find_or_create_by_name(dbc, name).await?;
+                    *categories.entry(name).or_insert(id)
+                }
+            };
+
+            let mut ids = Vec::with_capacity(post.series.len());
+            for name in &post.series {
+                let id = match series_ids.get(name.as_str()) {
+                    Some(id) => *id,
+                    None => {
+                        let id = series::find_or_create_by_name(dbc, user_id, name).await?;
+                        *series_ids.entry(name).or_insert(id)
+                    }
+                };
+                ids.push(id);
+            }
+
+            let article_data = ArticleRequest {
+                title: post.title.clone(),
+                slug: Some(post.slug.clone()),
+                cover: None,
+                content: post.content.clone(),
+                summary: Some(post.summary.clone()),
+                password_hash: None,
+                source: None,
+                source_url: None,
+                topping: None,
+                status: Some(if post.draft { 0 } else { 1 }),
+                category_id: Some(category_id),
+                tag_ids: None,
+                tag_names: Some(post.tags.clone()),
+                series_ids: Some(ids),
+            };
+            let author_id = post
+                .author
+                .as_ref()
+                .and_then(|login| users.by_login.get(login))
+                .copied()
+                .unwrap_or(user_id);
+            let article_id = article::import(
+                dbc,
+                author_id,
+                &article_data,
+                post.created_at,
+                post.updated_at,
+            )
+            .await?;
+            self.record(dbc, ARTICLE, &post.external_id, article_id)
+                .await?;
+            report.articles += 1;
+
+            for path in &post.old_paths {
+                if path != &Site::article_path(&post.slug)
+                    && redirect::create(dbc, path, article_id).await?
+                {
+                    report.redirects += 1;
+                }
+            }
+            self.apply_comments(dbc, article_id, &post.comments, &mut users, &mut report)
+                .await?;
+        }
+
+        for (article_id, comments) in &self.comments {
+            self.apply_comments(dbc, *article_id, comments, &mut users, &mut report)
+                .await?;
+        }
+
+        Ok(report)
+    }
+
+    async fn apply_comments(
+        &self,
+        dbc: &DatabaseConnection,
+        article_id: i32,
+        comments: &[Comment],
+        users: &mut Users,
+        report: &mut Report,
+    ) -> AppResult<()> {
+        for comment in comments {
+            let user_id = match (&comment.author, &comment.email) {
+                (Some(login), _) if users.by_login.contains_key(login) => users.by_login[login],
+                (_, Some(email)) => {
+                    users
+                        .find_or_create(dbc, email, &comment.name, report)
+                        .await?
+                }
+                _ => {
+                    users
+                        .find_or_create(dbc, GUEST_EMAIL, "guest", report)
+                        .await?
+                }
+            };
+            let parent_id = self.imported_id(dbc, COMMENT, &comment.parent).await?;
+
+            let comment_id = comment::import(
+                dbc,
+                article_id,
+                user_id,
+                parent_id,
+                &comment.content,
+                comment.created_at,
+            )
+            .await?;
+            self.record(dbc, COMMENT, &Some(comment.external_id.clone()), comment_id)
+                .await?;
+            report.comments += 1;
+        }
+
+        Ok(())
+    }
+
+    async fn imported_id(
+        &self,
+        dbc: &DatabaseConnection,
+        kind: &str,
+        external_id: &Option<String>,
+    ) -> AppResult<Option<i32>> {
+        match (&self.source, external_id) {
+            (Some(source), Some(external_id)) => {
+                import::read_entity_id(dbc, source, kind, external_id).await
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn record(
+        &self,
+        dbc: &DatabaseConnection,
+        kind: &str,
+        external_id: &Option<String>,
+        entity_id: i32,
+    ) -> AppResult<()> {
+        if let (Some(source), Some(external_id)) = (&self.source, external_id) {
+            import::create(dbc, source, kind, external_id, entity_id).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Account of the comments written without an email.
+const GUEST_EMAIL: &str = "guest@letters.invalid";
+
+/// Users of the authors and commenters, by login and by email.
+#[derive(Default)]
+struct Users {
+    by_login: HashMap<String, i32>,
+    by_email: HashMap<String, i32>,
+}
+
+impl Users {
+    /// The user with `email`, created with a random password and a username
+    /// made of `name` when there is none.
+    async fn find_or_create(
+        &mut self,
+        dbc: &DatabaseConnection,
+        email: &str,
+        name: &str,
+        report: &mut Report,
+    ) -> AppResult<i32> {
+        let email = email.trim().to_lowercase();
+        if let Some(id) = self.by_email.get(&email) {
+            return Ok(*id);
+        }
+
+        let id = match user::read_by_email(dbc, &email).await? {
+            Some(model) => model.id,
+            None => {
+                let base = Some(slugify(name))
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or_else(|| "user".to_string());
+                let mut username = base.clone();
+                let mut n = 1;
+                while user::check_username_exist(dbc, &username).await? {
+                    n += 1;
+                    username = format!("{}-{}", base, n);
+                }
+
+                report.users += 1;
+                user::create(
+                    dbc,
+                    &NewUser {
+                        username,
+                        email: email.clone(),
+                        password: random_password(),
+                    },
+                )
+                .await?
+            }
+        };
+        self.by_email.insert(email, id);
+        Ok(id)
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let drafts = self.posts.iter().filter(|post| post.draft).count();
+        writeln!(
+            f,
+            "{} articles to import, {} published and {} drafts.",
+            self.posts.len(),
+            self.posts.len() - drafts,
+            drafts
+        )?;
+        let comments: usize = self
+            .posts
+            .iter()
+            .map(|post| post.comments.len())
+            .chain(self.comments.iter().map(|(_, comments)| comments.len()))
+            .sum();
+        if comments > 0 {
+            writeln!(f, "{} comments to import.", comments)?;
+        }
+        if self.imported > 0 {
+            writeln!(f, "{} articles were imported before.", self.imported)?;
+        }
+
+        let lists = [
+            ("users", &self.new_users),
+            ("tags", &self.new_tags),
+            ("categories", &self.new_categories),
+            ("series", &self.new_series),
+        ];
+        for (name, list) in lists {
+            if !list.is_empty() {
+                let list: Vec<&str> = list.iter().map(String::as_str).collect();
+                writeln!(f, "New {} ({}): {}", name, list.len(), list.join(", "))?;
+            }
+        }
+
+        if !self.skipped.is_empty() {
+            writeln!(f, "Skipped ({}):", self.skipped.len())?;
+            for (source, reason) in &self.skipped {
+                writeln!(f, "  {}: {}", source, reason)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse the dates found in exports, those without a timezone are taken as
+/// UTC.
+pub fn parse_date(value: &str) -> Option<DateTimeUtc> {
+    let value = value.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    for format in [
+        "%Y-%m-%d %H:%M:%S %z",
+        "%Y-%m-%d %H:%M:%S%.f %z",
+        "%Y-%m-%d %H:%M %z",
+    ] {
+        if let Ok(date) = DateTime::parse_from_str(value, format) {
+            return Some(date.with_timezone(&Utc));
+        }
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(date) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(date.and_utc());
+        }
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+}
+
+/// Slug out of a title or a file name: lowercase words joined by dashes.
+pub fn slugify(value: &str) -> String {
+    value
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
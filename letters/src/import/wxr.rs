@@ -0,0 +1,425 @@
+//! WordPress eXtended RSS, the export format of WordPress.
+
+use std::collections::HashMap;
+
+use roxmltree::{Document, Node, ParsingOptions};
+use sea_orm::prelude::DateTimeUtc;
+
+use super::{html, parse_date, slugify, Author, Comment, Export, Post};
+use crate::utils::markdown;
+
+/// Read a WXR file. Posts and pages are imported, pages into
+/// `pages_category`, together with their approved comments.
+pub fn read(xml: &str, pages_category: &str) -> Result<Export, String> {
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+    let doc = Document::parse_with_options(xml, options).map_err(|err| err.to_string())?;
+    let channel = doc
+        .descendants()
+        .find(|node| node.has_tag_name("channel"))
+        .ok_or("Not a WXR file, there is no channel")?;
+
+    let site_url = text(channel, "base_site_url")
+        .or_else(|| text(channel, "link"))
+        .unwrap_or_default();
+
+    let mut logins_by_id = HashMap::new();
+    let mut authors = Vec::new();
+    for node in children(channel, "author") {
+        let Some(login) = text(node, "author_login") else {
+            continue;
+        };
+        if let Some(id) = text(node, "author_id") {
+            logins_by_id.insert(id, login.clone());
+        }
+        authors.push(Author {
+            email: text(node, "author_email").unwrap_or_default(),
+            display_name: text(node, "author_display_name").unwrap_or_else(|| login.clone()),
+            login,
+        });
+    }
+    // Authors without an email can not be matched to a user.
+    authors.retain(|author| !author.email.is_empty());
+
+    let mut export = Export {
+        source: Some(format!("wxr:{}", site_url.trim_end_matches('/'))),
+        authors,
+        ..Default::default()
+    };
+    for item in children(channel, "item") {
+        let id = text(item, "post_id").unwrap_or_default();
+        let source = format!("post {}", id);
+        match read_item(item, &logins_by_id, pages_category) {
+            Ok(Some(post)) => export.posts.push(post),
+            Ok(None) => {}
+            Err(err) => export.errors.push((source, err)),
+        }
+    }
+
+    Ok(export)
+}
+
+fn read_item(
+    item: Node,
+    logins_by_id: &HashMap<String, String>,
+    pages_category: &str,
+) -> Result<Option<Post>, String> {
+    let kind = text(item, "post_type").unwrap_or_default();
+    if kind != "post" && kind != "page" {
+        return Ok(None);
+    }
+    let draft = match text(item, "status").as_deref() {
+        Some("publish") => false,
+        Some("draft" | "pending" | "private" | "future") => true,
+        // Trash, auto drafts and revisions.
+        _ => return Ok(None),
+    };
+
+    let id = text(item, "post_id").ok_or("The post has no id")?;
+    let title = text(item, "title").unwrap_or_default();
+    let slug = text(item, "post_name")
+        .map(|slug| percent_decode(&slug))
+        .filter(|slug| !slug.is_empty())
+        .unwrap_or_else(|| slugify(&title));
+    let slug = if slug.is_empty() { id.clone() } else { slug };
+
+    let created_at = date(item, "post_date_gmt")
+        .or_else(|| date(item, "post_date"))
+        .ok_or("The post has no date")?;
+    let updated_at = date(item, "post_modified_gmt").unwrap_or(created_at);
+
+    let content = html::to_markdown(&encoded(item, "content").unwrap_or_default());
+    let summary = encoded(item, "excerpt")
+        .filter(|excerpt| !excerpt.trim().is_empty())
+        .map(|excerpt| markdown::excerpt(&html::to_markdown(&excerpt), 255))
+        .unwrap_or_default();
+
+    let mut tags = Vec::new();
+    let mut categories = Vec::new();
+    let mut series = Vec::new();
+    for node in children(item, "category") {
+        let name = node.text().unwrap_or_default().trim().to_string();
+        match node.attribute("domain") {
+            _ if name.is_empty() => {}
+            Some("post_tag") => tags.push(name),
+            Some("category") if name != "Uncategorized" => categories.push(name),
+            Some("series") => series.push(name),
+            _ => {}
+        }
+    }
+    if kind == "page" {
+        categories = vec![pages_category.to_string()];
+    }
+
+    let old_paths = ["link", "guid"]
+        .into_iter()
+        .filter_map(|name| text(item, name))
+        .filter_map(|url| path_of(&url))
+        .fold(Vec::new(), |mut paths, path| {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+            paths
+        });
+
+    let mut comments: Vec<Comment> = children(item, "comment")
+        .filter(|node| text(*node, "comment_approved").as_deref() == Some("1"))
+        .filter(|node| {
+            // Pingbacks and trackbacks are no comments.
+            matches!(
+                text(*node, "comment_type").as_deref(),
+                None | Some("") | Some("comment")
+            )
+        })
+        .filter_map(|node| {
+            Some(Comment {
+                external_id: text(node, "comment_id")?,
+                parent: text(node, "comment_parent").filter(|parent| parent != "0"),
+                author: text(node, "comment_user_id").and_then(|id| logins_by_id.get(&id).cloned()),
+                name: text(node, "comment_author").unwrap_or_default(),
+                email: text(node, "comment_author_email").filter(|email| !email.is_empty()),
+                content: html::to_markdown(&text(node, "comment_content").unwrap_or_default()),
+                created_at: date(node, "comment_date_gmt")
+                    .or_else(|| date(node, "comment_date"))?,
+            })
+        })
+        .collect();
+    // Parents before their answers.
+    comments.sort_by_key(|comment| comment.external_id.parse::<u64>().unwrap_or_default());
+
+    Ok(Some(Post {
+        source: format!("{} {}", kind, id),
+        external_id: Some(id),
+        author: text(item, "creator"),
+        title,
+        slug,
+        content,
+        summary,
+        created_at,
+        updated_at,
+        tags,
+        categories,
+        series,
+        draft,
+        old_paths,
+        comments,
+    }))
+}
+
+/// The elements named `name` right under `node`, whatever their namespace.
+fn children<'a, 'input>(
+    node: Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children()
+        .filter(move |child| child.is_element() && child.tag_name().name() == name)
+}
+
+fn text(node: Node, name: &'static str) -> Option<String> {
+    let text: String = children(node, name)
+        .next()?
+        .children()
+        .filter_map(|child| child.text())
+        .collect();
+    Some(text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+/// `content:encoded` and `excerpt:encoded` share their local name.
+fn encoded(node: Node, namespace: &str) -> Option<String> {
+    children(node, "encoded")
+        .find(|child| {
+            child
+                .tag_name()
+                .namespace()
+                .is_some_and(|ns| ns.contains(namespace))
+        })
+        .map(|child| child.children().filter_map(|child| child.text()).collect())
+}
+
+/// WordPress writes `0000-00-00 00:00:00` for the dates it does not know.
+fn date(node: Node, name: &'static str) -> Option<DateTimeUtc> {
+    text(node, name)
+        .filter(|date| !date.starts_with("0000"))
+        .and_then(|date| parse_date(&date))
+}
+
+/// Path and query of an url of the old site.
+fn path_of(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let path = &rest[rest.find('/')?..];
+    let path = path.split('#').next().unwrap_or_default();
+    Some(path.to_string()).filter(|path| path != "/")
+}
+
+fn percent_decode(value: &str) -> String {
+    percent_encoding::percent_decode_str(value)
+        .decode_utf8_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn wxr(items: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0"
+    xmlns:excerpt="http://wordpress.org/export/1.2/excerpt/"
+    xmlns:content="http://purl.org/rss/1.0/modules/content/"
+    xmlns:dc="http://purl.org/dc/elements/1.1/"
+    xmlns:wp="http://wordpress.org/export/1.2/">
+<channel>
+    <title>Old blog</title>
+    <link>https://old.example.com</link>
+    <wp:base_site_url>https://old.example.com/</wp:base_site_url>
+    <wp:author>
+        <wp:author_id>2</wp:author_id>
+        <wp:author_login><![CDATA[alice]]></wp:author_login>
+        <wp:author_email><![CDATA[alice@example.com]]></wp:author_email>
+        <wp:author_display_name><![CDATA[Alice]]></wp:author_display_name>
+    </wp:author>
+    <wp:author>
+        <wp:author_id>3</wp:author_id>
+        <wp:author_login><![CDATA[ghost]]></wp:author_login>
+    </wp:author>
+    {}
+</channel>
+</rss>"#,
+            items
+        )
+    }
+
+    fn item(id: u32, kind: &str, status: &str, extra: &str) -> String {
+        format!(
+            r#"<item>
+        <title>Post {id}</title>
+        <link>https://old.example.com/2024/05/post-{id}/</link>
+        <guid isPermaLink="false">https://old.example.com/?p={id}</guid>
+        <dc:creator><![CDATA[alice]]></dc:creator>
+        <content:encoded><![CDATA[<p>Body of <b>{id}</b></p>]]></content:encoded>
+        <excerpt:encoded><![CDATA[]]></excerpt:encoded>
+        <wp:post_id>{id}</wp:post_id>
+        <wp:post_date><![CDATA[2024-05-04 10:00:00]]></wp:post_date>
+        <wp:post_date_gmt><![CDATA[2024-05-04 08:00:00]]></wp:post_date_gmt>
+        <wp:post_modified_gmt><![CDATA[0000-00-00 00:00:00]]></wp:post_modified_gmt>
+        <wp:post_name><![CDATA[post-{id}]]></wp:post_name>
+        <wp:status><![CDATA[{status}]]></wp:status>
+        <wp:post_type><![CDATA[{kind}]]></wp:post_type>
+        {extra}
+    </item>"#
+        )
+    }
+
+    fn comment(id: u32, parent: u32, approved: u8, kind: &str) -> String {
+        format!(
+            r#"<wp:comment>
+            <wp:comment_id>{id}</wp:comment_id>
+            <wp:comment_author><![CDATA[Bob]]></wp:comment_author>
+            <wp:comment_author_email><![CDATA[]]></wp:comment_author_email>
+            <wp:comment_date_gmt><![CDATA[2024-05-05 09:00:00]]></wp:comment_date_gmt>
+            <wp:comment_content><![CDATA[Reply {id}]]></wp:comment_content>
+            <wp:comment_approved><![CDATA[{approved}]]></wp:comment_approved>
+            <wp:comment_type><![CDATA[{kind}]]></wp:comment_type>
+            <wp:comment_parent>{parent}</wp:comment_parent>
+            <wp:comment_user_id>2</wp:comment_user_id>
+        </wp:comment>"#
+        )
+    }
+
+    #[test]
+    fn posts_are_read_with_their_terms_and_comments() {
+        let extra = [
+            r#"<category domain="category" nicename="rust"><![CDATA[Rust]]></category>"#,
+            r#"<category domain="category" nicename="uncategorized"><![CDATA[Uncategorized]]></category>"#,
+            r#"<category domain="post_tag" nicename="async"><![CDATA[async]]></category>"#,
+            r#"<category domain="series" nicename="futures"><![CDATA[Futures]]></category>"#,
+            &comment(12, 11, 1, ""),
+            &comment(11, 0, 1, "comment"),
+            &comment(13, 0, 0, ""),
+            &comment(14, 0, 1, "pingback"),
+        ]
+        .concat();
+        let export = read(&wxr(&item(7, "post", "publish", &extra)), "Pages").unwrap();
+
+        assert_eq!(
+            export.source.as_deref(),
+            Some("wxr:https://old.example.com")
+        );
+        assert!(export.errors.is_empty());
+        let [author] = &export.authors[..] else {
+            panic!("the author without an email is dropped");
+        };
+        assert_eq!(author.login, "alice");
+        assert_eq!(author.display_name, "Alice");
+
+        let [post] = &export.posts[..] else {
+            panic!("one post expected");
+        };
+        assert_eq!(post.source, "post 7");
+        assert_eq!(post.external_id.as_deref(), Some("7"));
+        assert_eq!(post.author.as_deref(), Some("alice"));
+        assert_eq!(post.title, "Post 7");
+        assert_eq!(post.slug, "post-7");
+        assert_eq!(post.content, "Body of **7**");
+        assert_eq!(post.summary, "");
+        let date = Utc.with_ymd_and_hms(2024, 5, 4, 8, 0, 0).unwrap();
+        assert_eq!(post.created_at, date);
+        assert_eq!(post.updated_at, date);
+        assert_eq!(post.categories, ["Rust"]);
+        assert_eq!(post.tags, ["async"]);
+        assert_eq!(post.series, ["Futures"]);
+        assert!(!post.draft);
+        assert_eq!(post.old_paths, ["/2024/05/post-7/", "/?p=7"]);
+
+        let comments: Vec<_> = post
+            .comments
+            .iter()
+            .map(|comment| (comment.external_id.as_str(), comment.parent.as_deref()))
+            .collect();
+        assert_eq!(comments, [("11", None), ("12", Some("11"))]);
+        assert_eq!(post.comments[0].author.as_deref(), Some("alice"));
+        assert_eq!(post.comments[0].email, None);
+        assert_eq!(post.comments[0].content, "Reply 11");
+    }
+
+    #[test]
+    fn pages_go_into_their_category() {
+        let extra = r#"<category domain="category"><![CDATA[Rust]]></category>"#;
+        let export = read(&wxr(&item(8, "page", "publish", extra)), "Pages").unwrap();
+
+        assert_eq!(export.posts[0].source, "page 8");
+        assert_eq!(export.posts[0].categories, ["Pages"]);
+    }
+
+    #[test]
+    fn statuses_and_types() {
+        let items = [
+            item(1, "post", "draft", ""),
+            item(2, "post", "private", ""),
+            item(3, "post", "trash", ""),
+            item(4, "post", "auto-draft", ""),
+            item(5, "attachment", "inherit", ""),
+            item(6, "nav_menu_item", "publish", ""),
+        ]
+        .concat();
+        let export = read(&wxr(&items), "Pages").unwrap();
+
+        let posts: Vec<_> = export
+            .posts
+            .iter()
+            .map(|post| (post.slug.as_str(), post.draft))
+            .collect();
+        assert_eq!(posts, [("post-1", true), ("post-2", true)]);
+    }
+
+    #[test]
+    fn slugs_are_decoded_or_made_from_the_title() {
+        let encoded = item(1, "post", "publish", "").replace("post-1]]", "%e4%bd%a0%e5%a5%bd]]");
+        let untitled = item(2, "post", "draft", "")
+            .replace("post-2]]", "]]")
+            .replace("Post 2", "");
+        let export = read(&wxr(&[encoded, untitled].concat()), "Pages").unwrap();
+
+        assert_eq!(export.posts[0].slug, "你好");
+        assert_eq!(export.posts[1].slug, "2");
+    }
+
+    #[test]
+    fn posts_without_a_date_are_reported() {
+        let undated = item(9, "post", "publish", "")
+            .replace("2024-05-04 10:00:00", "0000-00-00 00:00:00")
+            .replace("2024-05-04 08:00:00", "0000-00-00 00:00:00");
+        let export = read(&wxr(&undated), "Pages").unwrap();
+
+        assert!(export.posts.is_empty());
+        assert_eq!(
+            export.errors,
+            [("post 9".to_string(), "The post has no date".to_string())]
+        );
+    }
+
+    #[test]
+    fn other_documents_are_rejected() {
+        assert_eq!(
+            read("<html><body/></html>", "Pages").unwrap_err(),
+            "Not a WXR file, there is no channel"
+        );
+        assert!(read("<rss>", "Pages").is_err());
+    }
+
+    #[test]
+    fn paths_of_the_old_urls() {
+        assert_eq!(
+            path_of("https://old.example.com/a/b?c=d#e").as_deref(),
+            Some("/a/b?c=d")
+        );
+        assert_eq!(path_of("https://old.example.com/"), None);
+        assert_eq!(path_of("https://old.example.com"), None);
+        assert_eq!(path_of("/relative").as_deref(), Some("/relative"));
+    }
+}
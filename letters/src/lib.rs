@@ -1,10 +1,25 @@
 mod api;
 pub mod app;
+mod backup;
+mod cache;
 pub mod cmd;
 pub mod conf;
+mod dataset;
 mod dto;
 mod error;
+mod export;
+mod feed;
 mod handlers;
+mod import;
 pub mod log;
+mod media;
+mod metrics;
+mod related;
+mod render;
 mod repos;
+mod search;
+mod seo;
+mod server;
+mod static_site;
+mod telemetry;
 mod utils;
@@ -1,42 +1,120 @@
-use std::env;
+use std::{env, sync::OnceLock};
 
+use anyhow::Context;
 use tracing_appender::{
     non_blocking::WorkerGuard,
     rolling::{RollingFileAppender, Rotation},
 };
-use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
-
-pub fn setup() -> Vec<WorkerGuard> {
-    let log_env = env::var("RUST_LOG").expect("Can't found 'RUST_LOG' env.");
-
-    let mut guards = Vec::new();
-    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| log_env.into());
-
-    let console_layer = tracing_subscriber::fmt::layer()
-        .pretty()
-        .with_writer(std::io::stderr);
-
-    let file_appender = RollingFileAppender::builder()
-        .max_log_files(5)
-        .rotation(Rotation::DAILY)
-        .filename_prefix("letters")
-        .filename_suffix("log")
-        .build("./logs")
-        .expect("initializing rolling file appender failed");
-    let (non_blocking_appender, file_guard) = tracing_appender::non_blocking(file_appender);
-    let file_layer = fmt::layer()
-        .with_target(true)
-        .with_ansi(false)
-        .with_level(true)
-        .with_writer(non_blocking_appender)
-        .with_filter(tracing_subscriber::filter::LevelFilter::TRACE);
-    guards.push(file_guard);
+use tracing_subscriber::{
+    fmt, layer::Layered, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Layer,
+    Registry,
+};
+
+use crate::{
+    conf::{Conf, LogFormat, LogRotation},
+    error::{AppError, AppResult},
+    telemetry::Exporter,
+};
+
+/// The registry under the filter, which every output is layered on.
+type Filtered = Layered<reload::Layer<EnvFilter, Registry>, Registry>;
+
+type Output = Box<dyn Layer<Filtered> + Send + Sync>;
+
+/// Swaps the filter of the running process, set once by `setup`.
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Flushes the files and exports the last spans once dropped.
+pub struct Guard {
+    _file: Option<WorkerGuard>,
+    _exporter: Exporter,
+}
+
+/// Log to the console, and to the files of `log.dir` when it is set, with
+/// the level of `RUST_LOG` or else of `log.level`.
+pub fn setup(conf: &Conf) -> anyhow::Result<Guard> {
+    let level = env::var("RUST_LOG")
+        .ok()
+        .filter(|level| !level.is_empty())
+        .unwrap_or_else(|| conf.log.level.clone());
+    let filter =
+        EnvFilter::try_new(&level).with_context(|| format!("Invalid log level {}", level))?;
+    let (filter, handle) = reload::Layer::new(filter);
+
+    let mut outputs = vec![output(conf.log.format, std::io::stderr, true)];
+    let mut file = None;
+    if let Some(dir) = &conf.log.dir {
+        let rotation = match conf.log.rotation {
+            LogRotation::Minutely => Rotation::MINUTELY,
+            LogRotation::Hourly => Rotation::HOURLY,
+            LogRotation::Daily => Rotation::DAILY,
+            LogRotation::Never => Rotation::NEVER,
+        };
+        let mut builder = RollingFileAppender::builder()
+            .rotation(rotation)
+            .filename_prefix("letters")
+            .filename_suffix("log");
+        if conf.log.max_files > 0 {
+            builder = builder.max_log_files(conf.log.max_files);
+        }
+        let appender = builder
+            .build(dir)
+            .with_context(|| format!("Open log directory {} failed", dir))?;
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+        outputs.push(output(conf.log.file_format, writer, false));
+        file = Some(guard);
+    }
+
+    let exporter = Exporter::new(&conf.telemetry)?;
+    let otel = tracing_opentelemetry::layer().with_tracer(exporter.tracer.clone());
 
     tracing_subscriber::registry()
-        .with(file_layer)
-        .with(console_layer)
-        .with(env_filter)
-        .init();
+        .with(filter)
+        .with(outputs)
+        .with(otel)
+        .try_init()
+        .context("Logging is already set up")?;
+    let _ = FILTER.set(handle);
+
+    Ok(Guard {
+        _file: file,
+        _exporter: exporter,
+    })
+}
+
+fn output<W>(format: LogFormat, writer: W, ansi: bool) -> Output
+where
+    W: for<'w> fmt::MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = fmt::layer().with_writer(writer).with_ansi(ansi);
+    match format {
+        LogFormat::Pretty => layer.pretty().boxed(),
+        LogFormat::Full => layer.boxed(),
+        LogFormat::Compact => layer.compact().boxed(),
+        LogFormat::Json => layer.json().boxed(),
+    }
+}
+
+/// The filter in use.
+pub fn level() -> AppResult<String> {
+    handle()?
+        .with_current(|filter| filter.to_string())
+        .map_err(|err| AppError::Unexpected(err.into()))
+}
+
+/// Replace the filter until the process ends, or the next call.
+pub fn set_level(level: &str) -> AppResult {
+    let filter =
+        EnvFilter::try_new(level).map_err(|err| AppError::InvalidInput(err.to_string()))?;
+    handle()?
+        .reload(filter)
+        .map_err(|err| AppError::Unexpected(err.into()))?;
+    tracing::info!(level, "Log level changed");
+    Ok(())
+}
 
-    guards
+fn handle() -> AppResult<&'static reload::Handle<EnvFilter, Registry>> {
+    FILTER
+        .get()
+        .ok_or_else(|| AppError::Unexpected(anyhow::anyhow!("Logging is not set up")))
 }
@@ -4,12 +4,12 @@ use letters::{cmd, conf::Conf, log};
 fn main() -> anyhow::Result<()> {
     dotenv().ok();
 
-    let _guard = log::setup();
-
     let cli = cmd::setup()?;
 
     let location = cli.config.clone().unwrap_or("".to_string());
-    let conf = Conf::new(&location, "LETTERS")?;
+    let conf = Conf::new(&location, &cli.profile, "LETTERS")?;
+
+    let _guard = log::setup(&conf)?;
 
     cmd::handle(&cli, &conf)?;
 
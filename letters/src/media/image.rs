@@ -0,0 +1,192 @@
+use std::io::Cursor;
+
+use hmac::{Hmac, Mac};
+use image::{
+    codecs::{jpeg::JpegEncoder, png::PngEncoder, webp::WebPEncoder},
+    imageops::FilterType,
+    DynamicImage, ImageDecoder, ImageReader,
+};
+use sha2::Sha256;
+
+use crate::{conf::Images, dto::media::ImageFormat, error::AppError, error::AppResult};
+
+pub struct Encoded {
+    pub format: ImageFormat,
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+/// An uploaded image, re-encoded without its metadata, with its variants.
+pub struct Processed {
+    pub original: Encoded,
+    pub blurhash: Option<String>,
+    pub variants: Vec<Encoded>,
+}
+
+/// Format of the uploads worth processing; animated GIFs and everything
+/// else are stored as they are.
+pub fn source_format(mime: &str) -> Option<ImageFormat> {
+    match mime {
+        "image/jpeg" => Some(ImageFormat::Jpeg),
+        "image/png" => Some(ImageFormat::Png),
+        "image/webp" => Some(ImageFormat::Webp),
+        _ => None,
+    }
+}
+
+pub fn process(data: &[u8], format: ImageFormat, conf: &Images) -> AppResult<Processed> {
+    let image = decode(data)?;
+
+    // Re-encoding drops EXIF, GPS included, the orientation being applied
+    // to the pixels beforehand.
+    let original = encode(&image, format, conf.quality)?;
+
+    let mut formats = vec![format];
+    if conf.webp && format != ImageFormat::Webp {
+        formats.push(ImageFormat::Webp);
+    }
+    if cfg!(feature = "avif") && conf.avif {
+        formats.push(ImageFormat::Avif);
+    }
+
+    let mut widths: Vec<u32> = conf
+        .variant_widths
+        .iter()
+        .copied()
+        .filter(|width| *width > 0 && *width < image.width())
+        .collect();
+    widths.sort_unstable();
+    widths.dedup();
+
+    let mut variants = Vec::with_capacity(widths.len() * formats.len());
+    for width in widths {
+        let resized = resize_to_width(&image, width);
+        for format in &formats {
+            variants.push(encode(&resized, *format, conf.quality)?);
+        }
+    }
+
+    Ok(Processed {
+        original,
+        blurhash: blurhash(&image),
+        variants,
+    })
+}
+
+/// Resize for the resize endpoint, images are never enlarged.
+pub fn resize(data: &[u8], width: u32, format: ImageFormat, quality: u8) -> AppResult<Encoded> {
+    let image = decode(data)?;
+    let image = if width < image.width() {
+        resize_to_width(&image, width)
+    } else {
+        image
+    };
+    encode(&image, format, quality)
+}
+
+/// Signature of a resize url of the media.
+pub fn sign(key: &str, media_id: i32, width: u32, format: ImageFormat) -> String {
+    let mac = mac(key, media_id, width, format).finalize().into_bytes();
+    mac.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn verify(key: &str, media_id: i32, width: u32, format: ImageFormat, signature: &str) -> bool {
+    let Some(signature) = unhex(signature) else {
+        return false;
+    };
+    mac(key, media_id, width, format)
+        .verify_slice(&signature)
+        .is_ok()
+}
+
+fn mac(key: &str, media_id: i32, width: u32, format: ImageFormat) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC takes keys of any size");
+    mac.update(format!("{}:{}:{}", media_id, width, format.as_str()).as_bytes());
+    mac
+}
+
+fn unhex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn decode(data: &[u8]) -> AppResult<DynamicImage> {
+    let invalid =
+        |err: image::ImageError| AppError::InvalidInput(format!("Invalid image: {}", err));
+
+    let mut decoder = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()?
+        .into_decoder()
+        .map_err(invalid)?;
+    let orientation = decoder.orientation().map_err(invalid)?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(invalid)?;
+    image.apply_orientation(orientation);
+
+    Ok(image)
+}
+
+fn resize_to_width(image: &DynamicImage, width: u32) -> DynamicImage {
+    let height = (image.height() as u64 * width as u64 / image.width() as u64).max(1) as u32;
+    image.resize_exact(width, height, FilterType::Lanczos3)
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat, quality: u8) -> AppResult<Encoded> {
+    let mut data = Vec::new();
+    let result = match format {
+        ImageFormat::Jpeg => image
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut data, quality)),
+        ImageFormat::Png => image.write_with_encoder(PngEncoder::new(&mut data)),
+        // The encoder of the image crate only writes lossless WebP.
+        ImageFormat::Webp => image
+            .to_rgba8()
+            .write_with_encoder(WebPEncoder::new_lossless(&mut data)),
+        ImageFormat::Avif => encode_avif(image, &mut data, quality),
+    };
+    result.map_err(|err| AppError::Unexpected(err.into()))?;
+
+    Ok(Encoded {
+        format,
+        width: image.width(),
+        height: image.height(),
+        data,
+    })
+}
+
+#[cfg(feature = "avif")]
+fn encode_avif(image: &DynamicImage, data: &mut Vec<u8>, quality: u8) -> image::ImageResult<()> {
+    use image::codecs::avif::AvifEncoder;
+
+    image
+        .to_rgba8()
+        .write_with_encoder(AvifEncoder::new_with_speed_quality(data, 8, quality))
+}
+
+#[cfg(not(feature = "avif"))]
+fn encode_avif(_image: &DynamicImage, _data: &mut Vec<u8>, _quality: u8) -> image::ImageResult<()> {
+    Err(image::ImageError::Unsupported(
+        image::error::UnsupportedError::from_format_and_kind(
+            image::ImageFormat::Avif.into(),
+            image::error::UnsupportedErrorKind::Format(image::ImageFormat::Avif.into()),
+        ),
+    ))
+}
+
+fn blurhash(image: &DynamicImage) -> Option<String> {
+    let thumbnail = image.thumbnail(32, 32).to_rgba8();
+    blurhash::encode(
+        4,
+        3,
+        thumbnail.width(),
+        thumbnail.height(),
+        thumbnail.as_raw(),
+    )
+    .ok()
+}
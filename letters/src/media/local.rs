@@ -0,0 +1,64 @@
+use std::{io::ErrorKind, path::PathBuf};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use super::Storage;
+use crate::error::AppResult;
+
+/// Files stored below a directory of the local filesystem.
+pub struct LocalStorage {
+    root: PathBuf,
+    public_url: String,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>, public_url: &str) -> Self {
+        Self {
+            root: root.into(),
+            public_url: public_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, data: Bytes, _mime: &str) -> AppResult<()> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        // Write aside and rename, so that readers never see a partial file.
+        let tmp = path.with_extension("part");
+        tokio::fs::write(&tmp, &data).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Option<Bytes>> {
+        match tokio::fs::read(self.root.join(key)).await {
+            Ok(data) => Ok(Some(data.into())),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        match tokio::fs::remove_file(self.root.join(key)).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> AppResult<()> {
+        match tokio::fs::remove_dir_all(self.root.join(prefix)).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_url, key)
+    }
+}
@@ -0,0 +1,268 @@
+pub mod image;
+mod local;
+mod s3;
+mod storage;
+
+use bytes::Bytes;
+use sea_orm::{DatabaseConnection, Set};
+use secrecy::ExposeSecret;
+use sha2::{Digest, Sha256};
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+pub use storage::Storage;
+
+use crate::{
+    conf::{Conf, StorageBackend},
+    dto::media::{ImageFormat, MediaResponse, MediaVariantResponse},
+    error::{AppError, AppResult},
+    repos::media,
+};
+use entity::media as MediaEntity;
+use entity::media_variant as MediaVariantEntity;
+
+/// Storage backend selected by the config.
+pub fn open_storage(conf: &Conf) -> AppResult<Box<dyn Storage>> {
+    let public_url = conf.media.public_url.as_deref();
+    let storage: Box<dyn Storage> = match conf.media.backend {
+        StorageBackend::Local => Box::new(LocalStorage::new(
+            &conf.media.local_dir,
+            &public_url
+                .map(str::to_string)
+                .unwrap_or_else(|| conf.site.url("/media")),
+        )),
+        StorageBackend::S3 => Box::new(S3Storage::new(&conf.media.s3, public_url)?),
+    };
+    Ok(storage)
+}
+
+/// Check and store an upload of `user_id`.
+///
+/// Files are stored under their SHA-256, so identical uploads share one
+/// object, and an owner uploading a file again gets the existing row back.
+/// Images are re-encoded without their metadata and get resized variants.
+pub async fn upload(
+    dbc: &DatabaseConnection,
+    storage: &dyn Storage,
+    conf: &Conf,
+    user_id: i32,
+    file_name: &str,
+    declared_mime: Option<&str>,
+    data: Bytes,
+) -> AppResult<MediaEntity::Model> {
+    if data.is_empty() {
+        return Err(AppError::InvalidInput("The file is empty".to_string()));
+    }
+    if data.len() as u64 > conf.media.max_size {
+        return Err(AppError::PayloadTooLarge(format!(
+            "The file is larger than {} bytes",
+            conf.media.max_size
+        )));
+    }
+
+    // Trust the content over what the client claims.
+    let sniffed = infer::get(&data);
+    let mime = sniffed
+        .map(|kind| kind.mime_type())
+        .or(declared_mime)
+        .unwrap_or("application/octet-stream");
+    if !conf
+        .media
+        .allowed_types
+        .iter()
+        .any(|allowed| allowed == mime)
+    {
+        return Err(AppError::UnsupportedMediaType(format!(
+            "The file type {} is not allowed",
+            mime
+        )));
+    }
+
+    let hash = hex(&Sha256::digest(&data));
+    if let Some(model) = media::read_by_hash(dbc, user_id, &hash).await? {
+        return Ok(model);
+    }
+
+    let model = MediaEntity::ActiveModel {
+        file_name: Set(file_name.to_string()),
+        mime: Set(mime.to_string()),
+        hash: Set(hash.clone()),
+        user_id: Set(user_id),
+        ..Default::default()
+    };
+
+    // Another owner uploaded it already, share the stored files.
+    if let Some(existing) = media::read_any_by_hash(dbc, &hash).await? {
+        let variants = media::read_variants(dbc, &[existing.id])
+            .await?
+            .remove(&existing.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|variant| MediaVariantEntity::ActiveModel {
+                width: Set(variant.width),
+                height: Set(variant.height),
+                mime: Set(variant.mime),
+                storage_key: Set(variant.storage_key),
+                size: Set(variant.size),
+                ..Default::default()
+            })
+            .collect();
+        let model = MediaEntity::ActiveModel {
+            storage_key: Set(existing.storage_key),
+            size: Set(existing.size),
+            width: Set(existing.width),
+            height: Set(existing.height),
+            blurhash: Set(existing.blurhash),
+            ..model
+        };
+        return media::create(dbc, model, variants).await;
+    }
+
+    let Some(format) = image::source_format(mime) else {
+        let extension = sniffed
+            .map(|kind| kind.extension().to_string())
+            .or_else(|| extension_of(file_name))
+            .unwrap_or_else(|| "bin".to_string());
+        let key = format!("{}/{}.{}", &hash[..2], hash, extension);
+        storage.put(&key, data.clone(), mime).await?;
+
+        let model = MediaEntity::ActiveModel {
+            storage_key: Set(key),
+            size: Set(data.len() as i64),
+            ..model
+        };
+        return media::create(dbc, model, vec![]).await;
+    };
+
+    let images = conf.media.images.clone();
+    let processed = tokio::task::spawn_blocking(move || image::process(&data, format, &images))
+        .await
+        .map_err(|err| AppError::Unexpected(err.into()))??;
+
+    let key = format!("{}/{}.{}", &hash[..2], hash, format.as_str());
+    let original = processed.original;
+    storage
+        .put(&key, original.data.clone().into(), format.mime())
+        .await?;
+
+    let mut variants = Vec::with_capacity(processed.variants.len());
+    for variant in processed.variants {
+        let key = format!(
+            "{}/w{}.{}",
+            derived_prefix(&hash),
+            variant.width,
+            variant.format.as_str()
+        );
+        let size = variant.data.len() as i64;
+        storage
+            .put(&key, variant.data.into(), variant.format.mime())
+            .await?;
+        variants.push(MediaVariantEntity::ActiveModel {
+            width: Set(variant.width as i32),
+            height: Set(variant.height as i32),
+            mime: Set(variant.format.mime().to_string()),
+            storage_key: Set(key),
+            size: Set(size),
+            ..Default::default()
+        });
+    }
+
+    let model = MediaEntity::ActiveModel {
+        storage_key: Set(key),
+        size: Set(original.data.len() as i64),
+        width: Set(Some(original.width as i32)),
+        height: Set(Some(original.height as i32)),
+        blurhash: Set(processed.blurhash),
+        ..model
+    };
+    media::create(dbc, model, variants).await
+}
+
+/// Delete a media of `user_id`, and its files once no other row uses them.
+pub async fn delete(
+    dbc: &DatabaseConnection,
+    storage: &dyn Storage,
+    user_id: i32,
+    media_id: i32,
+) -> AppResult<()> {
+    let model = media::delete_by_id(dbc, user_id, media_id).await?;
+    if media::count_by_hash(dbc, &model.hash).await? == 0 {
+        storage.delete(&model.storage_key).await?;
+        storage.delete_prefix(&derived_prefix(&model.hash)).await?;
+    }
+    Ok(())
+}
+
+/// Build the responses of the media, with the urls of their variants.
+pub async fn responses(
+    dbc: &DatabaseConnection,
+    storage: &dyn Storage,
+    models: Vec<MediaEntity::Model>,
+) -> AppResult<Vec<MediaResponse>> {
+    let ids: Vec<i32> = models.iter().map(|model| model.id).collect();
+    let mut variants = media::read_variants(dbc, &ids).await?;
+
+    Ok(models
+        .into_iter()
+        .map(|model| {
+            let url = storage.url(&model.storage_key);
+            let variants = variants
+                .remove(&model.id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|variant| MediaVariantResponse {
+                    width: variant.width,
+                    height: variant.height,
+                    mime: variant.mime,
+                    size: variant.size,
+                    url: storage.url(&variant.storage_key),
+                })
+                .collect();
+            MediaResponse::from((model, url, variants))
+        })
+        .collect())
+}
+
+/// Key signing the resize urls.
+pub fn signing_key(conf: &Conf) -> &str {
+    let key = conf.media.images.signing_key.expose_secret();
+    if key.is_empty() {
+        conf.auth.secret.expose_secret()
+    } else {
+        key
+    }
+}
+
+/// Signed url of the resize endpoint.
+pub fn resize_url(conf: &Conf, media_id: i32, width: u32, format: ImageFormat) -> String {
+    let signature = image::sign(signing_key(conf), media_id, width, format);
+    conf.site.url(&format!(
+        "/images/{}?w={}&f={}&s={}",
+        media_id,
+        width,
+        format.as_str(),
+        signature
+    ))
+}
+
+/// Key of the resize endpoint output cached in the storage.
+pub fn resized_key(hash: &str, width: u32, format: ImageFormat) -> String {
+    format!("{}/r{}.{}", derived_prefix(hash), width, format.as_str())
+}
+
+/// Files derived from an upload live below this prefix.
+fn derived_prefix(hash: &str) -> String {
+    format!("{}/{}", &hash[..2], hash)
+}
+
+fn extension_of(file_name: &str) -> Option<String> {
+    let (_, extension) = file_name.rsplit_once('.')?;
+    (!extension.is_empty()
+        && extension.len() <= 8
+        && extension.chars().all(|c| c.is_ascii_alphanumeric()))
+    .then(|| extension.to_ascii_lowercase())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
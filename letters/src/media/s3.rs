@@ -0,0 +1,91 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::TryStreamExt;
+use object_store::{
+    aws::{AmazonS3, AmazonS3Builder},
+    path::Path,
+    Attribute, Attributes, ObjectStore, PutOptions, PutPayload,
+};
+use secrecy::ExposeSecret;
+
+use super::Storage;
+use crate::{conf::S3 as S3Conf, error::AppResult};
+
+/// Files stored in a bucket of S3 or of an S3 compatible service.
+pub struct S3Storage {
+    store: AmazonS3,
+    public_url: String,
+}
+
+impl S3Storage {
+    pub fn new(conf: &S3Conf, public_url: Option<&str>) -> AppResult<Self> {
+        let mut builder = AmazonS3Builder::new()
+            .with_bucket_name(&conf.bucket)
+            .with_region(&conf.region)
+            .with_access_key_id(&conf.access_key_id)
+            .with_secret_access_key(conf.secret_access_key.expose_secret())
+            .with_allow_http(conf.allow_http);
+        if let Some(endpoint) = &conf.endpoint {
+            builder = builder.with_endpoint(endpoint);
+        }
+
+        let public_url = match (public_url, &conf.endpoint) {
+            (Some(url), _) => url.to_string(),
+            (None, Some(endpoint)) => format!("{}/{}", endpoint.trim_end_matches('/'), conf.bucket),
+            (None, None) => format!("https://{}.s3.{}.amazonaws.com", conf.bucket, conf.region),
+        };
+
+        Ok(Self {
+            store: builder.build()?,
+            public_url: public_url.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, data: Bytes, mime: &str) -> AppResult<()> {
+        let mut attributes = Attributes::new();
+        attributes.insert(Attribute::ContentType, mime.to_string().into());
+
+        self.store
+            .put_opts(
+                &Path::from(key),
+                PutPayload::from(data),
+                PutOptions {
+                    attributes,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Option<Bytes>> {
+        match self.store.get(&Path::from(key)).await {
+            Ok(result) => Ok(Some(result.bytes().await?)),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        match self.store.delete(&Path::from(key)).await {
+            Err(object_store::Error::NotFound { .. }) | Ok(()) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> AppResult<()> {
+        let prefix = Path::from(prefix);
+        let mut objects = self.store.list(Some(&prefix));
+        while let Some(meta) = objects.try_next().await? {
+            self.delete(meta.location.as_ref()).await?;
+        }
+        Ok(())
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_url, key)
+    }
+}
@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::error::AppResult;
+
+/// Where the uploaded files live. Keys are relative, `/` separated paths.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn put(&self, key: &str, data: Bytes, mime: &str) -> AppResult<()>;
+
+    async fn get(&self, key: &str) -> AppResult<Option<Bytes>>;
+
+    async fn delete(&self, key: &str) -> AppResult<()>;
+
+    /// Delete everything stored below `prefix`.
+    async fn delete_prefix(&self, prefix: &str) -> AppResult<()>;
+
+    /// Public url of a stored file.
+    fn url(&self, key: &str) -> String;
+}
@@ -0,0 +1,238 @@
+//! Counters of the server, written out in the Prometheus text format.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::app::AppState;
+
+/// Upper bounds of the latency buckets, in seconds.
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Route of the requests which matched none.
+const UNMATCHED: &str = "unmatched";
+
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// Requests by method and route
+    requests: Mutex<BTreeMap<(String, String), Requests>>,
+    articles_published: AtomicU64,
+    logins_failed: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct Requests {
+    statuses: BTreeMap<u16, u64>,
+    /// Requests which took at most the bound of the same index, each only
+    /// counted in its own bucket
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    seconds: f64,
+}
+
+/// Connections of the database pool.
+#[derive(Debug)]
+pub struct Pool {
+    pub size: u32,
+    pub idle: usize,
+    pub max: u32,
+}
+
+impl Metrics {
+    pub fn observe(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        let mut requests = self.requests.lock().unwrap();
+        let requests = requests
+            .entry((method.to_string(), route.to_string()))
+            .or_default();
+
+        *requests.statuses.entry(status).or_default() += 1;
+        let seconds = elapsed.as_secs_f64();
+        if let Some(bucket) = BUCKETS.iter().position(|bound| seconds <= *bound) {
+            requests.buckets[bucket] += 1;
+        }
+        requests.count += 1;
+        requests.seconds += seconds;
+    }
+
+    pub fn article_published(&self) {
+        self.articles_published.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn login_failed(&self) {
+        self.logins_failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Every metric, with the gauges read from the database by the caller.
+    pub fn render(&self, pool: Option<Pool>, comments: u64) -> String {
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "letters_http_requests_total",
+            "counter",
+            "Requests answered, by route and status.",
+        );
+        let requests = self.requests.lock().unwrap();
+        for ((method, route), requests) in requests.iter() {
+            for (status, count) in &requests.statuses {
+                let _ = writeln!(
+                    out,
+                    "letters_http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+                    method,
+                    escape(route),
+                    status,
+                    count
+                );
+            }
+        }
+
+        header(
+            &mut out,
+            "letters_http_request_duration_seconds",
+            "histogram",
+            "Time taken to answer the requests, by route.",
+        );
+        for ((method, route), requests) in requests.iter() {
+            let labels = format!("method=\"{}\",route=\"{}\"", method, escape(route));
+            let mut cumulative = 0;
+            for (bound, count) in BUCKETS.iter().zip(requests.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "letters_http_request_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                    labels, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "letters_http_request_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+                labels, requests.count
+            );
+            let _ = writeln!(
+                out,
+                "letters_http_request_duration_seconds_sum{{{}}} {}",
+                labels, requests.seconds
+            );
+            let _ = writeln!(
+                out,
+                "letters_http_request_duration_seconds_count{{{}}} {}",
+                labels, requests.count
+            );
+        }
+        drop(requests);
+
+        if let Some(pool) = pool {
+            header(
+                &mut out,
+                "letters_db_pool_connections",
+                "gauge",
+                "Open connections of the database pool, by state.",
+            );
+            let idle = pool.idle as u64;
+            let _ = writeln!(
+                out,
+                "letters_db_pool_connections{{state=\"idle\"}} {}",
+                idle
+            );
+            let _ = writeln!(
+                out,
+                "letters_db_pool_connections{{state=\"in_use\"}} {}",
+                (pool.size as u64).saturating_sub(idle)
+            );
+            header(
+                &mut out,
+                "letters_db_pool_max_connections",
+                "gauge",
+                "Connections the database pool may open.",
+            );
+            let _ = writeln!(out, "letters_db_pool_max_connections {}", pool.max);
+        }
+
+        header(
+            &mut out,
+            "letters_articles_published_total",
+            "counter",
+            "Articles created or updated as published.",
+        );
+        let _ = writeln!(
+            out,
+            "letters_articles_published_total {}",
+            self.articles_published.load(Ordering::Relaxed)
+        );
+        header(
+            &mut out,
+            "letters_comments",
+            "gauge",
+            "Comments in the database, trashed ones excluded.",
+        );
+        let _ = writeln!(out, "letters_comments {}", comments);
+        header(
+            &mut out,
+            "letters_logins_failed_total",
+            "counter",
+            "Authorizations refused for an unknown email or a wrong password.",
+        );
+        let _ = writeln!(
+            out,
+            "letters_logins_failed_total {}",
+            self.logins_failed.load(Ordering::Relaxed)
+        );
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Hand the route a request matched over to `track` through the response,
+/// the outer layers run before the routing.
+pub async fn mark_route(matched: MatchedPath, req: Request, next: Next) -> Response {
+    let mut response = next.run(req).await;
+    response.extensions_mut().insert(matched);
+    response
+}
+
+/// Count the request and the time taken to answer it.
+pub async fn track(State(state): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    let method = req.method().clone();
+    let start = Instant::now();
+    let response = next.run(req).await;
+
+    let route = response
+        .extensions()
+        .get::<MatchedPath>()
+        .map(MatchedPath::as_str)
+        .unwrap_or(UNMATCHED);
+    state.metrics.observe(
+        method.as_str(),
+        route,
+        response.status().as_u16(),
+        start.elapsed(),
+    );
+
+    response
+}
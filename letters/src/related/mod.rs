@@ -0,0 +1,322 @@
+mod tfidf;
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+};
+
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    dto::article::RelatedArticleResponse,
+    error::{AppError, AppResult, Resource, ResourceType},
+    repos::article,
+};
+use tfidf::{cosine, term_counts, Tfidf, Vector};
+
+const TAG_WEIGHT: f32 = 1.0;
+const CATEGORY_WEIGHT: f32 = 1.0;
+const SERIES_WEIGHT: f32 = 1.5;
+const CONTENT_WEIGHT: f32 = 3.0;
+
+/// Number of related articles computed and cached per article.
+pub const MAX_RELATED: usize = 20;
+
+struct Features {
+    category_id: i32,
+    tags: HashSet<i32>,
+    series: HashSet<i32>,
+    vector: Vector,
+}
+
+struct Corpus {
+    tfidf: Tfidf,
+    features: HashMap<i32, Features>,
+    articles: HashMap<i32, RelatedArticleResponse>,
+}
+
+/// "You might also like" recommendations, scored over the published articles.
+///
+/// The corpus and the per-article results are cached in memory until
+/// `invalidate` is called after an article, tag or series change.
+#[derive(Default)]
+pub struct RelatedArticles {
+    generation: AtomicU64,
+    corpus: RwLock<Option<Arc<Corpus>>>,
+    cache: RwLock<HashMap<i32, Arc<Vec<RelatedArticleResponse>>>>,
+}
+
+impl RelatedArticles {
+    pub fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        *self.corpus.write().unwrap() = None;
+        self.cache.write().unwrap().clear();
+    }
+
+    pub async fn read(
+        &self,
+        dbc: &DatabaseConnection,
+        article_id: i32,
+        limit: usize,
+    ) -> AppResult<Vec<RelatedArticleResponse>> {
+        let limit = limit.min(MAX_RELATED);
+        if let Some(related) = self.cache.read().unwrap().get(&article_id) {
+            return Ok(related.iter().take(limit).cloned().collect());
+        }
+
+        let generation = self.generation.load(Ordering::SeqCst);
+        let corpus = self.corpus(dbc, generation).await?;
+
+        let related = match corpus.features.get(&article_id) {
+            Some(features) => score(&corpus, article_id, features),
+            None => {
+                // Not published, score it against the corpus anyway.
+                let features = read_features(dbc, &corpus.tfidf, article_id).await?;
+                score(&corpus, article_id, &features)
+            }
+        };
+        let related = Arc::new(related);
+
+        if self.generation.load(Ordering::SeqCst) == generation {
+            self.cache
+                .write()
+                .unwrap()
+                .insert(article_id, Arc::clone(&related));
+        }
+
+        Ok(related.iter().take(limit).cloned().collect())
+    }
+
+    async fn corpus(&self, dbc: &DatabaseConnection, generation: u64) -> AppResult<Arc<Corpus>> {
+        if let Some(corpus) = self.corpus.read().unwrap().as_ref() {
+            return Ok(Arc::clone(corpus));
+        }
+
+        let corpus = Arc::new(build_corpus(dbc).await?);
+        if self.generation.load(Ordering::SeqCst) == generation {
+            *self.corpus.write().unwrap() = Some(Arc::clone(&corpus));
+        }
+
+        Ok(corpus)
+    }
+}
+
+async fn build_corpus(dbc: &DatabaseConnection) -> AppResult<Corpus> {
+    let mut counts = HashMap::new();
+    let mut features = HashMap::new();
+    let mut articles = HashMap::new();
+
+    let mut page = 0;
+    loop {
+        let models = article::read_published(dbc, page, 500).await?;
+        if models.is_empty() {
+            break;
+        }
+
+        let ids: Vec<i32> = models.iter().map(|model| model.id).collect();
+        let mut tags = article::read_tags_by_articles(dbc, &ids).await?;
+        let mut series: HashMap<i32, HashSet<i32>> = HashMap::new();
+        for (article_id, series_id) in article::read_series_ids_by_articles(dbc, &ids).await? {
+            series.entry(article_id).or_default().insert(series_id);
+        }
+
+        for model in models {
+            counts.insert(model.id, term_counts(&model.content));
+            features.insert(
+                model.id,
+                Features {
+                    category_id: model.category_id,
+                    tags: tags
+                        .remove(&model.id)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|tag| tag.id)
+                        .collect(),
+                    series: series.remove(&model.id).unwrap_or_default(),
+                    vector: Vector::new(),
+                },
+            );
+            articles.insert(model.id, RelatedArticleResponse::from(model));
+        }
+        page += 1;
+    }
+
+    let tfidf = Tfidf::fit(counts.values());
+    for (id, features) in features.iter_mut() {
+        features.vector = tfidf.vector(&counts[id]);
+    }
+
+    Ok(Corpus {
+        tfidf,
+        features,
+        articles,
+    })
+}
+
+async fn read_features(
+    dbc: &DatabaseConnection,
+    tfidf: &Tfidf,
+    article_id: i32,
+) -> AppResult<Features> {
+    let model = article::read_model_by_id(dbc, article_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(Resource {
+                r#type: ResourceType::Article,
+                detail: "Not found this article.".to_string(),
+            })
+        })?;
+    let tags = article::read_tags(dbc, article_id).await?;
+    let series = article::read_series_ids_by_articles(dbc, &[article_id]).await?;
+
+    Ok(Features {
+        category_id: model.category_id,
+        tags: tags.into_iter().map(|tag| tag.id).collect(),
+        series: series.into_iter().map(|(_, series_id)| series_id).collect(),
+        vector: tfidf.vector(&term_counts(&model.content)),
+    })
+}
+
+fn score(corpus: &Corpus, article_id: i32, target: &Features) -> Vec<RelatedArticleResponse> {
+    let mut scored: Vec<(f32, i32)> = corpus
+        .features
+        .iter()
+        .filter(|(id, _)| **id != article_id)
+        .map(|(id, other)| {
+            let shared_tags = target.tags.intersection(&other.tags).count() as f32;
+            let same_category = (target.category_id == other.category_id) as u8 as f32;
+            let same_series = !target.series.is_disjoint(&other.series) as u8 as f32;
+            let similarity = cosine(&target.vector, &other.vector);
+
+            let score = TAG_WEIGHT * shared_tags
+                + CATEGORY_WEIGHT * same_category
+                + SERIES_WEIGHT * same_series
+                + CONTENT_WEIGHT * similarity;
+            (score, *id)
+        })
+        .filter(|(score, _)| *score > 0.0)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored.truncate(MAX_RELATED);
+
+    scored
+        .into_iter()
+        .map(|(score, id)| RelatedArticleResponse {
+            score,
+            ..corpus.articles[&id].clone()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Id, category, tags, series and content of an article.
+    type Article<'a> = (i32, i32, &'a [i32], &'a [i32], &'a str);
+
+    fn corpus(articles: &[Article]) -> Corpus {
+        let counts: HashMap<i32, HashMap<String, usize>> = articles
+            .iter()
+            .map(|(id, .., content)| (*id, term_counts(content)))
+            .collect();
+        let tfidf = Tfidf::fit(counts.values());
+        let features = articles
+            .iter()
+            .map(|(id, category_id, tags, series, _)| {
+                (
+                    *id,
+                    Features {
+                        category_id: *category_id,
+                        tags: tags.iter().copied().collect(),
+                        series: series.iter().copied().collect(),
+                        vector: tfidf.vector(&counts[id]),
+                    },
+                )
+            })
+            .collect();
+        let responses = articles
+            .iter()
+            .map(|(id, ..)| {
+                (
+                    *id,
+                    RelatedArticleResponse {
+                        id: *id,
+                        title: format!("Article {}", id),
+                        slug: format!("article-{}", id),
+                        cover: String::new(),
+                        summary: String::new(),
+                        score: 0.0,
+                    },
+                )
+            })
+            .collect();
+
+        Corpus {
+            tfidf,
+            features,
+            articles: responses,
+        }
+    }
+
+    fn related(corpus: &Corpus, article_id: i32) -> Vec<(i32, f32)> {
+        score(corpus, article_id, &corpus.features[&article_id])
+            .into_iter()
+            .map(|article| (article.id, article.score))
+            .collect()
+    }
+
+    #[test]
+    fn shared_series_tags_and_category_add_up() {
+        let corpus = corpus(&[
+            (1, 1, &[1, 2], &[1], "alpha"),
+            (2, 1, &[1, 2], &[1], "beta"),
+            (3, 1, &[1], &[], "gamma"),
+            (4, 1, &[], &[], "delta"),
+            (5, 2, &[], &[], "epsilon"),
+        ]);
+
+        let related = related(&corpus, 1);
+
+        assert_eq!(
+            related,
+            [
+                (2, 2.0 * TAG_WEIGHT + CATEGORY_WEIGHT + SERIES_WEIGHT),
+                (3, TAG_WEIGHT + CATEGORY_WEIGHT),
+                (4, CATEGORY_WEIGHT),
+            ]
+        );
+    }
+
+    #[test]
+    fn similar_content_counts_across_categories() {
+        let corpus = corpus(&[
+            (1, 1, &[], &[], "pinning self referential futures"),
+            (2, 2, &[], &[], "futures are pinned before polling"),
+            (3, 3, &[], &[], "declarative macros"),
+        ]);
+
+        let related = related(&corpus, 1);
+
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].0, 2);
+        assert!(related[0].1 > 0.0 && related[0].1 <= CONTENT_WEIGHT);
+    }
+
+    #[test]
+    fn ties_are_broken_by_id_and_the_list_is_capped() {
+        let articles: Vec<Article> = (1..=MAX_RELATED as i32 + 5)
+            .map(|id| (id, 1, &[][..], &[][..], ""))
+            .collect();
+        let corpus = corpus(&articles);
+
+        let related = related(&corpus, 1);
+
+        assert_eq!(related.len(), MAX_RELATED);
+        assert_eq!(related[0].0, 2);
+        assert_eq!(related[MAX_RELATED - 1].0, MAX_RELATED as i32 + 1);
+    }
+}
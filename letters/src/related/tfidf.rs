@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use crate::search::tokenize;
+
+/// Only the heaviest terms of a document are kept in its vector.
+const MAX_TERMS: usize = 100;
+
+/// Sparse TF-IDF vector, normalized to unit length.
+pub type Vector = HashMap<String, f32>;
+
+/// Occurrences of every term of `text`.
+pub fn term_counts(text: &str) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for term in tokenize(text) {
+        *counts.entry(term).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Inverse document frequencies of a corpus.
+pub struct Tfidf {
+    docs: usize,
+    idf: HashMap<String, f32>,
+}
+
+impl Tfidf {
+    pub fn fit<'a>(corpus: impl Iterator<Item = &'a HashMap<String, usize>>) -> Self {
+        let mut docs = 0;
+        let mut df: HashMap<&str, usize> = HashMap::new();
+        for counts in corpus {
+            docs += 1;
+            for term in counts.keys() {
+                *df.entry(term).or_insert(0) += 1;
+            }
+        }
+
+        let idf = df
+            .into_iter()
+            .map(|(term, df)| (term.to_string(), Self::smooth_idf(docs, df)))
+            .collect();
+
+        Self { docs, idf }
+    }
+
+    pub fn vector(&self, counts: &HashMap<String, usize>) -> Vector {
+        let len: usize = counts.values().sum();
+        if len == 0 {
+            return Vector::new();
+        }
+
+        let mut weights: Vec<(&String, f32)> = counts
+            .iter()
+            .map(|(term, count)| {
+                let idf = self
+                    .idf
+                    .get(term)
+                    .copied()
+                    .unwrap_or_else(|| Self::smooth_idf(self.docs, 0));
+                (term, *count as f32 / len as f32 * idf)
+            })
+            .collect();
+        weights.sort_by(|a, b| b.1.total_cmp(&a.1));
+        weights.truncate(MAX_TERMS);
+
+        let norm = weights.iter().map(|(_, w)| w * w).sum::<f32>().sqrt();
+        weights
+            .into_iter()
+            .map(|(term, w)| (term.clone(), w / norm))
+            .collect()
+    }
+
+    fn smooth_idf(docs: usize, df: usize) -> f32 {
+        ((docs as f32 + 1.0) / (df as f32 + 1.0)).ln() + 1.0
+    }
+}
+
+/// Cosine similarity of two unit vectors.
+pub fn cosine(a: &Vector, b: &Vector) -> f32 {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    small
+        .iter()
+        .filter_map(|(term, w)| large.get(term).map(|v| w * v))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn term_counts_of_a_text() {
+        let counts = term_counts("Rust futures, Rust traits");
+
+        assert_eq!(counts["rust"], 2);
+        assert_eq!(counts["futures"], 1);
+        assert_eq!(counts.len(), 3);
+    }
+
+    #[test]
+    fn terms_of_every_document_weigh_less() {
+        let docs = [
+            term_counts("rust futures"),
+            term_counts("rust traits"),
+            term_counts("rust macros"),
+        ];
+        let tfidf = Tfidf::fit(docs.iter());
+
+        let vector = tfidf.vector(&docs[0]);
+
+        assert!(vector["futures"] > vector["rust"]);
+    }
+
+    #[test]
+    fn vectors_have_unit_length() {
+        let docs = [term_counts("pinning self referential futures")];
+        let tfidf = Tfidf::fit(docs.iter());
+
+        let vector = tfidf.vector(&docs[0]);
+
+        let norm: f32 = vector.values().map(|w| w * w).sum();
+        assert!((norm - 1.0).abs() < 1e-5);
+        assert!((cosine(&vector, &vector) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn an_empty_text_has_an_empty_vector() {
+        let tfidf = Tfidf::fit([term_counts("rust")].iter());
+
+        assert!(tfidf.vector(&term_counts("")).is_empty());
+    }
+
+    #[test]
+    fn cosine_ranks_the_closest_document_first() {
+        let docs = [
+            term_counts("pinning futures in async rust"),
+            term_counts("async futures and wakers"),
+            term_counts("declarative macros"),
+        ];
+        let tfidf = Tfidf::fit(docs.iter());
+        let vectors: Vec<Vector> = docs.iter().map(|doc| tfidf.vector(doc)).collect();
+
+        let close = cosine(&vectors[0], &vectors[1]);
+        let far = cosine(&vectors[0], &vectors[2]);
+
+        assert!(close > 0.0);
+        assert_eq!(far, 0.0);
+    }
+
+    #[test]
+    fn only_the_heaviest_terms_are_kept() {
+        let text: Vec<String> = (0..MAX_TERMS + 20).map(|i| format!("t{}", i)).collect();
+        let docs = [term_counts(&text.join(" "))];
+        let tfidf = Tfidf::fit(docs.iter());
+
+        assert_eq!(tfidf.vector(&docs[0]).len(), MAX_TERMS);
+    }
+}
@@ -0,0 +1,109 @@
+pub mod pages;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use blake2::{Blake2s256, Digest};
+use minijinja::{path_loader, Environment, Value};
+use serde::Serialize;
+
+use crate::{error::AppResult, utils::markdown};
+
+/// The templates of a theme directory.
+pub struct Theme {
+    env: Environment<'static>,
+    fingerprint: String,
+}
+
+impl Theme {
+    pub fn load(dir: &str) -> AppResult<Self> {
+        let mut env = Environment::new();
+        env.set_loader(path_loader(dir));
+        env.add_filter("markdown", |content: &str| {
+            Value::from_safe_string(markdown::to_html(content))
+        });
+
+        Ok(Self {
+            env,
+            fingerprint: fingerprint(Path::new(dir))?,
+        })
+    }
+
+    pub fn render<S: Serialize>(&self, name: &str, ctx: S) -> AppResult<String> {
+        Ok(self.env.get_template(name)?.render(ctx)?)
+    }
+
+    /// Digest of every file of the theme, changes whenever a template does.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+}
+
+/// The theme of the server rendered pages.
+///
+/// With `hot_reload` the templates are loaded again as soon as a file of the
+/// theme changes, which is meant for working on a theme in debug mode.
+pub struct SharedTheme {
+    dir: String,
+    hot_reload: bool,
+    theme: RwLock<Arc<Theme>>,
+}
+
+impl SharedTheme {
+    pub fn load(dir: &str, hot_reload: bool) -> AppResult<Self> {
+        Ok(Self {
+            dir: dir.to_string(),
+            hot_reload,
+            theme: RwLock::new(Arc::new(Theme::load(dir)?)),
+        })
+    }
+
+    pub fn get(&self) -> AppResult<Arc<Theme>> {
+        let theme = Arc::clone(&self.theme.read().unwrap());
+        if !self.hot_reload || fingerprint(Path::new(&self.dir))? == theme.fingerprint {
+            return Ok(theme);
+        }
+
+        tracing::debug!(dir = self.dir, "Reload theme");
+        let theme = Arc::new(Theme::load(&self.dir)?);
+        *self.theme.write().unwrap() = Arc::clone(&theme);
+        Ok(theme)
+    }
+}
+
+fn fingerprint(dir: &Path) -> AppResult<String> {
+    let mut files = Vec::new();
+    collect_files(dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = Blake2s256::new();
+    for file in files {
+        hasher.update(
+            file.strip_prefix(dir)
+                .unwrap_or(&file)
+                .to_string_lossy()
+                .as_bytes(),
+        );
+        hasher.update(fs::read(&file)?);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> AppResult<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
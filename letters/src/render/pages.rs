@@ -0,0 +1,244 @@
+//! Contexts of the html pages, shared by the static export and the server
+//! rendered frontend.
+
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use sea_orm::prelude::DateTimeUtc;
+use serde::Serialize;
+
+use crate::{
+    conf::Site,
+    dto::{
+        article::{ArticleInfo, PublishedArticle},
+        search::SearchHit,
+        series::ArticleSeries,
+        tag::ArticleTag,
+    },
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteContext {
+    pub title: String,
+    pub description: String,
+    pub language: String,
+    pub base_url: String,
+    /// Whether the search page is available, which it is not on a static export
+    pub search: bool,
+}
+
+impl From<&Site> for SiteContext {
+    fn from(site: &Site) -> Self {
+        Self {
+            title: site.title.clone(),
+            description: site.description.clone(),
+            language: site.language.clone(),
+            base_url: site.base_url.trim_end_matches('/').to_string(),
+            search: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Link {
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArticleItem {
+    pub id: i32,
+    pub title: String,
+    pub url: String,
+    pub cover: String,
+    pub summary: String,
+    /// `YYYY-MM-DD` of the creation
+    pub date: String,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+    pub author: Option<Link>,
+    pub category: Option<Link>,
+    pub tags: Vec<Link>,
+}
+
+impl ArticleItem {
+    pub fn new(article: &PublishedArticle, tags: &[ArticleTag]) -> Self {
+        Self {
+            id: article.id,
+            title: article.title.clone(),
+            url: Site::article_path(&article.slug),
+            cover: article.cover.clone(),
+            summary: article.summary.clone(),
+            date: article.created_at.format("%Y-%m-%d").to_string(),
+            created_at: article.created_at,
+            updated_at: article.updated_at,
+            author: article.author_name.as_ref().map(|name| Link {
+                name: name.clone(),
+                url: format!("/authors/{}", article.user_id),
+            }),
+            category: article.category_name.as_ref().map(|name| Link {
+                name: name.clone(),
+                url: format!("/categories/{}", article.category_id),
+            }),
+            tags: tags
+                .iter()
+                .map(|tag| Link {
+                    name: tag.name.clone(),
+                    url: format!("/tags/{}", tag.id),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeriesNav {
+    pub name: String,
+    pub url: String,
+    pub position: i32,
+    pub nums: i32,
+    pub prev: Option<Link>,
+    pub next: Option<Link>,
+}
+
+impl From<ArticleSeries> for SeriesNav {
+    fn from(series: ArticleSeries) -> Self {
+        let link = |article: ArticleInfo| Link {
+            name: article.title,
+            url: Site::article_path(&article.slug),
+        };
+
+        Self {
+            name: series.name,
+            url: format!("/series/{}", series.id),
+            position: series.position as i32,
+            nums: series.nums as i32,
+            prev: series.prev.map(link),
+            next: series.next.map(link),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArticlePage {
+    pub site: SiteContext,
+    pub article: ArticleItem,
+    /// Markdown, rendered by the `markdown` filter
+    pub content: String,
+    pub series: Vec<SeriesNav>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Pagination {
+    pub page: u64,
+    pub total_pages: u64,
+    pub prev_url: Option<String>,
+    pub next_url: Option<String>,
+}
+
+impl Pagination {
+    /// Page 1 lives at `base`, the next ones at `base/page/<n>`.
+    pub fn new(base: &str, page: u64, total_pages: u64) -> Self {
+        let base = base.trim_end_matches('/');
+        Self::with_urls(page, total_pages, |page| match page {
+            1 if base.is_empty() => "/".to_string(),
+            1 => base.to_string(),
+            _ => format!("{}/page/{}", base, page),
+        })
+    }
+
+    /// Pages of the search results, which keep the query in the url.
+    pub fn search(q: &str, page: u64, total_pages: u64) -> Self {
+        let q = utf8_percent_encode(q, NON_ALPHANUMERIC).to_string();
+        Self::with_urls(page, total_pages, |page| match page {
+            1 => format!("/search?q={}", q),
+            _ => format!("/search?q={}&page={}", q, page),
+        })
+    }
+
+    fn with_urls(page: u64, total_pages: u64, url: impl Fn(u64) -> String) -> Self {
+        Self {
+            page,
+            total_pages,
+            prev_url: (page > 1).then(|| url(page - 1)),
+            next_url: (page < total_pages).then(|| url(page + 1)),
+        }
+    }
+}
+
+/// The index and the pages of a category, tag, series or author.
+#[derive(Debug, Serialize)]
+pub struct ListPage {
+    pub site: SiteContext,
+    pub title: String,
+    pub description: Option<String>,
+    pub feed_url: Option<String>,
+    pub articles: Vec<ArticleItem>,
+    pub pagination: Option<Pagination>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArchiveMonth {
+    /// `YYYY-MM`
+    pub month: String,
+    pub articles: Vec<ArticleItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArchivePage {
+    pub site: SiteContext,
+    pub title: String,
+    pub months: Vec<ArchiveMonth>,
+}
+
+impl ArchivePage {
+    /// Group articles, sorted newest first, by month.
+    pub fn new(site: SiteContext, articles: Vec<ArticleItem>) -> Self {
+        let mut months: Vec<ArchiveMonth> = Vec::new();
+        for article in articles {
+            let month = article.created_at.format("%Y-%m").to_string();
+            match months.last_mut() {
+                Some(last) if last.month == month => last.articles.push(article),
+                _ => months.push(ArchiveMonth {
+                    month,
+                    articles: vec![article],
+                }),
+            }
+        }
+
+        Self {
+            site,
+            title: "Archive".to_string(),
+            months,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchItem {
+    pub article: ArticleItem,
+    /// Html of the matching part of the content, with the terms highlighted
+    pub highlight: String,
+}
+
+impl SearchItem {
+    pub fn new(article: ArticleItem, hit: &SearchHit) -> Self {
+        Self {
+            article,
+            highlight: hit.highlight.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchPage {
+    pub site: SiteContext,
+    pub q: String,
+    pub total: usize,
+    pub results: Vec<SearchItem>,
+    pub pagination: Option<Pagination>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotFoundPage {
+    pub site: SiteContext,
+    pub title: String,
+}
@@ -1,30 +1,58 @@
 use crate::{
     dto::{
-        article::{ArticleForQuery, ArticleRequest, UpdateArticleRequest},
+        article::{ArticleForQuery, ArticleRequest, PublishedArticle, UpdateArticleRequest},
+        feed::FeedScope,
+        tag::ArticleTag,
         Direction, PageQueryParam,
     },
     error::{AppError, AppResult, Resource, ResourceType},
+    repos::{import, redirect, series, tag},
 };
+use chrono::Utc;
 use entity::article as ArticleEntity;
 use entity::article_tag as ArticleTagEntity;
 use entity::category as CategoryEntity;
+use entity::comment as CommentEntity;
 use entity::series as SeriesEntity;
 use entity::series_article as SeriesArticleEntity;
 use entity::tag as TagEntity;
 use entity::user as UserEntity;
 use sea_orm::sea_query::Expr;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, JoinType::LeftJoin,
-    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, Set, TransactionTrait,
+    prelude::DateTimeUtc, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, JoinType::LeftJoin, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+    RelationTrait, Select, Set, TransactionTrait,
 };
-use std::cmp;
+use std::{cmp, collections::HashMap};
 
 pub async fn create(
     dbc: &DatabaseConnection,
     user_id: i32,
     article_data: &ArticleRequest,
 ) -> AppResult<i32> {
-    let model = ArticleEntity::ActiveModel {
+    insert(dbc, user_id, article_data, None).await
+}
+
+/// Create an article written elsewhere, keeping its original dates.
+pub async fn import(
+    dbc: &DatabaseConnection,
+    user_id: i32,
+    article_data: &ArticleRequest,
+    created_at: DateTimeUtc,
+    updated_at: DateTimeUtc,
+) -> AppResult<i32> {
+    insert(dbc, user_id, article_data, Some((created_at, updated_at))).await
+}
+
+async fn insert(
+    dbc: &DatabaseConnection,
+    user_id: i32,
+    article_data: &ArticleRequest,
+    dates: Option<(DateTimeUtc, DateTimeUtc)>,
+) -> AppResult<i32> {
+    let tx = dbc.begin().await?;
+
+    let mut model = ArticleEntity::ActiveModel {
         title: Set(article_data.title.to_owned()),
         slug: Set(article_data
             .slug
@@ -37,16 +65,35 @@ pub async fn create(
             .password_hash
             .to_owned()
             .unwrap_or("".to_string())),
-        source: Set(article_data.source.unwrap_or(0)),
+        source: Set(article_data.source.unwrap_or(0).into()),
         source_url: Set(article_data.source_url.to_owned().unwrap_or("".to_string())),
-        topping: Set(article_data.topping.unwrap_or(0)),
-        status: Set(article_data.status.unwrap_or(0)),
+        topping: Set(article_data.topping.unwrap_or(0).into()),
+        status: Set(article_data.status.unwrap_or(0).into()),
         category_id: Set(article_data.category_id.unwrap_or(0)),
         user_id: Set(user_id),
         ..Default::default()
+    };
+    if let Some((created_at, updated_at)) = dates {
+        model.created_at = Set(created_at);
+        model.updated_at = Set(updated_at);
+    }
+    let model = model.insert(&tx).await?;
+
+    if article_data.tag_ids.is_some() || article_data.tag_names.is_some() {
+        sync_tags(
+            &tx,
+            model.id,
+            article_data.tag_ids.as_deref().unwrap_or_default(),
+            article_data.tag_names.as_deref().unwrap_or_default(),
+        )
+        .await?;
     }
-    .insert(dbc)
-    .await?;
+
+    if let Some(series_ids) = &article_data.series_ids {
+        sync_series(&tx, model.id, series_ids).await?;
+    }
+
+    tx.commit().await?;
 
     Ok(model.id)
 }
@@ -56,9 +103,7 @@ pub async fn update(
     article_id: i32,
     update_data: &UpdateArticleRequest,
 ) -> AppResult<()> {
-    let model = ArticleEntity::Entity::find_by_id(article_id)
-        .one(dbc)
-        .await?;
+    let model = read_model_by_id(dbc, article_id).await?;
     if model.is_none() {
         return Err(AppError::NotFound(Resource {
             r#type: ResourceType::Article,
@@ -94,7 +139,7 @@ pub async fn update(
     }
 
     if let Some(source) = update_data.source {
-        model.source = Set(source);
+        model.source = Set(source.into());
     }
 
     if let Some(source_url) = &update_data.source_url {
@@ -102,23 +147,50 @@ pub async fn update(
     }
 
     if let Some(topping) = update_data.topping {
-        model.topping = Set(topping);
+        model.topping = Set(topping.into());
     }
 
     if let Some(status) = update_data.status {
-        model.status = Set(status);
+        model.status = Set(status.into());
     }
 
     if let Some(category_id) = update_data.category_id {
         model.category_id = Set(category_id);
     }
 
+    model.updated_at = Set(Utc::now());
     model.update(&tx).await?;
+
+    if update_data.tag_ids.is_some() || update_data.tag_names.is_some() {
+        sync_tags(
+            &tx,
+            article_id,
+            update_data.tag_ids.as_deref().unwrap_or_default(),
+            update_data.tag_names.as_deref().unwrap_or_default(),
+        )
+        .await?;
+    }
+
+    if let Some(series_ids) = &update_data.series_ids {
+        sync_series(&tx, article_id, series_ids).await?;
+    }
+
     tx.commit().await?;
 
     Ok(())
 }
 
+pub async fn read_model_by_id(
+    dbc: &DatabaseConnection,
+    article_id: i32,
+) -> AppResult<Option<ArticleEntity::Model>> {
+    let model = ArticleEntity::Entity::find_by_id(article_id)
+        .filter(ArticleEntity::Column::DeletedAt.is_null())
+        .one(dbc)
+        .await?;
+    Ok(model)
+}
+
 pub async fn read_by_id(
     dbc: &DatabaseConnection,
     article_id: i32,
@@ -128,15 +200,10 @@ pub async fn read_by_id(
         .column_as(UserEntity::Column::Username, "author_name")
         .column_as(CategoryEntity::Column::Id, "category_id")
         .column_as(CategoryEntity::Column::Name, "category_name")
-        .exprs([Expr::cust(
-            r#"GROUP_CONCAT(DISTINCT tag.name SEPARATOR " ") AS tag_names"#,
-        )])
         .join(LeftJoin, UserEntity::Relation::Article.def().rev())
         .join(LeftJoin, CategoryEntity::Relation::Article.def().rev())
-        .join(LeftJoin, ArticleTagEntity::Relation::Article.def().rev())
-        .join(LeftJoin, TagEntity::Relation::ArticleTag.def().rev())
-        .group_by(ArticleEntity::Column::Id)
-        .having(ArticleEntity::Column::Id.eq(article_id))
+        .filter(ArticleEntity::Column::Id.eq(article_id))
+        .filter(ArticleEntity::Column::DeletedAt.is_null())
         .into_model::<ArticleForQuery>()
         .one(dbc)
         .await?;
@@ -144,12 +211,223 @@ pub async fn read_by_id(
     Ok(model)
 }
 
+/// Tags attached to the article, trashed tags are left out.
+pub async fn read_tags(dbc: &DatabaseConnection, article_id: i32) -> AppResult<Vec<ArticleTag>> {
+    let models = TagEntity::Entity::find()
+        .select_only()
+        .column(TagEntity::Column::Id)
+        .column(TagEntity::Column::Name)
+        .join(LeftJoin, TagEntity::Relation::ArticleTag.def())
+        .filter(ArticleTagEntity::Column::ArticleId.eq(article_id))
+        .filter(TagEntity::Column::DeletedAt.is_null())
+        .order_by_asc(TagEntity::Column::Name)
+        .into_model::<ArticleTag>()
+        .all(dbc)
+        .await?;
+
+    Ok(models)
+}
+
+/// Tags of every given article, keyed by article id.
+pub async fn read_tags_by_articles(
+    dbc: &DatabaseConnection,
+    article_ids: &[i32],
+) -> AppResult<HashMap<i32, Vec<ArticleTag>>> {
+    let rows: Vec<(i32, i32, String)> = TagEntity::Entity::find()
+        .select_only()
+        .column(ArticleTagEntity::Column::ArticleId)
+        .column(TagEntity::Column::Id)
+        .column(TagEntity::Column::Name)
+        .join(LeftJoin, TagEntity::Relation::ArticleTag.def())
+        .filter(ArticleTagEntity::Column::ArticleId.is_in(article_ids.to_vec()))
+        .filter(TagEntity::Column::DeletedAt.is_null())
+        .order_by_asc(TagEntity::Column::Name)
+        .into_tuple()
+        .all(dbc)
+        .await?;
+
+    let mut tags: HashMap<i32, Vec<ArticleTag>> = HashMap::new();
+    for (article_id, id, name) in rows {
+        tags.entry(article_id)
+            .or_default()
+            .push(ArticleTag { id, name });
+    }
+
+    Ok(tags)
+}
+
+/// Series of every given article, as `(article_id, series_id)` pairs.
+pub async fn read_series_ids_by_articles(
+    dbc: &DatabaseConnection,
+    article_ids: &[i32],
+) -> AppResult<Vec<(i32, i32)>> {
+    let rows = SeriesArticleEntity::Entity::find()
+        .select_only()
+        .column(SeriesArticleEntity::Column::ArticleId)
+        .column(SeriesArticleEntity::Column::SeriesId)
+        .filter(SeriesArticleEntity::Column::ArticleId.is_in(article_ids.to_vec()))
+        .into_tuple()
+        .all(dbc)
+        .await?;
+
+    Ok(rows)
+}
+
+/// One page (0-based) of the published articles, oldest first.
+pub async fn read_published(
+    dbc: &DatabaseConnection,
+    page: u64,
+    per_page: u64,
+) -> AppResult<Vec<ArticleEntity::Model>> {
+    let models = ArticleEntity::Entity::find()
+        .filter(ArticleEntity::Column::Status.eq(1))
+        .filter(ArticleEntity::Column::DeletedAt.is_null())
+        .order_by_asc(ArticleEntity::Column::Id)
+        .paginate(dbc, per_page)
+        .fetch_page(page)
+        .await?;
+
+    Ok(models)
+}
+
+/// The latest published articles of a feed, newest first.
+pub async fn read_feed(
+    dbc: &DatabaseConnection,
+    scope: FeedScope,
+    limit: u64,
+) -> AppResult<Vec<PublishedArticle>> {
+    let models = select_scope(scope)
+        .order_by_desc(ArticleEntity::Column::CreatedAt)
+        .limit(limit)
+        .into_model::<PublishedArticle>()
+        .all(dbc)
+        .await?;
+
+    Ok(models)
+}
+
+/// A page of the published articles of a scope, with the number of pages.
+///
+/// Articles of a series are in reading order, the other scopes newest first.
+pub async fn read_published_page(
+    dbc: &DatabaseConnection,
+    scope: FeedScope,
+    page: u64,
+    per_page: u64,
+) -> AppResult<(Vec<PublishedArticle>, u64)> {
+    let mut select = select_scope(scope);
+    select = match scope {
+        FeedScope::Series(_) => select.order_by_asc(SeriesArticleEntity::Column::Position),
+        _ => select.order_by_desc(ArticleEntity::Column::CreatedAt),
+    };
+
+    let paginator = select
+        .order_by_desc(ArticleEntity::Column::Id)
+        .into_model::<PublishedArticle>()
+        .paginate(dbc, cmp::max(per_page, 1));
+    let total_pages = paginator.num_pages().await?;
+    let models = paginator.fetch_page(page.saturating_sub(1)).await?;
+
+    Ok((models, total_pages))
+}
+
+pub async fn read_published_by_slug(
+    dbc: &DatabaseConnection,
+    slug: &str,
+) -> AppResult<Option<PublishedArticle>> {
+    let model = select_published()
+        .filter(ArticleEntity::Column::Slug.eq(slug))
+        .into_model::<PublishedArticle>()
+        .one(dbc)
+        .await?;
+
+    Ok(model)
+}
+
+/// The published articles among `article_ids`, in the order of the ids.
+pub async fn read_published_by_ids(
+    dbc: &DatabaseConnection,
+    article_ids: &[i32],
+) -> AppResult<Vec<PublishedArticle>> {
+    if article_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut models: HashMap<i32, PublishedArticle> = select_published()
+        .filter(ArticleEntity::Column::Id.is_in(article_ids.to_vec()))
+        .into_model::<PublishedArticle>()
+        .all(dbc)
+        .await?
+        .into_iter()
+        .map(|model| (model.id, model))
+        .collect();
+
+    Ok(article_ids
+        .iter()
+        .filter_map(|id| models.remove(id))
+        .collect())
+}
+
+/// Every published article, newest first.
+pub async fn read_all_published(dbc: &DatabaseConnection) -> AppResult<Vec<PublishedArticle>> {
+    let models = select_published()
+        .order_by_desc(ArticleEntity::Column::CreatedAt)
+        .order_by_desc(ArticleEntity::Column::Id)
+        .into_model::<PublishedArticle>()
+        .all(dbc)
+        .await?;
+
+    Ok(models)
+}
+
+fn select_scope(scope: FeedScope) -> Select<ArticleEntity::Entity> {
+    let select = select_published();
+
+    match scope {
+        FeedScope::All => select,
+        FeedScope::Category(category_id) => {
+            select.filter(ArticleEntity::Column::CategoryId.eq(category_id))
+        }
+        FeedScope::Author(user_id) => select.filter(ArticleEntity::Column::UserId.eq(user_id)),
+        FeedScope::Tag(tag_id) => select
+            .join(LeftJoin, ArticleEntity::Relation::ArticleTag.def())
+            .filter(ArticleTagEntity::Column::TagId.eq(tag_id)),
+        FeedScope::Series(series_id) => select
+            .join(LeftJoin, ArticleEntity::Relation::SeriesArticle.def())
+            .filter(SeriesArticleEntity::Column::SeriesId.eq(series_id)),
+    }
+}
+
+fn select_published() -> Select<ArticleEntity::Entity> {
+    ArticleEntity::Entity::find()
+        .select_only()
+        .columns([
+            ArticleEntity::Column::Id,
+            ArticleEntity::Column::Title,
+            ArticleEntity::Column::Slug,
+            ArticleEntity::Column::Cover,
+            ArticleEntity::Column::Summary,
+            ArticleEntity::Column::Content,
+            ArticleEntity::Column::CreatedAt,
+            ArticleEntity::Column::UpdatedAt,
+            ArticleEntity::Column::UserId,
+            ArticleEntity::Column::CategoryId,
+        ])
+        .column_as(UserEntity::Column::Username, "author_name")
+        .column_as(CategoryEntity::Column::Name, "category_name")
+        .join(LeftJoin, UserEntity::Relation::Article.def().rev())
+        .join(LeftJoin, CategoryEntity::Relation::Article.def().rev())
+        .filter(ArticleEntity::Column::Status.eq(1))
+        .filter(ArticleEntity::Column::DeletedAt.is_null())
+}
+
 pub async fn _read_by_slug(
     dbc: &DatabaseConnection,
     slug: &str,
 ) -> AppResult<Option<ArticleEntity::Model>> {
     let model = ArticleEntity::Entity::find()
         .filter(ArticleEntity::Column::Slug.eq(slug))
+        .filter(ArticleEntity::Column::DeletedAt.is_null())
         .one(dbc)
         .await?;
 
@@ -160,7 +438,8 @@ pub async fn read_all(
     dbc: &DatabaseConnection,
     param: &PageQueryParam,
 ) -> AppResult<Vec<ArticleEntity::Model>> {
-    let mut select = ArticleEntity::Entity::find();
+    let mut select =
+        ArticleEntity::Entity::find().filter(ArticleEntity::Column::DeletedAt.is_null());
 
     match param.order_direction {
         Some(Direction::Desc) => {
@@ -184,8 +463,9 @@ pub async fn read_all_by_category(
     category_id: i32,
     param: &PageQueryParam,
 ) -> AppResult<Vec<ArticleEntity::Model>> {
-    let mut select =
-        ArticleEntity::Entity::find().filter(ArticleEntity::Column::CategoryId.eq(category_id));
+    let mut select = ArticleEntity::Entity::find()
+        .filter(ArticleEntity::Column::CategoryId.eq(category_id))
+        .filter(ArticleEntity::Column::DeletedAt.is_null());
 
     match param.order_direction {
         Some(Direction::Desc) => {
@@ -210,9 +490,9 @@ pub async fn read_all_by_tag(
     param: &PageQueryParam,
 ) -> AppResult<Vec<ArticleEntity::Model>> {
     let mut select = ArticleEntity::Entity::find()
-        .join(LeftJoin, ArticleTagEntity::Relation::Article.def())
-        .join(LeftJoin, TagEntity::Relation::ArticleTag.def().rev())
-        .having(TagEntity::Column::Id.eq(tag_id));
+        .join(LeftJoin, ArticleTagEntity::Relation::Article.def().rev())
+        .filter(ArticleEntity::Column::DeletedAt.is_null())
+        .filter(ArticleTagEntity::Column::TagId.eq(tag_id));
 
     match param.order_direction {
         Some(Direction::Desc) => {
@@ -231,22 +511,52 @@ pub async fn read_all_by_tag(
     Ok(models)
 }
 
+/// One page (0-based) of the articles of `user_id`, drafts included, in the
+/// order of the chapters when `series_id` is given and oldest first otherwise.
+pub async fn read_by_author(
+    dbc: &DatabaseConnection,
+    user_id: i32,
+    series_id: Option<i32>,
+    page: u64,
+    per_page: u64,
+) -> AppResult<Vec<ArticleEntity::Model>> {
+    let mut select = ArticleEntity::Entity::find()
+        .filter(ArticleEntity::Column::UserId.eq(user_id))
+        .filter(ArticleEntity::Column::DeletedAt.is_null());
+
+    select = match series_id {
+        Some(series_id) => select
+            .join(LeftJoin, SeriesArticleEntity::Relation::Article.def().rev())
+            .filter(SeriesArticleEntity::Column::SeriesId.eq(series_id))
+            .order_by_asc(SeriesArticleEntity::Column::Position),
+        None => select.order_by_asc(ArticleEntity::Column::CreatedAt),
+    };
+
+    let models = select
+        .order_by_asc(ArticleEntity::Column::Id)
+        .paginate(dbc, cmp::max(per_page, 1))
+        .fetch_page(page)
+        .await?;
+
+    Ok(models)
+}
+
 pub async fn read_all_by_series(
     dbc: &DatabaseConnection,
     series_id: i32,
     param: &PageQueryParam,
 ) -> AppResult<Vec<ArticleEntity::Model>> {
     let mut select = ArticleEntity::Entity::find()
-        .join(LeftJoin, SeriesArticleEntity::Relation::Article.def())
-        .join(LeftJoin, SeriesEntity::Relation::SeriesArticle.def().rev())
-        .having(SeriesEntity::Column::Id.eq(series_id));
+        .join(LeftJoin, SeriesArticleEntity::Relation::Article.def().rev())
+        .filter(SeriesArticleEntity::Column::SeriesId.eq(series_id))
+        .filter(ArticleEntity::Column::DeletedAt.is_null());
 
     match param.order_direction {
         Some(Direction::Desc) => {
-            select = select.order_by_desc(ArticleEntity::Column::CreatedAt);
+            select = select.order_by_desc(SeriesArticleEntity::Column::Position);
         }
         _ => {
-            select = select.order_by_asc(ArticleEntity::Column::CreatedAt);
+            select = select.order_by_asc(SeriesArticleEntity::Column::Position);
         }
     }
 
@@ -258,10 +568,251 @@ pub async fn read_all_by_series(
     Ok(models)
 }
 
+/// Replace the tags of the article with `tag_ids` plus the tags named in
+/// `tag_names`, unknown names are created on the fly.
+async fn sync_tags<C: ConnectionTrait>(
+    conn: &C,
+    article_id: i32,
+    tag_ids: &[i32],
+    tag_names: &[String],
+) -> AppResult<()> {
+    let mut target: Vec<i32> = Vec::new();
+
+    if !tag_ids.is_empty() {
+        let found: Vec<i32> = TagEntity::Entity::find()
+            .select_only()
+            .column(TagEntity::Column::Id)
+            .filter(TagEntity::Column::Id.is_in(tag_ids.to_vec()))
+            .filter(TagEntity::Column::DeletedAt.is_null())
+            .into_tuple()
+            .all(conn)
+            .await?;
+        if let Some(missing) = tag_ids.iter().find(|id| !found.contains(id)) {
+            return Err(AppError::InvalidInput(format!("Tag {} not found", missing)));
+        }
+        target.extend(found);
+    }
+
+    target.extend(tag::find_or_create_by_names(conn, tag_names).await?);
+    target.sort_unstable();
+    target.dedup();
+
+    let current: Vec<i32> = ArticleTagEntity::Entity::find()
+        .select_only()
+        .column(ArticleTagEntity::Column::TagId)
+        .filter(ArticleTagEntity::Column::ArticleId.eq(article_id))
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    ArticleTagEntity::Entity::delete_many()
+        .filter(ArticleTagEntity::Column::ArticleId.eq(article_id))
+        .filter(ArticleTagEntity::Column::TagId.is_not_in(target.clone()))
+        .exec(conn)
+        .await?;
+
+    let added: Vec<ArticleTagEntity::ActiveModel> = target
+        .into_iter()
+        .filter(|id| !current.contains(id))
+        .map(|tag_id| ArticleTagEntity::ActiveModel {
+            article_id: Set(article_id),
+            tag_id: Set(tag_id),
+            ..Default::default()
+        })
+        .collect();
+    if !added.is_empty() {
+        ArticleTagEntity::Entity::insert_many(added)
+            .exec(conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Replace the series the article belongs to with `series_ids`.
+async fn sync_series<C: ConnectionTrait>(
+    conn: &C,
+    article_id: i32,
+    series_ids: &[i32],
+) -> AppResult<()> {
+    let mut target = series_ids.to_vec();
+    target.sort_unstable();
+    target.dedup();
+
+    if !target.is_empty() {
+        let found: Vec<i32> = SeriesEntity::Entity::find()
+            .select_only()
+            .column(SeriesEntity::Column::Id)
+            .filter(SeriesEntity::Column::Id.is_in(target.clone()))
+            .into_tuple()
+            .all(conn)
+            .await?;
+        if let Some(missing) = target.iter().find(|id| !found.contains(id)) {
+            return Err(AppError::InvalidInput(format!(
+                "Series {} not found",
+                missing
+            )));
+        }
+    }
+
+    let current: Vec<i32> = SeriesArticleEntity::Entity::find()
+        .select_only()
+        .column(SeriesArticleEntity::Column::SeriesId)
+        .filter(SeriesArticleEntity::Column::ArticleId.eq(article_id))
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    let removed: Vec<i32> = current
+        .iter()
+        .copied()
+        .filter(|id| !target.contains(id))
+        .collect();
+    if !removed.is_empty() {
+        SeriesArticleEntity::Entity::delete_many()
+            .filter(SeriesArticleEntity::Column::ArticleId.eq(article_id))
+            .filter(SeriesArticleEntity::Column::SeriesId.is_in(removed.clone()))
+            .exec(conn)
+            .await?;
+    }
+
+    let added: Vec<i32> = target
+        .into_iter()
+        .filter(|id| !current.contains(id))
+        .collect();
+    for series_id in &added {
+        // Append as the last chapter.
+        let count = SeriesArticleEntity::Entity::find()
+            .filter(SeriesArticleEntity::Column::SeriesId.eq(*series_id))
+            .count(conn)
+            .await? as i32;
+        SeriesArticleEntity::ActiveModel {
+            series_id: Set(*series_id),
+            article_id: Set(article_id),
+            position: Set(count + 1),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await?;
+    }
+
+    for series_id in removed.into_iter().chain(added) {
+        series::renumber(conn, series_id).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn read_trash(dbc: &DatabaseConnection) -> AppResult<Vec<ArticleEntity::Model>> {
+    let models = ArticleEntity::Entity::find()
+        .filter(ArticleEntity::Column::DeletedAt.is_not_null())
+        .order_by_desc(ArticleEntity::Column::DeletedAt)
+        .all(dbc)
+        .await?;
+
+    Ok(models)
+}
+
+/// Move the article to the trash, it stays in database until purged.
 pub async fn delete_by_id(dbc: &DatabaseConnection, article_id: i32) -> AppResult<()> {
-    ArticleEntity::Entity::delete_by_id(article_id)
+    let now = Utc::now();
+    let result = ArticleEntity::Entity::update_many()
+        .col_expr(ArticleEntity::Column::DeletedAt, Expr::value(now))
+        .col_expr(ArticleEntity::Column::UpdatedAt, Expr::value(now))
+        .filter(ArticleEntity::Column::Id.eq(article_id))
+        .filter(ArticleEntity::Column::DeletedAt.is_null())
+        .exec(dbc)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(AppError::NotFound(Resource {
+            r#type: ResourceType::Article,
+            detail: "Not found this article".to_string(),
+        }));
+    }
+
+    Ok(())
+}
+
+pub async fn restore_by_id(dbc: &DatabaseConnection, article_id: i32) -> AppResult<()> {
+    let result = ArticleEntity::Entity::update_many()
+        .col_expr(
+            ArticleEntity::Column::DeletedAt,
+            Expr::value(Option::<DateTimeUtc>::None),
+        )
+        .col_expr(ArticleEntity::Column::UpdatedAt, Expr::value(Utc::now()))
+        .filter(ArticleEntity::Column::Id.eq(article_id))
+        .filter(ArticleEntity::Column::DeletedAt.is_not_null())
         .exec(dbc)
         .await?;
 
+    if result.rows_affected == 0 {
+        return Err(AppError::NotFound(Resource {
+            r#type: ResourceType::Article,
+            detail: "Not found this article in trash".to_string(),
+        }));
+    }
+
     Ok(())
 }
+
+/// Hard delete the articles trashed before `before`, together with their
+/// tag, series, comment and redirect rows. Returns the number of purged articles.
+pub async fn purge(dbc: &DatabaseConnection, before: DateTimeUtc) -> AppResult<u64> {
+    let ids: Vec<i32> = ArticleEntity::Entity::find()
+        .select_only()
+        .column(ArticleEntity::Column::Id)
+        .filter(ArticleEntity::Column::DeletedAt.lt(before))
+        .into_tuple()
+        .all(dbc)
+        .await?;
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    let tx = dbc.begin().await?;
+
+    let mut series_ids: Vec<i32> = SeriesArticleEntity::Entity::find()
+        .select_only()
+        .column(SeriesArticleEntity::Column::SeriesId)
+        .filter(SeriesArticleEntity::Column::ArticleId.is_in(ids.clone()))
+        .into_tuple()
+        .all(&tx)
+        .await?;
+    series_ids.sort_unstable();
+    series_ids.dedup();
+
+    ArticleTagEntity::Entity::delete_many()
+        .filter(ArticleTagEntity::Column::ArticleId.is_in(ids.clone()))
+        .exec(&tx)
+        .await?;
+    SeriesArticleEntity::Entity::delete_many()
+        .filter(SeriesArticleEntity::Column::ArticleId.is_in(ids.clone()))
+        .exec(&tx)
+        .await?;
+    for series_id in series_ids {
+        series::renumber(&tx, series_id).await?;
+    }
+    let comment_ids: Vec<i32> = CommentEntity::Entity::find()
+        .select_only()
+        .column(CommentEntity::Column::Id)
+        .filter(CommentEntity::Column::ArticleId.is_in(ids.clone()))
+        .into_tuple()
+        .all(&tx)
+        .await?;
+    import::delete_by_entities(&tx, import::COMMENT, comment_ids).await?;
+    CommentEntity::Entity::delete_many()
+        .filter(CommentEntity::Column::ArticleId.is_in(ids.clone()))
+        .exec(&tx)
+        .await?;
+    redirect::delete_by_articles(&tx, ids.clone()).await?;
+    import::delete_by_entities(&tx, import::ARTICLE, ids.clone()).await?;
+    let result = ArticleEntity::Entity::delete_many()
+        .filter(ArticleEntity::Column::Id.is_in(ids))
+        .exec(&tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(result.rows_affected)
+}
@@ -0,0 +1,81 @@
+use crate::error::AppResult;
+use sea_orm::{
+    ActiveModelTrait, ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait, IntoActiveModel,
+    Iterable, PaginatorTrait, PrimaryKeyToColumn, QueryOrder,
+};
+use serde::de::DeserializeOwned;
+
+/// Rows read from the database at once.
+const PAGE_SIZE: u64 = 500;
+
+/// Call `f` with every row of `E`, trashed ones included, ordered by primary
+/// key and read page by page so that a table is never held in memory.
+pub async fn for_each<E, F>(dbc: &DatabaseConnection, mut f: F) -> AppResult<u64>
+where
+    E: EntityTrait,
+    E::Model: Sync,
+    F: FnMut(E::Model) -> AppResult<()>,
+{
+    let mut query = E::find();
+    for key in E::PrimaryKey::iter() {
+        query = query.order_by_asc(key.into_column());
+    }
+
+    let mut paginator = query.paginate(dbc, PAGE_SIZE);
+    let mut rows = 0;
+    while let Some(models) = paginator.fetch_and_next().await? {
+        for model in models {
+            f(model)?;
+            rows += 1;
+        }
+    }
+
+    Ok(rows)
+}
+
+pub async fn is_empty<E: EntityTrait, C: ConnectionTrait>(conn: &C) -> AppResult<bool> {
+    Ok(E::find().one(conn).await?.is_none())
+}
+
+/// Insert rows as they were dumped, keeping their ids. The columns missing
+/// from a row are left to their database default.
+pub async fn load<A, C>(conn: &C, rows: Vec<serde_json::Value>) -> AppResult<()>
+where
+    A: ActiveModelTrait,
+    <A::Entity as EntityTrait>::Model: IntoActiveModel<A> + DeserializeOwned,
+    C: ConnectionTrait,
+{
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let models = rows
+        .into_iter()
+        .map(A::from_json)
+        .collect::<Result<Vec<_>, _>>()?;
+    A::Entity::insert_many(models)
+        .exec_without_returning(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Move the id sequence of `E` past the restored rows. Only PostgreSQL
+/// needs it, MySQL and SQLite follow the largest id by themselves.
+pub async fn reset_sequence<E, C>(conn: &C) -> AppResult<()>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    if conn.get_database_backend() != DbBackend::Postgres {
+        return Ok(());
+    }
+
+    let table = E::default().table_name().to_string();
+    conn.execute_unprepared(&format!(
+        r#"SELECT setval(pg_get_serial_sequence('"{table}"', 'id'), COALESCE(MAX("id"), 0) + 1, false) FROM "{table}""#
+    ))
+    .await?;
+
+    Ok(())
+}
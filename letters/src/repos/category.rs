@@ -2,17 +2,19 @@ use crate::{
     dto::category::{CategoryRequest, UpdateCategoryRequest},
     error::{AppError, AppResult, Resource, ResourceType},
 };
+use chrono::Utc;
+use entity::article as ArticleEntity;
 use entity::category as CategoryEntity;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set,
-    TransactionTrait,
+    prelude::DateTimeUtc, sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection,
+    EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
 };
 
 pub async fn create(dbc: &DatabaseConnection, category_data: &CategoryRequest) -> AppResult<i32> {
     let model = CategoryEntity::ActiveModel {
         name: Set(category_data.name.clone()),
         description: Set(category_data.description.clone()),
-        status: Set(category_data.status),
+        status: Set(category_data.status.into()),
         ..Default::default()
     }
     .insert(dbc)
@@ -46,7 +48,7 @@ pub async fn update(
     }
 
     if let Some(status) = update_data.status {
-        model.status = Set(status);
+        model.status = Set(status.into());
     }
 
     model.update(&tx).await?;
@@ -60,23 +62,133 @@ pub async fn read_by_id(
     category_id: i32,
 ) -> AppResult<Option<CategoryEntity::Model>> {
     let model = CategoryEntity::Entity::find_by_id(category_id)
+        .filter(CategoryEntity::Column::DeletedAt.is_null())
         .one(dbc)
         .await?;
     Ok(model)
 }
 
 pub async fn read_all(dbc: &DatabaseConnection) -> AppResult<Vec<CategoryEntity::Model>> {
-    let models = CategoryEntity::Entity::find().all(dbc).await?;
+    let models = CategoryEntity::Entity::find()
+        .filter(CategoryEntity::Column::DeletedAt.is_null())
+        .all(dbc)
+        .await?;
+    Ok(models)
+}
+
+/// The given categories, trashed ones included.
+pub async fn read_by_ids(
+    dbc: &DatabaseConnection,
+    category_ids: &[i32],
+) -> AppResult<Vec<CategoryEntity::Model>> {
+    let models = CategoryEntity::Entity::find()
+        .filter(CategoryEntity::Column::Id.is_in(category_ids.to_vec()))
+        .all(dbc)
+        .await?;
+    Ok(models)
+}
+
+pub async fn read_trash(dbc: &DatabaseConnection) -> AppResult<Vec<CategoryEntity::Model>> {
+    let models = CategoryEntity::Entity::find()
+        .filter(CategoryEntity::Column::DeletedAt.is_not_null())
+        .order_by_desc(CategoryEntity::Column::DeletedAt)
+        .all(dbc)
+        .await?;
     Ok(models)
 }
 
+/// Move the category to the trash, it stays in database until purged.
 pub async fn delete_by_id(dbc: &DatabaseConnection, category_id: i32) -> AppResult<()> {
-    CategoryEntity::Entity::delete_by_id(category_id)
+    let result = CategoryEntity::Entity::update_many()
+        .col_expr(CategoryEntity::Column::DeletedAt, Expr::value(Utc::now()))
+        .filter(CategoryEntity::Column::Id.eq(category_id))
+        .filter(CategoryEntity::Column::DeletedAt.is_null())
+        .exec(dbc)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(AppError::NotFound(Resource {
+            r#type: ResourceType::Category,
+            detail: "Not found this category".to_string(),
+        }));
+    }
+    Ok(())
+}
+
+pub async fn restore_by_id(dbc: &DatabaseConnection, category_id: i32) -> AppResult<()> {
+    let result = CategoryEntity::Entity::update_many()
+        .col_expr(
+            CategoryEntity::Column::DeletedAt,
+            Expr::value(Option::<DateTimeUtc>::None),
+        )
+        .filter(CategoryEntity::Column::Id.eq(category_id))
+        .filter(CategoryEntity::Column::DeletedAt.is_not_null())
         .exec(dbc)
         .await?;
+
+    if result.rows_affected == 0 {
+        return Err(AppError::NotFound(Resource {
+            r#type: ResourceType::Category,
+            detail: "Not found this category in trash".to_string(),
+        }));
+    }
     Ok(())
 }
 
+/// Hard delete the categories trashed before `before`. Categories still
+/// referenced by an article are kept until their articles are purged.
+pub async fn purge(dbc: &DatabaseConnection, before: DateTimeUtc) -> AppResult<u64> {
+    let models = CategoryEntity::Entity::find()
+        .filter(CategoryEntity::Column::DeletedAt.lt(before))
+        .all(dbc)
+        .await?;
+
+    let mut purged = 0;
+    for model in models {
+        let articles = ArticleEntity::Entity::find()
+            .filter(ArticleEntity::Column::CategoryId.eq(model.id))
+            .count(dbc)
+            .await?;
+        if articles > 0 {
+            continue;
+        }
+
+        CategoryEntity::Entity::delete_by_id(model.id)
+            .exec(dbc)
+            .await?;
+        purged += 1;
+    }
+
+    Ok(purged)
+}
+
+/// Look up a category by name, creating it as a published one when it does
+/// not exist and restoring it when it is trashed. Returns its id.
+pub async fn find_or_create_by_name(dbc: &DatabaseConnection, name: &str) -> AppResult<i32> {
+    let id = match check_name_exist(dbc, name).await? {
+        Some(model) if model.deleted_at.is_some() => {
+            let mut model: CategoryEntity::ActiveModel = model.into();
+            model.deleted_at = Set(None);
+            model.update(dbc).await?.id
+        }
+        Some(model) => model.id,
+        None => {
+            create(
+                dbc,
+                &CategoryRequest {
+                    name: name.to_string(),
+                    description: None,
+                    status: 1,
+                },
+            )
+            .await?
+        }
+    };
+
+    Ok(id)
+}
+
+/// Trashed categories are included, their name is still taken until purged.
 pub async fn check_name_exist(
     dbc: &DatabaseConnection,
     name: &str,
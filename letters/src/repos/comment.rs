@@ -0,0 +1,57 @@
+use crate::error::AppResult;
+use entity::comment as CommentEntity;
+use sea_orm::{
+    prelude::DateTimeUtc, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, Set,
+};
+
+/// Create a comment written elsewhere, keeping its date. `parent_id` is the
+/// comment answered, if any.
+pub async fn import(
+    dbc: &DatabaseConnection,
+    article_id: i32,
+    user_id: i32,
+    parent_id: Option<i32>,
+    content: &str,
+    created_at: DateTimeUtc,
+) -> AppResult<i32> {
+    let (top_comment_id, parent_comment_id) = match parent_id {
+        Some(parent_id) => {
+            let parent = CommentEntity::Entity::find_by_id(parent_id)
+                .one(dbc)
+                .await?;
+            let top = parent
+                .map(|parent| match parent.top_comment_id {
+                    0 => parent.id,
+                    top => top,
+                })
+                .unwrap_or(0);
+            (top, parent_id)
+        }
+        None => (0, 0),
+    };
+
+    let model = CommentEntity::ActiveModel {
+        content: Set(content.to_string()),
+        top_comment_id: Set(top_comment_id),
+        parent_comment_id: Set(parent_comment_id),
+        created_at: Set(created_at),
+        updated_at: Set(created_at),
+        article_id: Set(article_id),
+        user_id: Set(user_id),
+        ..Default::default()
+    }
+    .insert(dbc)
+    .await?;
+
+    Ok(model.id)
+}
+
+/// Comments not in the trash.
+pub async fn count(dbc: &DatabaseConnection) -> AppResult<u64> {
+    let count = CommentEntity::Entity::find()
+        .filter(CommentEntity::Column::DeletedAt.is_null())
+        .count(dbc)
+        .await?;
+    Ok(count)
+}
@@ -0,0 +1,56 @@
+use std::collections::HashSet;
+
+use crate::error::AppResult;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, QuerySelect,
+};
+
+/// The id after the largest one of `E`, 1 for an empty table.
+pub async fn next_id<E, C>(conn: &C, id: E::Column) -> AppResult<i32>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    let max: Option<Option<i32>> = E::find()
+        .select_only()
+        .column_as(id.max(), "id")
+        .into_tuple()
+        .one(conn)
+        .await?;
+
+    Ok(max.flatten().unwrap_or(0) + 1)
+}
+
+/// Every value of the text `column` of `E`, trashed rows included.
+pub async fn read_names<E, C>(conn: &C, column: E::Column) -> AppResult<HashSet<String>>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    let names: Vec<String> = E::find()
+        .select_only()
+        .column(column)
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    Ok(names.into_iter().collect())
+}
+
+/// Insert `models` with a single statement, ids included.
+pub async fn insert<A, C>(conn: &C, models: Vec<A>) -> AppResult<()>
+where
+    A: ActiveModelTrait,
+    <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
+    C: ConnectionTrait,
+{
+    if models.is_empty() {
+        return Ok(());
+    }
+
+    A::Entity::insert_many(models)
+        .exec_without_returning(conn)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use crate::error::AppResult;
+use entity::import_record as ImportRecordEntity;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    Set,
+};
+
+/// Kinds of the imported rows.
+pub const ARTICLE: &str = "article";
+pub const COMMENT: &str = "comment";
+
+/// Id of the row created for `external_id` by a previous import of `source`.
+pub async fn read_entity_id(
+    dbc: &DatabaseConnection,
+    source: &str,
+    kind: &str,
+    external_id: &str,
+) -> AppResult<Option<i32>> {
+    let model = ImportRecordEntity::Entity::find()
+        .filter(ImportRecordEntity::Column::Source.eq(source))
+        .filter(ImportRecordEntity::Column::Kind.eq(kind))
+        .filter(ImportRecordEntity::Column::ExternalId.eq(external_id))
+        .one(dbc)
+        .await?;
+
+    Ok(model.map(|model| model.entity_id))
+}
+
+pub async fn create<C: ConnectionTrait>(
+    conn: &C,
+    source: &str,
+    kind: &str,
+    external_id: &str,
+    entity_id: i32,
+) -> AppResult<()> {
+    ImportRecordEntity::ActiveModel {
+        source: Set(source.to_string()),
+        kind: Set(kind.to_string()),
+        external_id: Set(external_id.to_string()),
+        entity_id: Set(entity_id),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Forget the imported rows which are deleted, so that they are imported
+/// again by the next run.
+pub async fn delete_by_entities<C: ConnectionTrait>(
+    conn: &C,
+    kind: &str,
+    entity_ids: Vec<i32>,
+) -> AppResult<()> {
+    ImportRecordEntity::Entity::delete_many()
+        .filter(ImportRecordEntity::Column::Kind.eq(kind))
+        .filter(ImportRecordEntity::Column::EntityId.is_in(entity_ids))
+        .exec(conn)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use crate::{
+    dto::PageQueryParam,
+    error::{AppError, AppResult, Resource, ResourceType},
+};
+use entity::media as MediaEntity;
+use entity::media_variant as MediaVariantEntity;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use std::{cmp, collections::HashMap};
+
+/// Insert a media with its variants.
+pub async fn create(
+    dbc: &DatabaseConnection,
+    model: MediaEntity::ActiveModel,
+    variants: Vec<MediaVariantEntity::ActiveModel>,
+) -> AppResult<MediaEntity::Model> {
+    let tx = dbc.begin().await?;
+
+    let model = model.insert(&tx).await?;
+    for mut variant in variants {
+        variant.media_id = Set(model.id);
+        variant.insert(&tx).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(model)
+}
+
+pub async fn read_by_id(
+    dbc: &DatabaseConnection,
+    user_id: i32,
+    media_id: i32,
+) -> AppResult<Option<MediaEntity::Model>> {
+    let model = MediaEntity::Entity::find_by_id(media_id)
+        .filter(MediaEntity::Column::UserId.eq(user_id))
+        .one(dbc)
+        .await?;
+    Ok(model)
+}
+
+pub async fn read_by_hash(
+    dbc: &DatabaseConnection,
+    user_id: i32,
+    hash: &str,
+) -> AppResult<Option<MediaEntity::Model>> {
+    let model = MediaEntity::Entity::find()
+        .filter(MediaEntity::Column::UserId.eq(user_id))
+        .filter(MediaEntity::Column::Hash.eq(hash))
+        .one(dbc)
+        .await?;
+    Ok(model)
+}
+
+/// Any row of the stored file of `hash`, whoever the owner is.
+pub async fn read_any_by_hash(
+    dbc: &DatabaseConnection,
+    hash: &str,
+) -> AppResult<Option<MediaEntity::Model>> {
+    let model = MediaEntity::Entity::find()
+        .filter(MediaEntity::Column::Hash.eq(hash))
+        .one(dbc)
+        .await?;
+    Ok(model)
+}
+
+/// A media whoever the owner is, for serving it.
+pub async fn read_public_by_id(
+    dbc: &DatabaseConnection,
+    media_id: i32,
+) -> AppResult<Option<MediaEntity::Model>> {
+    let model = MediaEntity::Entity::find_by_id(media_id).one(dbc).await?;
+    Ok(model)
+}
+
+/// Variants of every given media, keyed by media id.
+pub async fn read_variants(
+    dbc: &DatabaseConnection,
+    media_ids: &[i32],
+) -> AppResult<HashMap<i32, Vec<MediaVariantEntity::Model>>> {
+    let models = MediaVariantEntity::Entity::find()
+        .filter(MediaVariantEntity::Column::MediaId.is_in(media_ids.to_vec()))
+        .order_by_asc(MediaVariantEntity::Column::Width)
+        .order_by_asc(MediaVariantEntity::Column::Id)
+        .all(dbc)
+        .await?;
+
+    let mut variants: HashMap<i32, Vec<MediaVariantEntity::Model>> = HashMap::new();
+    for model in models {
+        variants.entry(model.media_id).or_default().push(model);
+    }
+
+    Ok(variants)
+}
+
+/// Rows of every owner sharing the stored file of `hash`.
+pub async fn count_by_hash(dbc: &DatabaseConnection, hash: &str) -> AppResult<u64> {
+    let count = MediaEntity::Entity::find()
+        .filter(MediaEntity::Column::Hash.eq(hash))
+        .count(dbc)
+        .await?;
+    Ok(count)
+}
+
+pub async fn read_all_by_user(
+    dbc: &DatabaseConnection,
+    user_id: i32,
+    param: &PageQueryParam,
+) -> AppResult<Vec<MediaEntity::Model>> {
+    let models = MediaEntity::Entity::find()
+        .filter(MediaEntity::Column::UserId.eq(user_id))
+        .order_by_desc(MediaEntity::Column::CreatedAt)
+        .order_by_desc(MediaEntity::Column::Id)
+        .paginate(dbc, cmp::max(param.per_page.unwrap_or(10), 1))
+        .fetch_page(cmp::max(param.page.unwrap_or(1) - 1, 0))
+        .await?;
+
+    Ok(models)
+}
+
+/// Delete the row and its variants and hand it back, so that its files can
+/// be cleaned up.
+pub async fn delete_by_id(
+    dbc: &DatabaseConnection,
+    user_id: i32,
+    media_id: i32,
+) -> AppResult<MediaEntity::Model> {
+    let model = read_by_id(dbc, user_id, media_id).await?.ok_or_else(|| {
+        AppError::NotFound(Resource {
+            r#type: ResourceType::Media,
+            detail: "Not found this media.".to_string(),
+        })
+    })?;
+
+    let tx = dbc.begin().await?;
+    MediaVariantEntity::Entity::delete_many()
+        .filter(MediaVariantEntity::Column::MediaId.eq(media_id))
+        .exec(&tx)
+        .await?;
+    model.clone().delete(&tx).await?;
+    tx.commit().await?;
+
+    Ok(model)
+}
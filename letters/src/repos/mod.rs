@@ -1,5 +1,13 @@
 pub mod article;
+pub mod backup;
 pub mod category;
+pub mod comment;
+pub mod dataset;
+pub mod import;
+pub mod media;
+pub mod redirect;
 pub mod series;
+pub mod sitemap;
 pub mod tag;
+pub mod trash;
 pub mod user;
@@ -0,0 +1,48 @@
+use crate::error::AppResult;
+use entity::redirect as RedirectEntity;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    Set,
+};
+
+/// Redirect `path` to `article_id`, unless it already redirects somewhere.
+/// Returns whether the redirect was created.
+pub async fn create(dbc: &DatabaseConnection, path: &str, article_id: i32) -> AppResult<bool> {
+    if read_by_path(dbc, path).await?.is_some() {
+        return Ok(false);
+    }
+
+    RedirectEntity::ActiveModel {
+        path: Set(path.to_string()),
+        article_id: Set(article_id),
+        ..Default::default()
+    }
+    .insert(dbc)
+    .await?;
+
+    Ok(true)
+}
+
+pub async fn read_by_path(
+    dbc: &DatabaseConnection,
+    path: &str,
+) -> AppResult<Option<RedirectEntity::Model>> {
+    let model = RedirectEntity::Entity::find()
+        .filter(RedirectEntity::Column::Path.eq(path))
+        .one(dbc)
+        .await?;
+
+    Ok(model)
+}
+
+pub async fn delete_by_articles<C: ConnectionTrait>(
+    conn: &C,
+    article_ids: Vec<i32>,
+) -> AppResult<()> {
+    RedirectEntity::Entity::delete_many()
+        .filter(RedirectEntity::Column::ArticleId.is_in(article_ids))
+        .exec(conn)
+        .await?;
+
+    Ok(())
+}
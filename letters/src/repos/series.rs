@@ -1,16 +1,20 @@
 use crate::{
     dto::{
-        series::{SeriesRequest, UpdateSeriesRequest},
+        article::ArticleInfo,
+        series::{ArticleSeries, SeriesRequest, UpdateSeriesRequest},
         Direction, PageQueryParam,
     },
     error::{AppError, AppResult, Resource, ResourceType},
 };
+use entity::article as ArticleEntity;
 use entity::series as SeriesEntity;
+use entity::series_article as SeriesArticleEntity;
 use sea_orm::{
-    ActiveModelTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryOrder, Set,
-    TransactionTrait,
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, JoinType::InnerJoin, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+    RelationTrait, Set, TransactionTrait,
 };
-use std::cmp;
+use std::{cmp, collections::HashMap};
 
 pub async fn create(
     dbc: &DatabaseConnection,
@@ -21,9 +25,9 @@ pub async fn create(
         name: Set(series_data.name.to_owned()),
         description: Set(series_data.description.to_owned()),
         cover: Set(series_data.cover.to_owned().unwrap_or("".to_string())),
-        status: Set(series_data.status),
-        nums: Set(series_data.nums),
-        r#type: Set(series_data.r#type),
+        status: Set(series_data.status.into()),
+        nums: Set(0),
+        r#type: Set(series_data.r#type.into()),
         user_id: Set(user_id),
         ..Default::default()
     }
@@ -33,6 +37,31 @@ pub async fn create(
     Ok(model.id)
 }
 
+/// Look up a series by name, creating it as a published one of `user_id`
+/// when it does not exist. Returns its id.
+pub async fn find_or_create_by_name(
+    dbc: &DatabaseConnection,
+    user_id: i32,
+    name: &str,
+) -> AppResult<i32> {
+    if let Some(model) = read_by_name(dbc, name).await? {
+        return Ok(model.id);
+    }
+
+    create(
+        dbc,
+        user_id,
+        &SeriesRequest {
+            name: name.to_string(),
+            description: None,
+            cover: None,
+            status: 1,
+            r#type: 0,
+        },
+    )
+    .await
+}
+
 pub async fn update(
     dbc: &DatabaseConnection,
     series_id: i32,
@@ -62,15 +91,11 @@ pub async fn update(
     }
 
     if let Some(status) = update_data.status {
-        model.status = Set(status);
-    }
-
-    if let Some(nums) = update_data.nums {
-        model.nums = Set(nums);
+        model.status = Set(status.into());
     }
 
     if let Some(r#type) = update_data.r#type {
-        model.r#type = Set(r#type);
+        model.r#type = Set(r#type.into());
     }
 
     model.update(&tx).await?;
@@ -87,6 +112,17 @@ pub async fn read_by_id(
     Ok(model)
 }
 
+pub async fn read_by_name(
+    dbc: &DatabaseConnection,
+    name: &str,
+) -> AppResult<Option<SeriesEntity::Model>> {
+    let model = SeriesEntity::Entity::find()
+        .filter(SeriesEntity::Column::Name.eq(name))
+        .one(dbc)
+        .await?;
+    Ok(model)
+}
+
 pub async fn read_all(
     dbc: &DatabaseConnection,
     param: &PageQueryParam,
@@ -111,9 +147,308 @@ pub async fn read_all(
 }
 
 pub async fn delete_by_id(dbc: &DatabaseConnection, series_id: i32) -> AppResult<()> {
+    let tx = dbc.begin().await?;
+
+    SeriesArticleEntity::Entity::delete_many()
+        .filter(SeriesArticleEntity::Column::SeriesId.eq(series_id))
+        .exec(&tx)
+        .await?;
     SeriesEntity::Entity::delete_by_id(series_id)
-        .exec(dbc)
+        .exec(&tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Insert the article into the series at `position` (1-based), or append it
+/// when no position is given.
+pub async fn add_article(
+    dbc: &DatabaseConnection,
+    series_id: i32,
+    article_id: i32,
+    position: Option<i32>,
+) -> AppResult<()> {
+    if read_by_id(dbc, series_id).await?.is_none() {
+        return Err(AppError::NotFound(Resource {
+            r#type: ResourceType::Series,
+            detail: "Not found this series".to_string(),
+        }));
+    }
+
+    let article = ArticleEntity::Entity::find_by_id(article_id)
+        .filter(ArticleEntity::Column::DeletedAt.is_null())
+        .one(dbc)
+        .await?;
+    if article.is_none() {
+        return Err(AppError::NotFound(Resource {
+            r#type: ResourceType::Article,
+            detail: "Not found this article".to_string(),
+        }));
+    }
+
+    let tx = dbc.begin().await?;
+
+    let exists = SeriesArticleEntity::Entity::find()
+        .filter(SeriesArticleEntity::Column::SeriesId.eq(series_id))
+        .filter(SeriesArticleEntity::Column::ArticleId.eq(article_id))
+        .one(&tx)
+        .await?;
+    if exists.is_some() {
+        return Err(AppError::ResourceExistsError(Resource {
+            r#type: ResourceType::Article,
+            detail: "Article already in this series".to_string(),
+        }));
+    }
+
+    let count = SeriesArticleEntity::Entity::find()
+        .filter(SeriesArticleEntity::Column::SeriesId.eq(series_id))
+        .count(&tx)
+        .await? as i32;
+    let position = position.unwrap_or(count + 1).clamp(1, count + 1);
+
+    SeriesArticleEntity::Entity::update_many()
+        .col_expr(
+            SeriesArticleEntity::Column::Position,
+            Expr::col(SeriesArticleEntity::Column::Position).add(1),
+        )
+        .filter(SeriesArticleEntity::Column::SeriesId.eq(series_id))
+        .filter(SeriesArticleEntity::Column::Position.gte(position))
+        .exec(&tx)
+        .await?;
+
+    SeriesArticleEntity::ActiveModel {
+        series_id: Set(series_id),
+        article_id: Set(article_id),
+        position: Set(position),
+        ..Default::default()
+    }
+    .insert(&tx)
+    .await?;
+
+    renumber(&tx, series_id).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn remove_article(
+    dbc: &DatabaseConnection,
+    series_id: i32,
+    article_id: i32,
+) -> AppResult<()> {
+    let tx = dbc.begin().await?;
+
+    let result = SeriesArticleEntity::Entity::delete_many()
+        .filter(SeriesArticleEntity::Column::SeriesId.eq(series_id))
+        .filter(SeriesArticleEntity::Column::ArticleId.eq(article_id))
+        .exec(&tx)
+        .await?;
+    if result.rows_affected == 0 {
+        return Err(AppError::NotFound(Resource {
+            r#type: ResourceType::Article,
+            detail: "Not found this article in series".to_string(),
+        }));
+    }
+
+    renumber(&tx, series_id).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Reorder the chapters, `article_ids` must list every article of the series
+/// exactly once.
+pub async fn reorder_articles(
+    dbc: &DatabaseConnection,
+    series_id: i32,
+    article_ids: &[i32],
+) -> AppResult<()> {
+    let tx = dbc.begin().await?;
+
+    let mut current: Vec<i32> = SeriesArticleEntity::Entity::find()
+        .select_only()
+        .column(SeriesArticleEntity::Column::ArticleId)
+        .filter(SeriesArticleEntity::Column::SeriesId.eq(series_id))
+        .into_tuple()
+        .all(&tx)
+        .await?;
+    current.sort_unstable();
+
+    let mut requested = article_ids.to_vec();
+    requested.sort_unstable();
+    if current != requested {
+        return Err(AppError::InvalidInput(
+            "The article ids must list every article of the series exactly once".to_string(),
+        ));
+    }
+
+    for (i, article_id) in article_ids.iter().enumerate() {
+        SeriesArticleEntity::Entity::update_many()
+            .col_expr(
+                SeriesArticleEntity::Column::Position,
+                Expr::value(i as i32 + 1),
+            )
+            .filter(SeriesArticleEntity::Column::SeriesId.eq(series_id))
+            .filter(SeriesArticleEntity::Column::ArticleId.eq(*article_id))
+            .exec(&tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Close the gaps in the chapter positions of the series and store the
+/// number of chapters in `series.nums`.
+pub async fn renumber<C: ConnectionTrait>(conn: &C, series_id: i32) -> AppResult<()> {
+    let models = SeriesArticleEntity::Entity::find()
+        .filter(SeriesArticleEntity::Column::SeriesId.eq(series_id))
+        .order_by_asc(SeriesArticleEntity::Column::Position)
+        .order_by_asc(SeriesArticleEntity::Column::Id)
+        .all(conn)
+        .await?;
+
+    let nums = models.len() as i32;
+    for (i, model) in models.into_iter().enumerate() {
+        let position = i as i32 + 1;
+        if model.position == position {
+            continue;
+        }
+
+        let mut model: SeriesArticleEntity::ActiveModel = model.into();
+        model.position = Set(position);
+        model.update(conn).await?;
+    }
+
+    SeriesEntity::Entity::update_many()
+        .col_expr(SeriesEntity::Column::Nums, Expr::value(nums))
+        .filter(SeriesEntity::Column::Id.eq(series_id))
+        .exec(conn)
         .await?;
 
     Ok(())
 }
+
+/// Every series, oldest first.
+pub async fn read_all_unpaged(dbc: &DatabaseConnection) -> AppResult<Vec<SeriesEntity::Model>> {
+    let models = SeriesEntity::Entity::find()
+        .order_by_asc(SeriesEntity::Column::CreatedAt)
+        .all(dbc)
+        .await?;
+    Ok(models)
+}
+
+/// Every chapter of every series, in order.
+pub async fn read_all_chapters(
+    dbc: &DatabaseConnection,
+) -> AppResult<Vec<SeriesArticleEntity::Model>> {
+    let models = SeriesArticleEntity::Entity::find()
+        .order_by_asc(SeriesArticleEntity::Column::SeriesId)
+        .order_by_asc(SeriesArticleEntity::Column::Position)
+        .all(dbc)
+        .await?;
+    Ok(models)
+}
+
+/// Series of every given article as `(series_id, name, position)`, keyed by
+/// article id.
+pub async fn read_chapters_by_articles(
+    dbc: &DatabaseConnection,
+    article_ids: &[i32],
+) -> AppResult<HashMap<i32, Vec<(i32, String, i32)>>> {
+    let rows: Vec<(i32, i32, String, i32)> = SeriesArticleEntity::Entity::find()
+        .select_only()
+        .column(SeriesArticleEntity::Column::ArticleId)
+        .column(SeriesArticleEntity::Column::SeriesId)
+        .column(SeriesEntity::Column::Name)
+        .column(SeriesArticleEntity::Column::Position)
+        .join(InnerJoin, SeriesArticleEntity::Relation::Series.def())
+        .filter(SeriesArticleEntity::Column::ArticleId.is_in(article_ids.to_vec()))
+        .order_by_asc(SeriesArticleEntity::Column::SeriesId)
+        .into_tuple()
+        .all(dbc)
+        .await?;
+
+    let mut chapters: HashMap<i32, Vec<(i32, String, i32)>> = HashMap::new();
+    for (article_id, series_id, name, position) in rows {
+        chapters
+            .entry(article_id)
+            .or_default()
+            .push((series_id, name, position));
+    }
+
+    Ok(chapters)
+}
+
+/// Every series the article belongs to, with its position and the previous
+/// and next chapters, which are published ones only with `published_only`.
+pub async fn read_by_article(
+    dbc: &DatabaseConnection,
+    article_id: i32,
+    published_only: bool,
+) -> AppResult<Vec<ArticleSeries>> {
+    let models = SeriesArticleEntity::Entity::find()
+        .find_also_related(SeriesEntity::Entity)
+        .filter(SeriesArticleEntity::Column::ArticleId.eq(article_id))
+        .order_by_asc(SeriesArticleEntity::Column::SeriesId)
+        .all(dbc)
+        .await?;
+
+    let mut resp = Vec::with_capacity(models.len());
+    for (chapter, series) in models {
+        let Some(series) = series else {
+            continue;
+        };
+
+        let prev = neighbour(dbc, series.id, chapter.position, true, published_only).await?;
+        let next = neighbour(dbc, series.id, chapter.position, false, published_only).await?;
+        resp.push(ArticleSeries {
+            id: series.id,
+            name: series.name,
+            nums: series.nums as u32,
+            position: chapter.position as u32,
+            prev,
+            next,
+        });
+    }
+
+    Ok(resp)
+}
+
+/// The closest chapter before or after `position`, skipping trashed articles.
+async fn neighbour(
+    dbc: &DatabaseConnection,
+    series_id: i32,
+    position: i32,
+    before: bool,
+    published_only: bool,
+) -> AppResult<Option<ArticleInfo>> {
+    let mut select = ArticleEntity::Entity::find()
+        .select_only()
+        .column(ArticleEntity::Column::Id)
+        .column(ArticleEntity::Column::Title)
+        .column(ArticleEntity::Column::Slug)
+        .join(InnerJoin, ArticleEntity::Relation::SeriesArticle.def())
+        .filter(SeriesArticleEntity::Column::SeriesId.eq(series_id))
+        .filter(ArticleEntity::Column::DeletedAt.is_null());
+    if published_only {
+        select = select.filter(ArticleEntity::Column::Status.eq(1));
+    }
+
+    if before {
+        select = select
+            .filter(SeriesArticleEntity::Column::Position.lt(position))
+            .order_by_desc(SeriesArticleEntity::Column::Position);
+    } else {
+        select = select
+            .filter(SeriesArticleEntity::Column::Position.gt(position))
+            .order_by_asc(SeriesArticleEntity::Column::Position);
+    }
+
+    let model = select.into_model::<ArticleInfo>().one(dbc).await?;
+
+    Ok(model)
+}
@@ -0,0 +1,95 @@
+use crate::{dto::seo::SitemapEntry, error::AppResult};
+use entity::article as ArticleEntity;
+use entity::category as CategoryEntity;
+use entity::series as SeriesEntity;
+use entity::tag as TagEntity;
+use entity::user as UserEntity;
+use sea_orm::{
+    prelude::DateTimeUtc, ColumnTrait, DatabaseConnection, EntityTrait, JoinType::InnerJoin,
+    QueryFilter, QueryOrder, QuerySelect, RelationTrait,
+};
+
+/// Every public page of the site: the home page, the published articles,
+/// the categories, tags and series, and the authors of published articles.
+pub async fn read_entries(dbc: &DatabaseConnection) -> AppResult<Vec<SitemapEntry>> {
+    let articles: Vec<(String, DateTimeUtc)> = ArticleEntity::Entity::find()
+        .select_only()
+        .column(ArticleEntity::Column::Slug)
+        .column(ArticleEntity::Column::UpdatedAt)
+        .filter(ArticleEntity::Column::Status.eq(1))
+        .filter(ArticleEntity::Column::DeletedAt.is_null())
+        .order_by_asc(ArticleEntity::Column::Id)
+        .into_tuple()
+        .all(dbc)
+        .await?;
+
+    let categories: Vec<(i32, DateTimeUtc)> = CategoryEntity::Entity::find()
+        .select_only()
+        .column(CategoryEntity::Column::Id)
+        .column(CategoryEntity::Column::UpdatedAt)
+        .filter(CategoryEntity::Column::DeletedAt.is_null())
+        .order_by_asc(CategoryEntity::Column::Id)
+        .into_tuple()
+        .all(dbc)
+        .await?;
+
+    let tags: Vec<(i32, DateTimeUtc)> = TagEntity::Entity::find()
+        .select_only()
+        .column(TagEntity::Column::Id)
+        .column(TagEntity::Column::UpdatedAt)
+        .filter(TagEntity::Column::DeletedAt.is_null())
+        .order_by_asc(TagEntity::Column::Id)
+        .into_tuple()
+        .all(dbc)
+        .await?;
+
+    let series: Vec<(i32, DateTimeUtc)> = SeriesEntity::Entity::find()
+        .select_only()
+        .column(SeriesEntity::Column::Id)
+        .column(SeriesEntity::Column::UpdatedAt)
+        .order_by_asc(SeriesEntity::Column::Id)
+        .into_tuple()
+        .all(dbc)
+        .await?;
+
+    let authors: Vec<(i32, DateTimeUtc)> = UserEntity::Entity::find()
+        .select_only()
+        .column(UserEntity::Column::Id)
+        .column(UserEntity::Column::UpdatedAt)
+        .distinct()
+        .join(InnerJoin, UserEntity::Relation::Article.def())
+        .filter(ArticleEntity::Column::Status.eq(1))
+        .filter(ArticleEntity::Column::DeletedAt.is_null())
+        .filter(UserEntity::Column::DeletedAt.is_null())
+        .order_by_asc(UserEntity::Column::Id)
+        .into_tuple()
+        .all(dbc)
+        .await?;
+
+    let mut entries = Vec::with_capacity(
+        1 + articles.len() + categories.len() + tags.len() + series.len() + authors.len(),
+    );
+    entries.push(SitemapEntry {
+        path: "/".to_string(),
+        lastmod: articles.iter().map(|(_, updated_at)| *updated_at).max(),
+    });
+
+    let pages = [
+        ("/categories", categories),
+        ("/tags", tags),
+        ("/series", series),
+        ("/authors", authors),
+    ];
+    entries.extend(articles.into_iter().map(|(slug, updated_at)| SitemapEntry {
+        path: format!("/articles/{}", slug),
+        lastmod: Some(updated_at),
+    }));
+    for (prefix, rows) in pages {
+        entries.extend(rows.into_iter().map(|(id, updated_at)| SitemapEntry {
+            path: format!("{}/{}", prefix, id),
+            lastmod: Some(updated_at),
+        }));
+    }
+
+    Ok(entries)
+}
@@ -2,18 +2,20 @@ use crate::{
     dto::tag::{TagRequest, UpdateTagRequest},
     error::{AppError, AppResult, Resource, ResourceType},
 };
+use chrono::Utc;
+use entity::article_tag as ArticleTagEntity;
 use entity::tag as TagEntity;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set,
-    TransactionTrait,
+    prelude::DateTimeUtc, sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait,
+    DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
 };
 
 pub async fn create(dbc: &DatabaseConnection, tag_data: &TagRequest) -> AppResult<i32> {
     let model = TagEntity::ActiveModel {
         name: Set(tag_data.name.clone()),
         description: Set(tag_data.description.clone()),
-        r#type: Set(tag_data.r#type),
-        status: Set(tag_data.status),
+        r#type: Set(tag_data.r#type.into()),
+        status: Set(tag_data.status.into()),
         ..Default::default()
     }
     .insert(dbc)
@@ -22,6 +24,49 @@ pub async fn create(dbc: &DatabaseConnection, tag_data: &TagRequest) -> AppResul
     Ok(model.id)
 }
 
+/// Look up the tags by name, creating the unknown ones as published custom
+/// tags and restoring the trashed ones. Returns their ids.
+pub async fn find_or_create_by_names<C: ConnectionTrait>(
+    conn: &C,
+    names: &[String],
+) -> AppResult<Vec<i32>> {
+    let mut ids = Vec::with_capacity(names.len());
+
+    for name in names.iter().map(|name| name.trim()) {
+        if name.is_empty() {
+            continue;
+        }
+
+        let model = TagEntity::Entity::find()
+            .filter(TagEntity::Column::Name.eq(name))
+            .one(conn)
+            .await?;
+
+        let id = match model {
+            Some(model) if model.deleted_at.is_some() => {
+                let mut model: TagEntity::ActiveModel = model.into();
+                model.deleted_at = Set(None);
+                model.update(conn).await?.id
+            }
+            Some(model) => model.id,
+            None => {
+                TagEntity::ActiveModel {
+                    name: Set(name.to_string()),
+                    r#type: Set(1),
+                    status: Set(1),
+                    ..Default::default()
+                }
+                .insert(conn)
+                .await?
+                .id
+            }
+        };
+        ids.push(id);
+    }
+
+    Ok(ids)
+}
+
 pub async fn update(
     dbc: &DatabaseConnection,
     tag_id: i32,
@@ -47,11 +92,11 @@ pub async fn update(
     }
 
     if let Some(r#type) = update_data.r#type {
-        model.r#type = Set(r#type);
+        model.r#type = Set(r#type.into());
     }
 
     if let Some(status) = update_data.status {
-        model.status = Set(status);
+        model.status = Set(status.into());
     }
 
     model.update(&tx).await?;
@@ -64,20 +109,98 @@ pub async fn read_by_id(
     dbc: &DatabaseConnection,
     tag_id: i32,
 ) -> AppResult<Option<TagEntity::Model>> {
-    let model = TagEntity::Entity::find_by_id(tag_id).one(dbc).await?;
+    let model = TagEntity::Entity::find_by_id(tag_id)
+        .filter(TagEntity::Column::DeletedAt.is_null())
+        .one(dbc)
+        .await?;
     Ok(model)
 }
 
 pub async fn read_all(dbc: &DatabaseConnection) -> AppResult<Vec<TagEntity::Model>> {
-    let models = TagEntity::Entity::find().all(dbc).await?;
+    let models = TagEntity::Entity::find()
+        .filter(TagEntity::Column::DeletedAt.is_null())
+        .all(dbc)
+        .await?;
     Ok(models)
 }
 
+pub async fn read_trash(dbc: &DatabaseConnection) -> AppResult<Vec<TagEntity::Model>> {
+    let models = TagEntity::Entity::find()
+        .filter(TagEntity::Column::DeletedAt.is_not_null())
+        .order_by_desc(TagEntity::Column::DeletedAt)
+        .all(dbc)
+        .await?;
+    Ok(models)
+}
+
+/// Move the tag to the trash, it stays in database until purged.
 pub async fn delete_by_id(dbc: &DatabaseConnection, tag_id: i32) -> AppResult<()> {
-    TagEntity::Entity::delete_by_id(tag_id).exec(dbc).await?;
+    let result = TagEntity::Entity::update_many()
+        .col_expr(TagEntity::Column::DeletedAt, Expr::value(Utc::now()))
+        .filter(TagEntity::Column::Id.eq(tag_id))
+        .filter(TagEntity::Column::DeletedAt.is_null())
+        .exec(dbc)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(AppError::NotFound(Resource {
+            r#type: ResourceType::Tag,
+            detail: "Not found this tag".to_string(),
+        }));
+    }
     Ok(())
 }
 
+pub async fn restore_by_id(dbc: &DatabaseConnection, tag_id: i32) -> AppResult<()> {
+    let result = TagEntity::Entity::update_many()
+        .col_expr(
+            TagEntity::Column::DeletedAt,
+            Expr::value(Option::<DateTimeUtc>::None),
+        )
+        .filter(TagEntity::Column::Id.eq(tag_id))
+        .filter(TagEntity::Column::DeletedAt.is_not_null())
+        .exec(dbc)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(AppError::NotFound(Resource {
+            r#type: ResourceType::Tag,
+            detail: "Not found this tag in trash".to_string(),
+        }));
+    }
+    Ok(())
+}
+
+/// Hard delete the tags trashed before `before` and detach them from articles.
+pub async fn purge(dbc: &DatabaseConnection, before: DateTimeUtc) -> AppResult<u64> {
+    let ids: Vec<i32> = TagEntity::Entity::find()
+        .select_only()
+        .column(TagEntity::Column::Id)
+        .filter(TagEntity::Column::DeletedAt.lt(before))
+        .into_tuple()
+        .all(dbc)
+        .await?;
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    let tx = dbc.begin().await?;
+
+    ArticleTagEntity::Entity::delete_many()
+        .filter(ArticleTagEntity::Column::TagId.is_in(ids.clone()))
+        .exec(&tx)
+        .await?;
+    let result = TagEntity::Entity::delete_many()
+        .filter(TagEntity::Column::Id.is_in(ids))
+        .exec(&tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(result.rows_affected)
+}
+
+/// Trashed tags are included, their name is still taken until purged.
 pub async fn check_name_exist(
     dbc: &DatabaseConnection,
     name: &str,
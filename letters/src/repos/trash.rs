@@ -0,0 +1,31 @@
+use crate::{
+    error::AppResult,
+    repos::{article, category, tag, user},
+};
+use sea_orm::{prelude::DateTimeUtc, DatabaseConnection};
+
+#[derive(Debug, Default)]
+pub struct PurgeReport {
+    pub articles: u64,
+    pub categories: u64,
+    pub tags: u64,
+    pub users: u64,
+}
+
+/// Hard delete everything trashed before `before`.
+///
+/// Articles go first so that their categories and authors are no longer
+/// referenced when those are purged.
+pub async fn purge(dbc: &DatabaseConnection, before: DateTimeUtc) -> AppResult<PurgeReport> {
+    let articles = article::purge(dbc, before).await?;
+    let tags = tag::purge(dbc, before).await?;
+    let categories = category::purge(dbc, before).await?;
+    let users = user::purge(dbc, before).await?;
+
+    Ok(PurgeReport {
+        articles,
+        categories,
+        tags,
+        users,
+    })
+}
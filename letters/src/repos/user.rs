@@ -6,10 +6,15 @@ use crate::{
     error::{AppError, AppResult, Resource, ResourceType},
     utils::hash::generate_hash,
 };
+use chrono::Utc;
+use entity::article as ArticleEntity;
+use entity::comment as CommentEntity;
+use entity::media as MediaEntity;
+use entity::series as SeriesEntity;
 use entity::user as UserEntity;
 use sea_orm::{
-    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait,
-    PaginatorTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+    prelude::DateTimeUtc, sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection,
+    EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
 };
 use std::cmp;
 
@@ -78,7 +83,10 @@ pub async fn read_by_id(
     dbc: &DatabaseConnection,
     user_id: i32,
 ) -> AppResult<Option<UserEntity::Model>> {
-    let model = UserEntity::Entity::find_by_id(user_id).one(dbc).await?;
+    let model = UserEntity::Entity::find_by_id(user_id)
+        .filter(UserEntity::Column::DeletedAt.is_null())
+        .one(dbc)
+        .await?;
     Ok(model)
 }
 
@@ -89,6 +97,7 @@ pub async fn read_by_email(
 ) -> AppResult<Option<UserEntity::Model>> {
     let model = UserEntity::Entity::find()
         .filter(UserEntity::Column::Email.eq(email))
+        .filter(UserEntity::Column::DeletedAt.is_null())
         .one(dbc)
         .await?;
 
@@ -99,7 +108,7 @@ pub async fn _read_all(
     dbc: &DatabaseConnection,
     param: PageQueryParam,
 ) -> AppResult<Vec<UserEntity::Model>> {
-    let mut select = UserEntity::Entity::find();
+    let mut select = UserEntity::Entity::find().filter(UserEntity::Column::DeletedAt.is_null());
 
     match param.order_direction {
         Some(Direction::Desc) => {
@@ -117,6 +126,90 @@ pub async fn _read_all(
     Ok(models)
 }
 
+pub async fn read_trash(dbc: &DatabaseConnection) -> AppResult<Vec<UserEntity::Model>> {
+    let models = UserEntity::Entity::find()
+        .filter(UserEntity::Column::DeletedAt.is_not_null())
+        .order_by_desc(UserEntity::Column::DeletedAt)
+        .all(dbc)
+        .await?;
+    Ok(models)
+}
+
+/// Move the user to the trash, a trashed user can no longer sign in.
+pub async fn delete_by_id(dbc: &DatabaseConnection, user_id: i32) -> AppResult<()> {
+    let result = UserEntity::Entity::update_many()
+        .col_expr(UserEntity::Column::DeletedAt, Expr::value(Utc::now()))
+        .filter(UserEntity::Column::Id.eq(user_id))
+        .filter(UserEntity::Column::DeletedAt.is_null())
+        .exec(dbc)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(AppError::NotFound(Resource {
+            r#type: ResourceType::User,
+            detail: "Not found this user".to_string(),
+        }));
+    }
+    Ok(())
+}
+
+pub async fn restore_by_id(dbc: &DatabaseConnection, user_id: i32) -> AppResult<()> {
+    let result = UserEntity::Entity::update_many()
+        .col_expr(
+            UserEntity::Column::DeletedAt,
+            Expr::value(Option::<DateTimeUtc>::None),
+        )
+        .filter(UserEntity::Column::Id.eq(user_id))
+        .filter(UserEntity::Column::DeletedAt.is_not_null())
+        .exec(dbc)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(AppError::NotFound(Resource {
+            r#type: ResourceType::User,
+            detail: "Not found this user in trash".to_string(),
+        }));
+    }
+    Ok(())
+}
+
+/// Hard delete the users trashed before `before`. Users who still own
+/// articles, series or comments are kept.
+pub async fn purge(dbc: &DatabaseConnection, before: DateTimeUtc) -> AppResult<u64> {
+    let models = UserEntity::Entity::find()
+        .filter(UserEntity::Column::DeletedAt.lt(before))
+        .all(dbc)
+        .await?;
+
+    let mut purged = 0;
+    for model in models {
+        let articles = ArticleEntity::Entity::find()
+            .filter(ArticleEntity::Column::UserId.eq(model.id))
+            .count(dbc)
+            .await?;
+        let series = SeriesEntity::Entity::find()
+            .filter(SeriesEntity::Column::UserId.eq(model.id))
+            .count(dbc)
+            .await?;
+        let comments = CommentEntity::Entity::find()
+            .filter(CommentEntity::Column::UserId.eq(model.id))
+            .count(dbc)
+            .await?;
+        let media = MediaEntity::Entity::find()
+            .filter(MediaEntity::Column::UserId.eq(model.id))
+            .count(dbc)
+            .await?;
+        if articles + series + comments + media > 0 {
+            continue;
+        }
+
+        UserEntity::Entity::delete_by_id(model.id).exec(dbc).await?;
+        purged += 1;
+    }
+
+    Ok(purged)
+}
+
 pub async fn _check_email_exist(dbc: &DatabaseConnection, email: &str) -> AppResult<bool> {
     let model = UserEntity::Entity::find()
         .filter(UserEntity::Column::Email.eq(email))
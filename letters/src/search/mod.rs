@@ -0,0 +1,477 @@
+mod tokenizer;
+
+pub use tokenizer::tokenize;
+
+use std::{collections::HashMap, fs, ops::Bound, path::Path};
+
+use sea_orm::DatabaseConnection;
+use tantivy::{
+    collector::{Count, TopDocs},
+    directory::MmapDirectory,
+    query::{BooleanQuery, Occur, Query, QueryParser, RangeQuery, TermQuery},
+    schema::{
+        DateOptions, DateTimePrecision, Field, IndexRecordOption, Schema, TextFieldIndexing,
+        TextOptions, Value, FAST, INDEXED, STORED,
+    },
+    tokenizer::{LowerCaser, RemoveLongFilter, TextAnalyzer},
+    DateTime, Index, IndexReader, IndexWriter, ReloadPolicy, SnippetGenerator, TantivyDocument,
+    Term,
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    dto::{
+        search::{SearchHit, SearchQueryParam, SearchResponse},
+        tag::ArticleTag,
+    },
+    error::{AppError, AppResult},
+    repos::article,
+};
+use tokenizer::CjkTokenizer;
+
+const TOKENIZER: &str = "cjk";
+const WRITER_MEMORY: usize = 50_000_000;
+/// How deep the pages of a search can go, the collector allocates for every
+/// hit before the requested page.
+pub const MAX_RESULTS: u64 = 10_000;
+
+struct Fields {
+    id: Field,
+    title: Field,
+    summary: Field,
+    content: Field,
+    tags: Field,
+    tag_ids: Field,
+    category_id: Field,
+    user_id: Field,
+    created_at: Field,
+}
+
+/// Embedded full-text index over the published articles.
+pub struct SearchIndex {
+    index: Index,
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+    fields: Fields,
+}
+
+impl SearchIndex {
+    pub fn open(dir: &str) -> anyhow::Result<Self> {
+        let (schema, fields) = build_schema();
+
+        fs::create_dir_all(dir)?;
+        let index = Index::open_or_create(MmapDirectory::open(Path::new(dir))?, schema)?;
+        index.tokenizers().register(
+            TOKENIZER,
+            TextAnalyzer::builder(CjkTokenizer::default())
+                .filter(RemoveLongFilter::limit(40))
+                .filter(LowerCaser)
+                .build(),
+        );
+
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        let writer = index.writer(WRITER_MEMORY)?;
+
+        Ok(Self {
+            index,
+            reader,
+            writer: Mutex::new(writer),
+            fields,
+        })
+    }
+
+    /// Bring the index in line with the article row: published articles are
+    /// (re)indexed, unpublished, trashed or missing ones are removed.
+    ///
+    /// Failures are logged rather than returned, the database stays the
+    /// source of truth and `reindex` can always rebuild the index.
+    pub async fn sync_article(&self, dbc: &DatabaseConnection, article_id: i32) {
+        if let Err(err) = self.try_sync_article(dbc, article_id).await {
+            tracing::error!(?err, article_id, "Sync search index failed");
+        }
+    }
+
+    async fn try_sync_article(&self, dbc: &DatabaseConnection, article_id: i32) -> AppResult<()> {
+        let model = article::read_model_by_id(dbc, article_id).await?;
+        let tags = article::read_tags(dbc, article_id).await?;
+
+        let mut writer = self.writer.lock().await;
+        writer.delete_term(Term::from_field_u64(self.fields.id, article_id as u64));
+        if let Some(model) = model.filter(|model| model.status == 1) {
+            writer.add_document(self.document(&model, &tags))?;
+        }
+        writer.commit()?;
+        self.reader.reload()?;
+
+        Ok(())
+    }
+
+    /// Drop the whole index and index every published article again.
+    pub async fn rebuild(&self, dbc: &DatabaseConnection) -> AppResult<u64> {
+        let mut count = 0;
+        let mut writer = self.writer.lock().await;
+        writer.delete_all_documents()?;
+
+        let mut page = 0;
+        loop {
+            let models = article::read_published(dbc, page, 500).await?;
+            if models.is_empty() {
+                break;
+            }
+
+            let ids: Vec<i32> = models.iter().map(|model| model.id).collect();
+            let mut tags: HashMap<i32, Vec<ArticleTag>> =
+                article::read_tags_by_articles(dbc, &ids).await?;
+            for model in models {
+                let tags = tags.remove(&model.id).unwrap_or_default();
+                writer.add_document(self.document(&model, &tags))?;
+                count += 1;
+            }
+            page += 1;
+        }
+
+        writer.commit()?;
+        self.reader.reload()?;
+
+        Ok(count)
+    }
+
+    pub fn search(&self, param: &SearchQueryParam) -> AppResult<SearchResponse> {
+        let q = param.q.trim();
+        if q.is_empty() {
+            return Err(AppError::InvalidInput(
+                "The search query can not be empty".to_string(),
+            ));
+        }
+
+        let fields = &self.fields;
+        let mut parser = QueryParser::for_index(
+            &self.index,
+            vec![fields.title, fields.summary, fields.content, fields.tags],
+        );
+        parser.set_conjunction_by_default();
+        parser.set_field_boost(fields.title, 3.0);
+        parser.set_field_boost(fields.tags, 2.0);
+        parser.set_field_boost(fields.summary, 1.5);
+        let (text_query, _errors) = parser.parse_query_lenient(q);
+
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![(Occur::Must, text_query)];
+        let filters = [
+            (fields.category_id, param.category_id),
+            (fields.tag_ids, param.tag_id),
+            (fields.user_id, param.author_id),
+        ];
+        for (field, value) in filters {
+            if let Some(value) = value {
+                clauses.push((
+                    Occur::Must,
+                    Box::new(TermQuery::new(
+                        Term::from_field_u64(field, value as u64),
+                        IndexRecordOption::Basic,
+                    )),
+                ));
+            }
+        }
+        if param.from.is_some() || param.to.is_some() {
+            let bound = |date: Option<chrono::DateTime<chrono::Utc>>| match date {
+                Some(date) => Bound::Included(DateTime::from_timestamp_secs(date.timestamp())),
+                None => Bound::Unbounded,
+            };
+            clauses.push((
+                Occur::Must,
+                Box::new(RangeQuery::new_date_bounds(
+                    "created_at".to_string(),
+                    bound(param.from),
+                    bound(param.to),
+                )),
+            ));
+        }
+        let query = BooleanQuery::new(clauses);
+
+        let page = param.page.unwrap_or(1).max(1);
+        let per_page = param.per_page.unwrap_or(10).clamp(1, 100);
+        if page > last_page(per_page) {
+            return Err(AppError::InvalidInput(format!(
+                "Only the first {} results can be paged through",
+                MAX_RESULTS
+            )));
+        }
+        let searcher = self.reader.searcher();
+        let (total, top_docs) = searcher.search(
+            &query,
+            &(
+                Count,
+                TopDocs::with_limit(per_page as usize).and_offset(((page - 1) * per_page) as usize),
+            ),
+        )?;
+
+        let title_snippets = SnippetGenerator::create(&searcher, &query, fields.title)?;
+        let mut content_snippets = SnippetGenerator::create(&searcher, &query, fields.content)?;
+        content_snippets.set_max_num_chars(200);
+
+        let mut hits = Vec::with_capacity(top_docs.len());
+        for (score, address) in top_docs {
+            let doc: TantivyDocument = searcher.doc(address)?;
+            let text = |field: Field| {
+                doc.get_first(field)
+                    .and_then(|value| value.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+
+            let title = title_snippets.snippet_from_doc(&doc);
+            let content = content_snippets.snippet_from_doc(&doc);
+            hits.push(SearchHit {
+                id: doc
+                    .get_first(fields.id)
+                    .and_then(|value| value.as_u64())
+                    .unwrap_or_default() as i32,
+                title: text(fields.title),
+                summary: text(fields.summary),
+                score,
+                title_highlight: if title.is_empty() {
+                    html_escape(&text(fields.title))
+                } else {
+                    title.to_html()
+                },
+                highlight: if content.is_empty() {
+                    html_escape(&text(fields.summary))
+                } else {
+                    content.to_html()
+                },
+            });
+        }
+
+        Ok(SearchResponse {
+            total,
+            page,
+            per_page,
+            hits,
+        })
+    }
+
+    fn document(&self, model: &entity::article::Model, tags: &[ArticleTag]) -> TantivyDocument {
+        let fields = &self.fields;
+        let mut doc = TantivyDocument::default();
+        doc.add_u64(fields.id, model.id as u64);
+        doc.add_text(fields.title, &model.title);
+        doc.add_text(fields.summary, &model.summary);
+        doc.add_text(fields.content, &model.content);
+        for tag in tags {
+            doc.add_text(fields.tags, &tag.name);
+            doc.add_u64(fields.tag_ids, tag.id as u64);
+        }
+        doc.add_u64(fields.category_id, model.category_id as u64);
+        doc.add_u64(fields.user_id, model.user_id as u64);
+        doc.add_date(
+            fields.created_at,
+            DateTime::from_timestamp_secs(model.created_at.timestamp()),
+        );
+        doc
+    }
+}
+
+/// The last page of `per_page` hits within the first `MAX_RESULTS`.
+pub fn last_page(per_page: u64) -> u64 {
+    (MAX_RESULTS / per_page.max(1)).max(1)
+}
+
+fn build_schema() -> (Schema, Fields) {
+    let text = TextOptions::default()
+        .set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer(TOKENIZER)
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        )
+        .set_stored();
+    let date = DateOptions::from(INDEXED)
+        .set_stored()
+        .set_fast()
+        .set_precision(DateTimePrecision::Seconds);
+
+    let mut builder = Schema::builder();
+    let fields = Fields {
+        id: builder.add_u64_field("id", INDEXED | STORED | FAST),
+        title: builder.add_text_field("title", text.clone()),
+        summary: builder.add_text_field("summary", text.clone()),
+        content: builder.add_text_field("content", text.clone()),
+        tags: builder.add_text_field("tags", text),
+        tag_ids: builder.add_u64_field("tag_ids", INDEXED),
+        category_id: builder.add_u64_field("category_id", INDEXED),
+        user_id: builder.add_u64_field("user_id", INDEXED),
+        created_at: builder.add_date_field("created_at", date),
+    };
+
+    (builder.build(), fields)
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn article(id: i32, title: &str, content: &str) -> entity::article::Model {
+        entity::article::Model {
+            id,
+            title: title.to_string(),
+            slug: format!("article-{}", id),
+            cover: String::new(),
+            content: content.to_string(),
+            summary: String::new(),
+            password_hash: String::new(),
+            source: 0,
+            source_url: String::new(),
+            topping: 0,
+            status: 1,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+            user_id: 1,
+            category_id: 1,
+        }
+    }
+
+    async fn index(dir: &tempfile::TempDir, articles: &[entity::article::Model]) -> SearchIndex {
+        let index = SearchIndex::open(dir.path().to_str().unwrap()).unwrap();
+        let mut writer = index.writer.lock().await;
+        for model in articles {
+            let tags = [ArticleTag {
+                id: model.id,
+                name: format!("tag{}", model.id),
+            }];
+            writer.add_document(index.document(model, &tags)).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+        index.reader.reload().unwrap();
+        index
+    }
+
+    fn query(q: &str) -> SearchQueryParam {
+        SearchQueryParam {
+            q: q.to_string(),
+            category_id: None,
+            tag_id: None,
+            author_id: None,
+            from: None,
+            to: None,
+            page: None,
+            per_page: None,
+        }
+    }
+
+    fn ids(resp: &SearchResponse) -> Vec<i32> {
+        resp.hits.iter().map(|hit| hit.id).collect()
+    }
+
+    #[tokio::test]
+    async fn a_match_in_the_title_ranks_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = index(
+            &dir,
+            &[
+                article(
+                    1,
+                    "Traits",
+                    "Pinning comes up with self referential futures",
+                ),
+                article(2, "Pinning", "Why futures can not move"),
+            ],
+        )
+        .await;
+
+        let resp = index.search(&query("pinning")).unwrap();
+
+        assert_eq!(resp.total, 2);
+        assert_eq!(ids(&resp), [2, 1]);
+        assert_eq!(resp.hits[0].title_highlight, "<b>Pinning</b>");
+    }
+
+    #[tokio::test]
+    async fn every_term_must_match() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = index(
+            &dir,
+            &[
+                article(1, "Pinning", "Self referential futures"),
+                article(2, "Futures", "Polling a future"),
+            ],
+        )
+        .await;
+
+        let resp = index.search(&query("pinning futures")).unwrap();
+
+        assert_eq!(ids(&resp), [1]);
+    }
+
+    #[tokio::test]
+    async fn cjk_queries_match_by_bigrams() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = index(
+            &dir,
+            &[
+                article(1, "全文搜索引擎", "倒排索引"),
+                article(2, "异步编程", "运行时"),
+            ],
+        )
+        .await;
+
+        let resp = index.search(&query("搜索")).unwrap();
+
+        assert_eq!(ids(&resp), [1]);
+    }
+
+    #[tokio::test]
+    async fn hits_are_filtered_by_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = index(
+            &dir,
+            &[
+                article(1, "Pinning", "Futures"),
+                article(2, "Polling", "Futures"),
+            ],
+        )
+        .await;
+
+        let resp = index
+            .search(&SearchQueryParam {
+                tag_id: Some(2),
+                ..query("futures")
+            })
+            .unwrap();
+
+        assert_eq!(ids(&resp), [2]);
+    }
+
+    #[tokio::test]
+    async fn pages_stop_at_the_results_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = index(&dir, &[article(1, "Pinning", "Futures")]).await;
+
+        let param = SearchQueryParam {
+            page: Some(last_page(10) + 1),
+            ..query("futures")
+        };
+        assert!(matches!(
+            index.search(&param),
+            Err(AppError::InvalidInput(_))
+        ));
+
+        let param = SearchQueryParam {
+            page: Some(last_page(10)),
+            ..query("futures")
+        };
+        assert!(index.search(&param).unwrap().hits.is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+/// Splits latin text on non alphanumeric characters and CJK text into
+/// overlapping bigrams, a lone CJK character is kept as a unigram.
+///
+/// Bigrams give a reasonable recall for Chinese, Japanese and Korean without
+/// a dictionary, as long as queries go through the same tokenizer.
+#[derive(Clone, Default)]
+pub struct CjkTokenizer {
+    tokens: Vec<Token>,
+}
+
+pub struct CjkTokenStream<'a> {
+    tokens: &'a [Token],
+    index: usize,
+    token: Token,
+}
+
+impl Tokenizer for CjkTokenizer {
+    type TokenStream<'a> = CjkTokenStream<'a>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> CjkTokenStream<'a> {
+        self.tokens = split(text);
+        CjkTokenStream {
+            tokens: &self.tokens,
+            index: 0,
+            token: Token::default(),
+        }
+    }
+}
+
+impl<'a> TokenStream for CjkTokenStream<'a> {
+    fn advance(&mut self) -> bool {
+        match self.tokens.get(self.index) {
+            Some(token) => {
+                self.token = token.clone();
+                self.index += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.token
+    }
+}
+
+/// Lowercased terms of `text`, as they are written to the search index.
+pub fn tokenize(text: &str) -> Vec<String> {
+    split(text)
+        .into_iter()
+        .map(|token| token.text.to_lowercase())
+        .collect()
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'     // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}'   // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}'   // CJK Unified Ideographs
+        | '\u{AC00}'..='\u{D7AF}'   // Hangul Syllables
+        | '\u{F900}'..='\u{FAFF}'   // CJK Compatibility Ideographs
+        | '\u{20000}'..='\u{2A6DF}' // CJK Extension B
+    )
+}
+
+fn split(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if is_cjk(c) {
+            let mut run = vec![(start, c)];
+            while let Some(&(i, c)) = chars.peek() {
+                if !is_cjk(c) {
+                    break;
+                }
+                run.push((i, c));
+                chars.next();
+            }
+
+            if run.len() == 1 {
+                push(&mut tokens, text, start, start + c.len_utf8());
+                continue;
+            }
+            for pair in run.windows(2) {
+                let (from, _) = pair[0];
+                let (i, c) = pair[1];
+                push(&mut tokens, text, from, i + c.len_utf8());
+            }
+        } else if c.is_alphanumeric() {
+            let mut end = start + c.len_utf8();
+            while let Some(&(i, c)) = chars.peek() {
+                if !c.is_alphanumeric() || is_cjk(c) {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            push(&mut tokens, text, start, end);
+        }
+    }
+
+    tokens
+}
+
+fn push(tokens: &mut Vec<Token>, text: &str, from: usize, to: usize) {
+    tokens.push(Token {
+        offset_from: from,
+        offset_to: to,
+        position: tokens.len(),
+        text: text[from..to].to_string(),
+        position_length: 1,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latin_text_is_split_on_punctuation_and_lowercased() {
+        assert_eq!(
+            tokenize("Async Rust: pin_project, 2024!"),
+            ["async", "rust", "pin", "project", "2024"]
+        );
+    }
+
+    #[test]
+    fn cjk_text_is_split_into_bigrams() {
+        assert_eq!(tokenize("全文搜索"), ["全文", "文搜", "搜索"]);
+        assert_eq!(tokenize("ひらがな"), ["ひら", "らが", "がな"]);
+    }
+
+    #[test]
+    fn a_lone_cjk_character_is_a_unigram() {
+        assert_eq!(tokenize("字"), ["字"]);
+        assert_eq!(tokenize("Rust 的 trait"), ["rust", "的", "trait"]);
+    }
+
+    #[test]
+    fn mixed_text_splits_between_scripts() {
+        assert_eq!(tokenize("Rust编程语言"), ["rust", "编程", "程语", "语言"]);
+    }
+
+    #[test]
+    fn tokens_point_back_into_the_text() {
+        let text = "Tokio 异步";
+        let tokens = split(text);
+
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(&text[tokens[1].offset_from..tokens[1].offset_to], "异步");
+        assert_eq!(tokens[1].position, 1);
+    }
+}
@@ -0,0 +1,247 @@
+pub mod sitemap;
+
+use serde_json::json;
+
+use crate::{
+    conf::{Seo as SeoConf, Site},
+    dto::{
+        seo::{OpenGraph, SeoResponse, TwitterCard},
+        tag::ArticleTag,
+    },
+    utils::markdown,
+};
+
+/// Longest description put into the meta tags.
+const DESCRIPTION_CHARS: usize = 160;
+
+pub fn robots(site: &Site, conf: &SeoConf) -> String {
+    let mut lines = vec!["User-agent: *".to_string()];
+    lines.extend(
+        conf.robots_allow
+            .iter()
+            .map(|path| format!("Allow: {}", path)),
+    );
+    lines.extend(
+        conf.robots_disallow
+            .iter()
+            .map(|path| format!("Disallow: {}", path)),
+    );
+    lines.push("".to_string());
+    lines.push(format!("Sitemap: {}", site.url("/sitemap.xml")));
+    lines.push("".to_string());
+    lines.join("\n")
+}
+
+/// Canonical url, OpenGraph and Twitter card fields, and the `BlogPosting`
+/// JSON-LD of an article.
+pub fn article_seo(
+    site: &Site,
+    conf: &SeoConf,
+    article: &entity::article::Model,
+    author: Option<&entity::user::Model>,
+    category: Option<&str>,
+    tags: &[ArticleTag],
+) -> SeoResponse {
+    let canonical_url = site.article_url(&article.slug);
+    let description = if article.summary.trim().is_empty() {
+        markdown::excerpt(&article.content, DESCRIPTION_CHARS)
+    } else {
+        article.summary.trim().to_string()
+    };
+    let image = Some(article.cover.as_str())
+        .filter(|cover| !cover.is_empty())
+        .or(conf.default_image.as_deref())
+        .map(|image| absolute_url(site, image));
+    let tags: Vec<String> = tags.iter().map(|tag| tag.name.clone()).collect();
+
+    let mut json_ld = json!({
+        "@context": "https://schema.org",
+        "@type": "BlogPosting",
+        "headline": article.title,
+        "description": description,
+        "url": canonical_url,
+        "mainEntityOfPage": { "@type": "WebPage", "@id": canonical_url },
+        "datePublished": article.created_at.to_rfc3339(),
+        "dateModified": article.updated_at.to_rfc3339(),
+        "inLanguage": site.language,
+        "publisher": { "@type": "Organization", "name": site.title, "url": site.url("/") },
+    });
+    if let Some(image) = &image {
+        json_ld["image"] = json!([image]);
+    }
+    if let Some(author) = author {
+        json_ld["author"] = json!({
+            "@type": "Person",
+            "name": author.username,
+            "url": site.url(&format!("/authors/{}", author.id)),
+        });
+    }
+    if let Some(category) = category {
+        json_ld["articleSection"] = json!(category);
+    }
+    if !tags.is_empty() {
+        json_ld["keywords"] = json!(tags.join(", "));
+    }
+
+    SeoResponse {
+        canonical_url: canonical_url.clone(),
+        title: format!("{} - {}", article.title, site.title),
+        description: description.clone(),
+        open_graph: OpenGraph {
+            r#type: "article".to_string(),
+            title: article.title.clone(),
+            description: description.clone(),
+            url: canonical_url,
+            site_name: site.title.clone(),
+            locale: site.language.replace('-', "_"),
+            image: image.clone(),
+            published_time: article.created_at,
+            modified_time: article.updated_at,
+            author: author.map(|author| author.username.clone()),
+            section: category.map(str::to_string),
+            tags,
+        },
+        twitter: TwitterCard {
+            card: if image.is_some() {
+                "summary_large_image"
+            } else {
+                "summary"
+            }
+            .to_string(),
+            title: article.title.clone(),
+            description,
+            image,
+            site: conf.twitter_site.clone(),
+        },
+        json_ld,
+    }
+}
+
+fn absolute_url(site: &Site, url: &str) -> String {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        url.to_string()
+    } else {
+        site.url(&format!("/{}", url.trim_start_matches('/')))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn site() -> Site {
+        Site {
+            base_url: "https://example.com".to_string(),
+            title: "Letters".to_string(),
+            description: String::new(),
+            language: "en-US".to_string(),
+        }
+    }
+
+    fn article(cover: &str, summary: &str) -> entity::article::Model {
+        let date = Utc.with_ymd_and_hms(2024, 5, 4, 8, 0, 0).unwrap();
+        entity::article::Model {
+            id: 1,
+            title: "Pinning".to_string(),
+            slug: "pinning".to_string(),
+            cover: cover.to_string(),
+            content: "Self referential **futures** can not move.".to_string(),
+            summary: summary.to_string(),
+            password_hash: String::new(),
+            source: 0,
+            source_url: String::new(),
+            topping: 0,
+            status: 1,
+            created_at: date,
+            updated_at: date,
+            deleted_at: None,
+            user_id: 1,
+            category_id: 1,
+        }
+    }
+
+    #[test]
+    fn robots_lists_the_rules_and_the_sitemap() {
+        let conf = SeoConf {
+            robots_allow: vec!["/".to_string()],
+            robots_disallow: vec!["/api/".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            robots(&site(), &conf),
+            "User-agent: *\nAllow: /\nDisallow: /api/\n\nSitemap: https://example.com/sitemap.xml\n"
+        );
+    }
+
+    #[test]
+    fn article_without_summary_or_cover() {
+        let conf = SeoConf {
+            default_image: Some("/images/default.png".to_string()),
+            ..Default::default()
+        };
+
+        let seo = article_seo(&site(), &conf, &article("", ""), None, None, &[]);
+
+        assert_eq!(seo.canonical_url, "https://example.com/articles/pinning");
+        assert_eq!(seo.title, "Pinning - Letters");
+        assert_eq!(seo.description, "Self referential futures can not move.");
+        assert_eq!(
+            seo.open_graph.image.as_deref(),
+            Some("https://example.com/images/default.png")
+        );
+        assert_eq!(seo.open_graph.locale, "en_US");
+        assert_eq!(seo.twitter.card, "summary_large_image");
+        assert!(seo.json_ld.get("author").is_none());
+    }
+
+    #[test]
+    fn article_with_author_category_and_tags() {
+        let author = entity::user::Model {
+            id: 7,
+            username: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            password_hash: String::new(),
+            bio: None,
+            avatar: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+        };
+        let tags = [
+            ArticleTag {
+                id: 1,
+                name: "async".to_string(),
+            },
+            ArticleTag {
+                id: 2,
+                name: "rust".to_string(),
+            },
+        ];
+
+        let seo = article_seo(
+            &site(),
+            &SeoConf::default(),
+            &article("https://cdn.example.com/cover.png", "About pinning"),
+            Some(&author),
+            Some("Rust"),
+            &tags,
+        );
+
+        assert_eq!(seo.description, "About pinning");
+        assert_eq!(
+            seo.twitter.image.as_deref(),
+            Some("https://cdn.example.com/cover.png")
+        );
+        assert_eq!(seo.json_ld["@type"], "BlogPosting");
+        assert_eq!(
+            seo.json_ld["author"]["url"],
+            "https://example.com/authors/7"
+        );
+        assert_eq!(seo.json_ld["articleSection"], "Rust");
+        assert_eq!(seo.json_ld["keywords"], "async, rust");
+        assert_eq!(seo.json_ld["datePublished"], "2024-05-04T08:00:00+00:00");
+    }
+}
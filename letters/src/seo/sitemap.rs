@@ -0,0 +1,135 @@
+use std::fmt::Write;
+
+use sea_orm::prelude::DateTimeUtc;
+
+use crate::{conf::Site, dto::seo::SitemapEntry};
+
+/// Most urls a single sitemap file may hold.
+pub const MAX_URLS: usize = 50_000;
+
+pub fn render_urlset(site: &Site, entries: &[SitemapEntry]) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?><urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+    );
+    for entry in entries {
+        xml.push_str("<url>");
+        push_loc(&mut xml, &site.url(&entry.path));
+        push_lastmod(&mut xml, entry.lastmod);
+        xml.push_str("</url>");
+    }
+    xml.push_str("</urlset>");
+    xml
+}
+
+/// Index pointing at `/sitemaps/<n>.xml` for every chunk of `MAX_URLS`.
+pub fn render_index(site: &Site, entries: &[SitemapEntry]) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?><sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+    );
+    for (i, chunk) in entries.chunks(MAX_URLS).enumerate() {
+        xml.push_str("<sitemap>");
+        push_loc(&mut xml, &site.url(&format!("/sitemaps/{}.xml", i + 1)));
+        push_lastmod(
+            &mut xml,
+            chunk.iter().filter_map(|entry| entry.lastmod).max(),
+        );
+        xml.push_str("</sitemap>");
+    }
+    xml.push_str("</sitemapindex>");
+    xml
+}
+
+fn push_loc(xml: &mut String, url: &str) {
+    xml.push_str("<loc>");
+    for c in url.chars() {
+        match c {
+            '&' => xml.push_str("&amp;"),
+            '<' => xml.push_str("&lt;"),
+            '>' => xml.push_str("&gt;"),
+            '"' => xml.push_str("&quot;"),
+            '\'' => xml.push_str("&apos;"),
+            _ => xml.push(c),
+        }
+    }
+    xml.push_str("</loc>");
+}
+
+fn push_lastmod(xml: &mut String, lastmod: Option<DateTimeUtc>) {
+    if let Some(lastmod) = lastmod {
+        let _ = write!(
+            xml,
+            "<lastmod>{}</lastmod>",
+            lastmod.format("%Y-%m-%dT%H:%M:%SZ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn site() -> Site {
+        Site {
+            base_url: "https://example.com/".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn entries(count: usize) -> Vec<SitemapEntry> {
+        (0..count)
+            .map(|i| SitemapEntry {
+                path: format!("/articles/{}", i),
+                lastmod: Some(Utc.timestamp_opt(1_700_000_000 + i as i64, 0).unwrap()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn urlset_escapes_the_urls() {
+        let entries = [
+            SitemapEntry {
+                path: "/".to_string(),
+                lastmod: None,
+            },
+            SitemapEntry {
+                path: "/search?q=a&b".to_string(),
+                lastmod: Some(Utc.with_ymd_and_hms(2024, 5, 4, 8, 30, 0).unwrap()),
+            },
+        ];
+
+        let xml = render_urlset(&site(), &entries);
+
+        assert!(xml.ends_with(
+            "<url><loc>https://example.com/</loc></url>\
+             <url><loc>https://example.com/search?q=a&amp;b</loc>\
+             <lastmod>2024-05-04T08:30:00Z</lastmod></url></urlset>"
+        ));
+    }
+
+    #[test]
+    fn index_splits_above_the_url_limit() {
+        let entries = entries(MAX_URLS + 1);
+
+        let xml = render_index(&site(), &entries);
+
+        assert_eq!(xml.matches("<sitemap>").count(), 2);
+        assert!(xml.contains("<loc>https://example.com/sitemaps/1.xml</loc>"));
+        assert!(xml.contains("<loc>https://example.com/sitemaps/2.xml</loc>"));
+        assert!(!xml.contains("sitemaps/3.xml"));
+        // Each chunk is dated by its latest url.
+        let last = entries[MAX_URLS - 1].lastmod.unwrap();
+        assert!(xml.contains(&format!(
+            "<lastmod>{}</lastmod>",
+            last.format("%Y-%m-%dT%H:%M:%SZ")
+        )));
+    }
+
+    #[test]
+    fn index_of_exactly_the_limit_has_one_sitemap() {
+        let xml = render_index(&site(), &entries(MAX_URLS));
+
+        assert_eq!(xml.matches("<sitemap>").count(), 1);
+    }
+}
@@ -0,0 +1,239 @@
+//! Listening on TCP and Unix sockets, optionally behind TLS, until the
+//! process is asked to stop.
+
+mod tls;
+
+use std::{fmt, io, path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::Context;
+use axum::Router;
+use futures::future::select_all;
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::{conn::auto, graceful::GracefulShutdown},
+    service::TowerToHyperService,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpListener,
+};
+
+use crate::conf::Server;
+use tls::Certificates;
+
+/// A TLS handshake taking longer is given up.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A socket address or the path of a Unix socket.
+#[derive(Debug, Clone, PartialEq)]
+enum Address {
+    Tcp(String),
+    Unix(PathBuf),
+}
+
+impl Address {
+    fn parse(address: &str) -> Self {
+        match address.strip_prefix("unix:") {
+            Some(path) => Address::Unix(PathBuf::from(path)),
+            None => Address::Tcp(address.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Address::Tcp(address) => write!(f, "{}", address),
+            Address::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// What a connection is read from and written to, whatever the socket and
+/// whether it is encrypted.
+trait Io: AsyncRead + AsyncWrite + Unpin + Send + 'static {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send + 'static> Io for T {}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixListener, PathBuf),
+}
+
+impl Listener {
+    async fn bind(address: &Address) -> anyhow::Result<Self> {
+        match address {
+            Address::Tcp(address) => {
+                let listener = TcpListener::bind(address)
+                    .await
+                    .with_context(|| format!("Bind {} failed", address))?;
+                Ok(Listener::Tcp(listener))
+            }
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                // A socket left behind by a previous run would make the bind
+                // fail, anything else is not ours to remove.
+                use std::os::unix::fs::FileTypeExt;
+                if std::fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket()) {
+                    std::fs::remove_file(path)?;
+                }
+                let listener = tokio::net::UnixListener::bind(path)
+                    .with_context(|| format!("Bind unix:{} failed", path.display()))?;
+                Ok(Listener::Unix(listener, path.clone()))
+            }
+            #[cfg(not(unix))]
+            Address::Unix(_) => anyhow::bail!("Unix sockets are not supported on this platform"),
+        }
+    }
+
+    async fn accept(&self) -> io::Result<Box<dyn Io>> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, _) = listener.accept().await?;
+                stream.set_nodelay(true)?;
+                Ok(Box::new(stream))
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener, _) => {
+                let (stream, _) = listener.accept().await?;
+                Ok(Box::new(stream))
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Listener {
+    fn drop(&mut self) {
+        if let Listener::Unix(_, path) = self {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Serve `router` on every address of `conf` until SIGINT or SIGTERM.
+///
+/// The listeners are then closed and the requests in flight are given
+/// `shutdown_timeout_seconds` to finish, the connections still open after
+/// that are dropped.
+pub async fn run(router: Router, conf: &Server) -> anyhow::Result<()> {
+    let certificates = match &conf.tls {
+        Some(tls) => {
+            let certificates = Arc::new(Certificates::load(tls)?);
+            certificates.spawn_reload(tls);
+            Some(certificates)
+        }
+        None => None,
+    };
+    let scheme = if certificates.is_some() {
+        "https"
+    } else {
+        "http"
+    };
+
+    let mut listeners = Vec::new();
+    for address in conf.addresses() {
+        let address = Address::parse(&address);
+        listeners.push(Listener::bind(&address).await?);
+        tracing::info!("Listening on {}://{}", scheme, address);
+    }
+
+    let graceful = GracefulShutdown::new();
+    let signal = shutdown_signal();
+    tokio::pin!(signal);
+    loop {
+        let accepted = tokio::select! {
+            accepted = accept(&listeners) => accepted,
+            _ = &mut signal => break,
+        };
+        let stream = match accepted {
+            Ok(stream) => stream,
+            Err(err) => {
+                // Mostly running out of file descriptors, give the open
+                // connections some time to end.
+                tracing::warn!(?err, "Accept connection failed");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+
+        let acceptor = certificates
+            .as_ref()
+            .map(|certificates| certificates.acceptor());
+        let service = TowerToHyperService::new(router.clone());
+        let watcher = graceful.watcher();
+        tokio::spawn(async move {
+            let stream: Box<dyn Io> = match acceptor {
+                Some(acceptor) => {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(stream)) => Box::new(stream),
+                        Ok(Err(err)) => {
+                            tracing::debug!(?err, "TLS handshake failed");
+                            return;
+                        }
+                        Err(_) => {
+                            tracing::debug!("TLS handshake timed out");
+                            return;
+                        }
+                    }
+                }
+                None => stream,
+            };
+
+            let builder = auto::Builder::new(TokioExecutor::new());
+            let connection = builder.serve_connection_with_upgrades(TokioIo::new(stream), service);
+            if let Err(err) = watcher.watch(connection).await {
+                tracing::debug!(?err, "Serve connection failed");
+            }
+        });
+    }
+
+    drop(listeners);
+    tracing::info!("Shutting down, waiting for the requests in flight");
+    let timeout = Duration::from_secs(conf.shutdown_timeout_seconds);
+    match tokio::time::timeout(timeout, graceful.shutdown()).await {
+        Ok(()) => tracing::info!("Shut down"),
+        Err(_) => tracing::warn!("Shutdown timed out, the open connections are dropped"),
+    }
+
+    Ok(())
+}
+
+/// The next connection of any of the listeners.
+async fn accept(listeners: &[Listener]) -> io::Result<Box<dyn Io>> {
+    let (accepted, _, _) =
+        select_all(listeners.iter().map(|listener| Box::pin(listener.accept()))).await;
+    accepted
+}
+
+/// Resolve on the first SIGINT, or SIGTERM where there is one.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            tracing::error!(?err, "Listen to SIGINT failed");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(err) => {
+                tracing::error!(?err, "Listen to SIGTERM failed");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
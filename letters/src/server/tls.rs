@@ -0,0 +1,91 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Context;
+use tokio_rustls::{
+    rustls::{
+        crypto::ring,
+        pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+        ServerConfig,
+    },
+    TlsAcceptor,
+};
+
+use crate::conf::Tls;
+
+/// The certificate and the key in use, swapped for the new ones when the
+/// files change. A connection keeps the ones it was accepted with.
+pub struct Certificates {
+    config: RwLock<Arc<ServerConfig>>,
+}
+
+impl Certificates {
+    pub fn load(conf: &Tls) -> anyhow::Result<Self> {
+        Ok(Self {
+            config: RwLock::new(Arc::new(server_config(conf)?)),
+        })
+    }
+
+    pub fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(Arc::clone(&self.config.read().unwrap()))
+    }
+
+    /// Check the files every `reload_interval_seconds` and load them again
+    /// when one of them was modified. Files which can not be read or do not
+    /// match are logged and the previous ones kept.
+    pub fn spawn_reload(self: &Arc<Self>, conf: &Tls) {
+        if conf.reload_interval_seconds == 0 {
+            return;
+        }
+
+        let certificates = Arc::clone(self);
+        let conf = conf.clone();
+        tokio::spawn(async move {
+            let mut modified = modified_at(&conf);
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(conf.reload_interval_seconds));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let now = modified_at(&conf);
+                if now == modified {
+                    continue;
+                }
+                match server_config(&conf) {
+                    Ok(config) => {
+                        *certificates.config.write().unwrap() = Arc::new(config);
+                        modified = now;
+                        tracing::info!("Reloaded the TLS certificate");
+                    }
+                    Err(err) => tracing::error!(?err, "Reload TLS certificate failed"),
+                }
+            }
+        });
+    }
+}
+
+fn server_config(conf: &Tls) -> anyhow::Result<ServerConfig> {
+    let certs = CertificateDer::pem_file_iter(&conf.cert)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .with_context(|| format!("Read TLS certificate {} failed", conf.cert))?;
+    let key = PrivateKeyDer::from_pem_file(&conf.key)
+        .with_context(|| format!("Read TLS key {} failed", conf.key))?;
+
+    let mut config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("The TLS certificate does not match its key")?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+fn modified_at(conf: &Tls) -> [Option<SystemTime>; 2] {
+    [&conf.cert, &conf.key].map(|path| {
+        std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok()
+    })
+}
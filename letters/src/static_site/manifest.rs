@@ -0,0 +1,30 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppResult;
+
+pub const FILE_NAME: &str = ".letters-manifest.json";
+
+/// Fingerprint of the sources of every exported file, keyed by the path of
+/// the file relative to the output directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub files: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    /// The manifest of the previous export, empty when there is none.
+    pub fn load(out: &Path) -> Self {
+        std::fs::read(out.join(FILE_NAME))
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, out: &Path) -> AppResult<()> {
+        let data = serde_json::to_vec_pretty(self).map_err(anyhow::Error::from)?;
+        std::fs::write(out.join(FILE_NAME), data)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,373 @@
+mod manifest;
+
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use blake2::{Blake2s256, Digest};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+
+use crate::{
+    conf::Conf,
+    dto::{
+        article::PublishedArticle,
+        feed::{FeedFormat, FeedScope},
+        tag::ArticleTag,
+    },
+    error::AppResult,
+    feed::Channel,
+    render::{
+        pages::{
+            ArchivePage, ArticleItem, ArticlePage, Link, ListPage, Pagination, SeriesNav,
+            SiteContext,
+        },
+        Theme,
+    },
+    repos::{article, category, series, sitemap, tag},
+    seo::{self, sitemap::MAX_URLS},
+};
+use manifest::Manifest;
+
+#[derive(Debug, Default)]
+pub struct ExportReport {
+    pub written: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+}
+
+/// Render the public site into `out`.
+///
+/// Every file is fingerprinted from the data it is rendered from and the
+/// theme, and only rewritten when its fingerprint changed since the previous
+/// export, unless `full` is set. Files of the previous export which are no
+/// longer produced are removed.
+pub async fn export(
+    dbc: &DatabaseConnection,
+    conf: &Conf,
+    theme: &Theme,
+    out: &Path,
+    full: bool,
+) -> AppResult<ExportReport> {
+    std::fs::create_dir_all(out)?;
+    let previous = if full {
+        Manifest::default()
+    } else {
+        Manifest::load(out)
+    };
+    let mut writer = Writer {
+        out: out.to_path_buf(),
+        theme,
+        previous,
+        manifest: Manifest::default(),
+        report: ExportReport::default(),
+    };
+
+    let site = &conf.site;
+    let site_ctx = SiteContext::from(site);
+
+    let articles: Vec<PublishedArticle> = article::read_all_published(dbc)
+        .await?
+        .into_iter()
+        .filter(|article| {
+            let exportable = is_file_name(&article.slug);
+            if !exportable {
+                tracing::warn!(
+                    article.id,
+                    article.slug,
+                    "Slug is not a valid file name, skipped"
+                );
+            }
+            exportable
+        })
+        .collect();
+    let ids: Vec<i32> = articles.iter().map(|article| article.id).collect();
+    let mut tags: HashMap<i32, Vec<ArticleTag>> = HashMap::new();
+    for chunk in ids.chunks(500) {
+        tags.extend(article::read_tags_by_articles(dbc, chunk).await?);
+    }
+    let tags_of = |id: i32| tags.get(&id).map(Vec::as_slice).unwrap_or_default();
+    let items: HashMap<i32, ArticleItem> = articles
+        .iter()
+        .map(|article| (article.id, ArticleItem::new(article, tags_of(article.id))))
+        .collect();
+    let items_of = |ids: &[i32]| -> Vec<ArticleItem> {
+        ids.iter().filter_map(|id| items.get(id).cloned()).collect()
+    };
+
+    // Chapters of the published articles only, so that the navigation never
+    // points at a draft.
+    let all_series = series::read_all_unpaged(dbc).await?;
+    let mut chapters: HashMap<i32, Vec<(i32, i32)>> = HashMap::new();
+    for chapter in series::read_all_chapters(dbc).await? {
+        if items.contains_key(&chapter.article_id) {
+            chapters
+                .entry(chapter.series_id)
+                .or_default()
+                .push((chapter.position, chapter.article_id));
+        }
+    }
+
+    // Article pages.
+    for article in &articles {
+        let series = all_series
+            .iter()
+            .filter_map(|series| {
+                let chapters = chapters.get(&series.id)?;
+                let index = chapters.iter().position(|(_, id)| *id == article.id)?;
+                let link = |index: usize| {
+                    let item = &items[&chapters[index].1];
+                    Link {
+                        name: item.title.clone(),
+                        url: item.url.clone(),
+                    }
+                };
+                Some(SeriesNav {
+                    name: series.name.clone(),
+                    url: format!("/series/{}", series.id),
+                    position: chapters[index].0,
+                    nums: series.nums,
+                    prev: index.checked_sub(1).map(link),
+                    next: (index + 1 < chapters.len()).then(|| link(index + 1)),
+                })
+            })
+            .collect();
+
+        let page = ArticlePage {
+            site: site_ctx.clone(),
+            article: items[&article.id].clone(),
+            content: article.content.clone(),
+            series,
+        };
+        writer.page(
+            &format!("articles/{}/index.html", article.slug),
+            "article.html",
+            &page,
+        )?;
+    }
+
+    // Index, paginated.
+    let per_page = conf.theme.per_page.max(1) as usize;
+    let total_pages = ids.len().div_ceil(per_page).max(1);
+    for page in 1..=total_pages {
+        let start = (page - 1) * per_page;
+        let ctx = ListPage {
+            site: site_ctx.clone(),
+            title: site.title.clone(),
+            description: Some(site.description.clone()).filter(|d| !d.is_empty()),
+            feed_url: Some("/feed.xml".to_string()),
+            articles: items_of(&ids[start..ids.len().min(start + per_page)]),
+            pagination: Some(Pagination::new("/", page as u64, total_pages as u64)),
+        };
+        let path = match page {
+            1 => "index.html".to_string(),
+            _ => format!("page/{}/index.html", page),
+        };
+        writer.page(&path, "index.html", &ctx)?;
+    }
+
+    writer.page(
+        "archive/index.html",
+        "archive.html",
+        &ArchivePage::new(site_ctx.clone(), items_of(&ids)),
+    )?;
+
+    // Category, tag, series and author pages, with their feeds.
+    let mut lists: Vec<(FeedScope, String, Option<String>, Vec<i32>)> = Vec::new();
+    for category in category::read_all(dbc).await? {
+        let ids = articles
+            .iter()
+            .filter(|article| article.category_id == category.id)
+            .map(|article| article.id)
+            .collect();
+        lists.push((
+            FeedScope::Category(category.id),
+            category.name,
+            category.description,
+            ids,
+        ));
+    }
+    for tag in tag::read_all(dbc).await? {
+        let ids = articles
+            .iter()
+            .filter(|article| tags_of(article.id).iter().any(|t| t.id == tag.id))
+            .map(|article| article.id)
+            .collect();
+        lists.push((FeedScope::Tag(tag.id), tag.name, tag.description, ids));
+    }
+    for series in &all_series {
+        let ids = chapters
+            .get(&series.id)
+            .map(|chapters| chapters.iter().map(|(_, id)| *id).collect())
+            .unwrap_or_default();
+        lists.push((
+            FeedScope::Series(series.id),
+            series.name.clone(),
+            series.description.clone(),
+            ids,
+        ));
+    }
+    let mut authors = HashSet::new();
+    for article in &articles {
+        if !authors.insert(article.user_id) {
+            continue;
+        }
+        let ids = articles
+            .iter()
+            .filter(|other| other.user_id == article.user_id)
+            .map(|other| other.id)
+            .collect();
+        lists.push((
+            FeedScope::Author(article.user_id),
+            article.author_name.clone().unwrap_or_default(),
+            None,
+            ids,
+        ));
+    }
+
+    let by_id: HashMap<i32, &PublishedArticle> = articles.iter().map(|a| (a.id, a)).collect();
+    let mut feeds = vec![(FeedScope::All, site.title.clone(), ids.clone())];
+    for (scope, name, description, ids) in lists {
+        let base = scope.path();
+        let ctx = ListPage {
+            site: site_ctx.clone(),
+            title: name.clone(),
+            description,
+            feed_url: Some(format!("{}/feed.xml", base)),
+            articles: items_of(&ids),
+            pagination: None,
+        };
+        writer.page(
+            &format!("{}/index.html", base.trim_start_matches('/')),
+            "list.html",
+            &ctx,
+        )?;
+        feeds.push((scope, format!("{} - {}", site.title, name), ids));
+    }
+
+    for (scope, title, ids) in feeds {
+        let mut latest: Vec<&PublishedArticle> = ids.iter().map(|id| by_id[id]).collect();
+        latest.sort_by_key(|article| Reverse(article.created_at));
+        latest.truncate(conf.feed.limit as usize);
+        let latest: Vec<(PublishedArticle, Vec<ArticleTag>)> = latest
+            .into_iter()
+            .map(|article| (article.clone(), tags_of(article.id).to_vec()))
+            .collect();
+
+        let base = scope.path();
+        for format in [FeedFormat::Rss, FeedFormat::Atom, FeedFormat::Json] {
+            let path = format!("{}/{}", base, format.file_name());
+            let channel = Channel::new(
+                site,
+                &conf.feed,
+                title.clone(),
+                &base,
+                site.url(&path),
+                latest.clone(),
+            );
+            writer.file(path.trim_start_matches('/'), channel.render(format))?;
+        }
+    }
+
+    // Sitemap and robots.txt.
+    let entries = sitemap::read_entries(dbc).await?;
+    if entries.len() > MAX_URLS {
+        writer.file("sitemap.xml", seo::sitemap::render_index(site, &entries))?;
+        for (i, chunk) in entries.chunks(MAX_URLS).enumerate() {
+            writer.file(
+                &format!("sitemaps/{}.xml", i + 1),
+                seo::sitemap::render_urlset(site, chunk),
+            )?;
+        }
+    } else {
+        writer.file("sitemap.xml", seo::sitemap::render_urlset(site, &entries))?;
+    }
+    writer.file("robots.txt", seo::robots(site, &conf.seo))?;
+
+    writer.finish()
+}
+
+struct Writer<'a> {
+    out: PathBuf,
+    theme: &'a Theme,
+    previous: Manifest,
+    manifest: Manifest,
+    report: ExportReport,
+}
+
+impl Writer<'_> {
+    /// Render a page, unless its context and the theme are unchanged.
+    fn page<S: Serialize>(&mut self, path: &str, template: &str, ctx: &S) -> AppResult<()> {
+        let mut hasher = Blake2s256::new();
+        hasher.update(self.theme.fingerprint());
+        hasher.update(template);
+        hasher.update(serde_json::to_vec(ctx).map_err(anyhow::Error::from)?);
+        let fingerprint = hex(&hasher.finalize());
+
+        if self.unchanged(path, &fingerprint) {
+            return Ok(());
+        }
+        let html = self.theme.render(template, ctx)?;
+        self.write(path, fingerprint, html)
+    }
+
+    /// Write a file rendered without the theme, unless its content is unchanged.
+    fn file(&mut self, path: &str, content: String) -> AppResult<()> {
+        let fingerprint = hex(&Blake2s256::digest(content.as_bytes()));
+        if self.unchanged(path, &fingerprint) {
+            return Ok(());
+        }
+        self.write(path, fingerprint, content)
+    }
+
+    fn unchanged(&mut self, path: &str, fingerprint: &str) -> bool {
+        let unchanged = self.previous.files.get(path).map(String::as_str) == Some(fingerprint)
+            && self.out.join(path).is_file();
+        if unchanged {
+            self.manifest
+                .files
+                .insert(path.to_string(), fingerprint.to_string());
+            self.report.unchanged += 1;
+        }
+        unchanged
+    }
+
+    fn write(&mut self, path: &str, fingerprint: String, content: String) -> AppResult<()> {
+        let file = self.out.join(path);
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&file, content)?;
+
+        self.manifest.files.insert(path.to_string(), fingerprint);
+        self.report.written += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> AppResult<ExportReport> {
+        for path in self.previous.files.keys() {
+            if !self.manifest.files.contains_key(path) {
+                match std::fs::remove_file(self.out.join(path)) {
+                    Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                        return Err(err.into())
+                    }
+                    _ => self.report.removed += 1,
+                }
+            }
+        }
+
+        self.manifest.save(&self.out)?;
+        Ok(self.report)
+    }
+}
+
+/// Whether a slug can be used as a directory name as it is.
+fn is_file_name(slug: &str) -> bool {
+    !slug.is_empty() && slug != "." && slug != ".." && !slug.contains(['/', '\\', '\0'])
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,245 @@
+//! Request ids, W3C trace context and the export of the spans to an
+//! OpenTelemetry collector.
+
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context as _;
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use opentelemetry::{
+    global,
+    trace::{Span as _, SpanKind, Status, TraceContextExt, TraceId, Tracer, TracerProvider as _},
+    KeyValue,
+};
+use opentelemetry_http::HeaderExtractor;
+use opentelemetry_otlp::{Protocol, WithExportConfig};
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator,
+    runtime,
+    trace::{Sampler, TracerProvider},
+    Resource,
+};
+use sea_orm::{metric, DatabaseBackend};
+use tokio::runtime::Runtime;
+use tracing::{field::Empty, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use uuid::Uuid;
+
+use crate::conf::Telemetry;
+
+pub static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest request id taken over from a client, a longer one is replaced.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: RequestId;
+}
+
+/// Id of the request being answered, from its `X-Request-Id` header or
+/// generated.
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// The id of the request the current task answers, if any.
+pub fn request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.0.clone()).ok()
+}
+
+/// Take the `X-Request-Id` of the request over, or give it one, and send it
+/// back with the response. The id is available to the handlers through the
+/// extensions and `request_id`.
+pub async fn set_request_id(mut req: Request, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(&X_REQUEST_ID)
+        .and_then(|id| id.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN)
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let header = HeaderValue::from_str(&id).expect("request id is a valid header value");
+
+    req.extensions_mut().insert(RequestId(id.clone()));
+    let mut response = REQUEST_ID.scope(RequestId(id), next.run(req)).await;
+    response.headers_mut().insert(X_REQUEST_ID.clone(), header);
+    response
+}
+
+/// Span of a request, continuing the trace of its `traceparent` header. Every
+/// event logged while answering it carries the request and trace ids.
+pub fn make_span<B>(req: &axum::http::Request<B>) -> Span {
+    let request_id = req
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.as_str())
+        .unwrap_or_default();
+    let span = tracing::info_span!(
+        "request",
+        method = %req.method(),
+        uri = %req.uri(),
+        version = ?req.version(),
+        request_id,
+        trace_id = Empty,
+        otel.name = %req.method(),
+        otel.kind = "server",
+        otel.status_code = Empty,
+        http.route = Empty,
+        http.response.status_code = Empty,
+    );
+
+    let parent = global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(req.headers()))
+    });
+    span.set_parent(parent);
+    let trace_id = span.context().span().span_context().trace_id();
+    if trace_id != TraceId::INVALID {
+        span.record("trace_id", tracing::field::display(trace_id));
+    }
+
+    span
+}
+
+/// Name the span of the request after the route it matched.
+pub async fn name_span(matched: MatchedPath, req: Request, next: Next) -> Response {
+    let span = Span::current();
+    span.record("http.route", matched.as_str());
+    span.record(
+        "otel.name",
+        format!("{} {}", req.method(), matched.as_str()),
+    );
+    next.run(req).await
+}
+
+/// Log the response and add its status to the span of the request.
+pub fn on_response<B>(response: &axum::http::Response<B>, latency: Duration, span: &Span) {
+    let status = response.status();
+    span.record("http.response.status_code", status.as_u16());
+    if status.is_server_error() {
+        span.record("otel.status_code", "ERROR");
+    }
+
+    tracing::info!(
+        latency = %format!("{} ms", latency.as_millis()),
+        status = status.as_u16(),
+        "finished processing request"
+    );
+}
+
+/// Record a SQL statement as a span of the request which ran it, with the
+/// time it took. Given to the connection as its metric callback.
+pub fn record_query(info: &metric::Info<'_>) {
+    let parent = Span::current().context();
+    if !parent.span().span_context().is_sampled() {
+        return;
+    }
+
+    let end = SystemTime::now();
+    let start = end.checked_sub(info.elapsed).unwrap_or(end);
+    let sql = info.statement.sql.as_str();
+    let operation = sql
+        .split_whitespace()
+        .next()
+        .unwrap_or("QUERY")
+        .to_uppercase();
+    let system = match info.statement.db_backend {
+        DatabaseBackend::MySql => "mysql",
+        DatabaseBackend::Postgres => "postgresql",
+        DatabaseBackend::Sqlite => "sqlite",
+    };
+
+    let tracer = global::tracer("letters");
+    let mut span = tracer
+        .span_builder(operation.clone())
+        .with_kind(SpanKind::Client)
+        .with_start_time(start)
+        .with_attributes([
+            KeyValue::new("db.system", system),
+            KeyValue::new("db.operation", operation),
+            KeyValue::new("db.statement", sql.to_string()),
+        ])
+        .start_with_context(&tracer, &parent);
+    if info.failed {
+        span.set_status(Status::error("query failed"));
+    }
+    span.end_with_timestamp(end);
+}
+
+/// What the OpenTelemetry layer of the logs needs, and what must stay alive
+/// while the spans are exported.
+pub struct Exporter {
+    pub tracer: opentelemetry_sdk::trace::Tracer,
+    provider: TracerProvider,
+    /// Runs the export, the commands build runtimes of their own once the
+    /// logs are set up
+    runtime: Option<Runtime>,
+}
+
+impl Exporter {
+    /// A tracer exporting to `conf.otlp_endpoint`, or only giving the spans
+    /// their trace ids when it is unset. Either way the trace context of the
+    /// requests is propagated.
+    pub fn new(conf: &Telemetry) -> anyhow::Result<Self> {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+
+        let resource = Resource::new([
+            KeyValue::new("service.name", conf.service_name.clone()),
+            KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+        ]);
+        let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(conf.sample_ratio)));
+        let builder = TracerProvider::builder()
+            .with_resource(resource)
+            .with_sampler(sampler);
+
+        let (provider, runtime) = match &conf.otlp_endpoint {
+            Some(endpoint) => {
+                let protocol = match conf.otlp_protocol.as_str() {
+                    "http/json" => Protocol::HttpJson,
+                    _ => Protocol::HttpBinary,
+                };
+                let exporter = opentelemetry_otlp::SpanExporter::builder()
+                    .with_http()
+                    .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+                    .with_protocol(protocol)
+                    .build()
+                    .context("Build OTLP exporter failed")?;
+
+                let runtime = tokio::runtime::Builder::new_multi_thread()
+                    .worker_threads(1)
+                    .thread_name("telemetry")
+                    .enable_all()
+                    .build()?;
+                let _guard = runtime.enter();
+                let provider = builder
+                    .with_batch_exporter(exporter, runtime::Tokio)
+                    .build();
+                (provider, Some(runtime))
+            }
+            None => (builder.build(), None),
+        };
+
+        global::set_tracer_provider(provider.clone());
+        Ok(Self {
+            tracer: provider.tracer("letters"),
+            provider,
+            runtime,
+        })
+    }
+}
+
+impl Drop for Exporter {
+    /// Send the spans still waiting in the batch.
+    fn drop(&mut self) {
+        if self.runtime.is_some() {
+            if let Err(err) = self.provider.shutdown() {
+                eprintln!("Export the last spans failed: {}", err);
+            }
+        }
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_timeout(Duration::from_secs(5));
+        }
+    }
+}
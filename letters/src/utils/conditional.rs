@@ -0,0 +1,68 @@
+use std::time::SystemTime;
+
+use axum::{
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use axum_extra::headers::{ETag, HeaderMapExt, IfModifiedSince, IfNoneMatch, LastModified};
+use blake2::{Blake2s256, Digest};
+use sea_orm::prelude::DateTimeUtc;
+
+/// Answer a GET request with `body`, or with `304 Not Modified` when the
+/// client already holds the same representation.
+///
+/// The ETag is derived from the body, `If-None-Match` takes precedence over
+/// `If-Modified-Since` as RFC 9110 requires.
+pub fn respond(
+    req_headers: &HeaderMap,
+    content_type: &'static str,
+    last_modified: Option<DateTimeUtc>,
+    body: String,
+) -> Response {
+    let etag = etag(body.as_bytes());
+    let last_modified = last_modified.map(SystemTime::from);
+
+    let mut headers = HeaderMap::new();
+    headers.typed_insert(etag.clone());
+    if let Some(modified) = last_modified {
+        headers.typed_insert(LastModified::from(modified));
+    }
+
+    if is_not_modified(req_headers, &etag, last_modified) {
+        return (StatusCode::NOT_MODIFIED, headers).into_response();
+    }
+
+    headers.insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static(content_type),
+    );
+    (headers, body).into_response()
+}
+
+/// ETag of a representation, derived from its body.
+pub fn etag(body: &[u8]) -> ETag {
+    let digest = Blake2s256::digest(body);
+    format!("\"{}\"", hex(&digest[..16]))
+        .parse()
+        .expect("hex digest is a valid etag")
+}
+
+/// Whether the client already holds the representation with `etag` and
+/// `last_modified`, per the conditional headers of its request.
+pub fn is_not_modified(
+    req_headers: &HeaderMap,
+    etag: &ETag,
+    last_modified: Option<SystemTime>,
+) -> bool {
+    match req_headers.typed_get::<IfNoneMatch>() {
+        Some(if_none_match) => !if_none_match.precondition_passes(etag),
+        None => match (req_headers.typed_get::<IfModifiedSince>(), last_modified) {
+            (Some(since), Some(modified)) => !since.is_modified(modified),
+            _ => false,
+        },
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
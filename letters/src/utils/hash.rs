@@ -27,3 +27,8 @@ pub fn verify_password(password: &str, password_hash: &str) -> AppResult<()> {
         .verify_password(password.as_bytes(), &password_hash)
         .map_err(|_| AppError::InvalidInput("The password is not correct".to_string()))
 }
+
+/// A random password, for the accounts nobody logs in with until it is reset.
+pub fn random_password() -> String {
+    SaltString::generate(&mut OsRng).to_string()
+}
@@ -9,6 +9,7 @@ use axum_extra::{
     TypedHeader,
 };
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, TokenData, Validation};
+use secrecy::ExposeSecret;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use utoipa::ToSchema;
@@ -66,7 +67,8 @@ where
         let state = Arc::<AppState>::from_ref(state);
 
         // Decode the user data
-        let token_data = AuthClaims::decode(bearer.token(), &state.conf.auth.secret)?;
+        let token_data =
+            AuthClaims::decode(bearer.token(), state.conf.auth.secret.expose_secret())?;
         Ok(token_data.claims)
     }
 }
@@ -85,6 +87,7 @@ use axum_extra::{
     TypedHeader,
 };
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, TokenData, Validation};
+use secrecy::ExposeSecret;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -139,7 +142,7 @@ where
 
         let state = Arc::<AppState>::from_ref(state);
 
-        let token_data = decode(bearer.token(), &state.conf.auth.secret)
+        let token_data = decode(bearer.token(), state.conf.auth.secret.expose_secret())
             .map_err(|_| Error::Auth(AuthError::InvalidToken))?;
 
         Ok(token_data.claims)
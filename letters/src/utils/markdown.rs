@@ -0,0 +1,37 @@
+use pulldown_cmark::{html, Event, Options, Parser, TagEnd};
+
+/// Render the markdown content of an article into html.
+pub fn to_html(content: &str) -> String {
+    let parser = Parser::new_ext(
+        content,
+        Options::ENABLE_TABLES
+            | Options::ENABLE_FOOTNOTES
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_TASKLISTS,
+    );
+
+    let mut output = String::with_capacity(content.len() * 3 / 2);
+    html::push_html(&mut output, parser);
+    output
+}
+
+/// Plain text of the first `max_chars` characters of the markdown content.
+pub fn excerpt(content: &str, max_chars: usize) -> String {
+    let mut text = String::new();
+    for event in Parser::new(content) {
+        match event {
+            Event::Text(s) | Event::Code(s) => text.push_str(&s),
+            Event::SoftBreak | Event::HardBreak | Event::End(TagEnd::Paragraph) => text.push(' '),
+            _ => {}
+        }
+        if text.chars().count() > max_chars {
+            break;
+        }
+    }
+
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text,
+    }
+}
@@ -1,3 +1,5 @@
 pub mod avatar;
+pub mod conditional;
 pub mod hash;
 pub mod jwt;
+pub mod markdown;
@@ -0,0 +1,65 @@
+use std::sync::Once;
+
+use axum::http::StatusCode;
+use letters::{conf::Conf, log};
+use serde_json::json;
+
+use crate::helpers::TestApp;
+
+/// Logging is global to the process, it is set up once for every test and
+/// kept quiet.
+fn setup_logging() {
+    static SETUP: Once = Once::new();
+    SETUP.call_once(|| {
+        std::env::remove_var("RUST_LOG");
+        let mut conf = Conf::default();
+        conf.log.level = "off".to_string();
+        conf.log.dir = None;
+        std::mem::forget(log::setup(&conf).unwrap());
+    });
+}
+
+#[tokio::test]
+async fn log_level_can_be_changed_at_runtime() {
+    setup_logging();
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+
+    let resp = app
+        .put(
+            "/api/v1/admin/log-level",
+            Some(&token),
+            json!({ "level": "off,letters::cmd=debug" }),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+
+    let resp = app.get("/api/v1/admin/log-level", Some(&token)).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    let level = resp.json()["level"].as_str().unwrap().to_string();
+    assert!(level.contains("letters::cmd=debug"), "{}", level);
+}
+
+#[tokio::test]
+async fn invalid_log_level_is_refused() {
+    setup_logging();
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+
+    let resp = app
+        .put(
+            "/api/v1/admin/log-level",
+            Some(&token),
+            json!({ "level": "letters=loud" }),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn log_level_needs_a_token() {
+    let app = TestApp::spawn().await;
+
+    let resp = app.get("/api/v1/admin/log-level", None).await;
+    assert_ne!(resp.status, StatusCode::OK);
+}
@@ -0,0 +1,220 @@
+use axum::http::{header, StatusCode};
+use serde_json::json;
+
+use crate::helpers::TestApp;
+
+#[tokio::test]
+async fn create_article_needs_a_token() {
+    let app = TestApp::spawn().await;
+
+    let resp = app
+        .post(
+            "/api/v1/articles",
+            None,
+            json!({ "title": "Hello", "content": "World" }),
+        )
+        .await;
+
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn create_and_get_article() {
+    let app = TestApp::spawn().await;
+    let (user_id, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let tag_id = app.create_tag(&token, "async").await;
+
+    let article_id = app
+        .create_article(
+            &token,
+            json!({
+                "title": "Futures",
+                "content": "Poll until ready",
+                "status": 1,
+                "category_id": category_id,
+                "tag_ids": [tag_id],
+                "tag_names": ["tokio"],
+            }),
+        )
+        .await;
+
+    let resp = app
+        .get(&format!("/api/v1/articles/{}", article_id), None)
+        .await;
+    assert_eq!(resp.status, StatusCode::OK);
+    let body = resp.json();
+    assert_eq!(body["title"], "Futures");
+    assert_eq!(body["content"], "Poll until ready");
+    assert_eq!(body["author"]["id"], user_id);
+    assert_eq!(body["author"]["username"], "alice");
+    assert_eq!(body["category"]["id"], category_id);
+    let mut tags: Vec<_> = body["tags"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tag| tag["name"].as_str().unwrap().to_string())
+        .collect();
+    tags.sort();
+    assert_eq!(tags, ["async", "tokio"]);
+}
+
+#[tokio::test]
+async fn get_a_missing_article() {
+    let app = TestApp::spawn().await;
+
+    let resp = app.get("/api/v1/articles/42", None).await;
+
+    assert_eq!(resp.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn list_articles_page_by_page() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    for title in ["One", "Two", "Three"] {
+        app.publish(&token, category_id, title, "Text").await;
+    }
+
+    let resp = app.get("/api/v1/articles?page=1&per_page=2", None).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.json().as_array().unwrap().len(), 2);
+
+    let resp = app.get("/api/v1/articles?page=2&per_page=2", None).await;
+    let body = resp.json();
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["title"], "Three");
+}
+
+#[tokio::test]
+async fn update_article() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let article_id = app.publish(&token, category_id, "Draft", "Text").await;
+
+    let resp = app
+        .put(
+            &format!("/api/v1/articles/{}", article_id),
+            Some(&token),
+            json!({ "title": "Final", "tag_names": ["edited"] }),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::OK);
+
+    let body = app
+        .get(&format!("/api/v1/articles/{}", article_id), None)
+        .await
+        .json();
+    assert_eq!(body["title"], "Final");
+    assert_eq!(body["tags"][0]["name"], "edited");
+}
+
+#[tokio::test]
+async fn delete_article_moves_it_to_the_trash() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let article_id = app.publish(&token, category_id, "Gone", "Text").await;
+
+    let resp = app
+        .delete(&format!("/api/v1/articles/{}", article_id), Some(&token))
+        .await;
+    assert_eq!(resp.status, StatusCode::OK);
+
+    let resp = app
+        .get(&format!("/api/v1/articles/{}", article_id), None)
+        .await;
+    assert_eq!(resp.status, StatusCode::NOT_FOUND);
+
+    let body = app.get("/api/v1/trash", Some(&token)).await.json();
+    assert_eq!(body["articles"][0]["id"], article_id);
+}
+
+#[tokio::test]
+async fn get_related_articles() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let article = |title: &str| {
+        json!({
+            "title": title,
+            "content": "Ownership and borrowing in rust",
+            "status": 1,
+            "category_id": category_id,
+            "tag_names": ["ownership"],
+        })
+    };
+    let first = app.create_article(&token, article("Ownership")).await;
+    let second = app.create_article(&token, article("Borrowing")).await;
+
+    let resp = app
+        .get(&format!("/api/v1/articles/{}/related?limit=5", first), None)
+        .await;
+
+    assert_eq!(resp.status, StatusCode::OK);
+    let body = resp.json();
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["id"], second);
+}
+
+#[tokio::test]
+async fn get_article_seo() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let article_id = app
+        .create_article(
+            &token,
+            json!({
+                "title": "Futures",
+                "slug": "futures",
+                "content": "Poll until ready",
+                "summary": "All about polling",
+                "status": 1,
+                "category_id": category_id,
+            }),
+        )
+        .await;
+
+    let resp = app
+        .get(&format!("/api/v1/articles/{}/seo", article_id), None)
+        .await;
+
+    assert_eq!(resp.status, StatusCode::OK);
+    let body = resp.json();
+    assert_eq!(
+        body["canonical_url"],
+        app.state.conf.site.article_url("futures")
+    );
+    assert_eq!(body["description"], "All about polling");
+    assert_eq!(body["open_graph"]["author"], "alice");
+    assert_eq!(body["open_graph"]["section"], "rust");
+}
+
+#[tokio::test]
+async fn export_articles_of_the_current_user() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let (_, other) = app.login("bob").await;
+    let category_id = app.create_category(&token, "rust").await;
+    app.create_article(
+        &token,
+        json!({ "title": "Mine", "slug": "mine", "content": "Text", "category_id": category_id }),
+    )
+    .await;
+    app.create_article(
+        &other,
+        json!({ "title": "Theirs", "slug": "theirs", "content": "Text", "category_id": category_id }),
+    )
+    .await;
+
+    let resp = app.get("/api/v1/articles/export", Some(&token)).await;
+
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.headers[header::CONTENT_TYPE], "application/zip");
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(resp.body.to_vec())).unwrap();
+    assert_eq!(zip.len(), 1);
+    assert_eq!(zip.by_index(0).unwrap().name(), "mine.md");
+}
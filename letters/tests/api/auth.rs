@@ -0,0 +1,66 @@
+use axum::http::StatusCode;
+use serde_json::json;
+
+use crate::helpers::{email, TestApp, PASSWORD};
+
+#[tokio::test]
+async fn authorize_returns_a_bearer_token() {
+    let app = TestApp::spawn().await;
+    app.create_user("alice").await;
+
+    let resp = app
+        .post(
+            "/api/v1/authorize",
+            None,
+            json!({ "email": email("alice"), "password": PASSWORD }),
+        )
+        .await;
+
+    assert_eq!(resp.status, StatusCode::OK);
+    let body = resp.json();
+    assert_eq!(body["token_type"], "Bearer");
+    assert_eq!(body["expires_in"], 3600);
+    assert!(!body["access_token"].as_str().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn authorize_rejects_a_wrong_password() {
+    let app = TestApp::spawn().await;
+    app.create_user("alice").await;
+
+    let resp = app
+        .post(
+            "/api/v1/authorize",
+            None,
+            json!({ "email": email("alice"), "password": "not the password" }),
+        )
+        .await;
+
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn authorize_rejects_an_unknown_user() {
+    let app = TestApp::spawn().await;
+
+    let resp = app
+        .post(
+            "/api/v1/authorize",
+            None,
+            json!({ "email": email("nobody"), "password": PASSWORD }),
+        )
+        .await;
+
+    assert_eq!(resp.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn protected_routes_need_a_valid_token() {
+    let app = TestApp::spawn().await;
+
+    let resp = app.get("/api/v1/users/profile", None).await;
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+
+    let resp = app.get("/api/v1/users/profile", Some("not a token")).await;
+    assert_eq!(resp.status, StatusCode::UNAUTHORIZED);
+}
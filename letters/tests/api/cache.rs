@@ -0,0 +1,191 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use serde_json::json;
+
+use crate::helpers::TestApp;
+
+#[tokio::test]
+async fn public_responses_are_kept_until_their_article_changes() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "Rust").await;
+    let article_id = app.publish(&token, category_id, "Hello", "Hello").await;
+    let uri = format!("/api/v1/articles/{}", article_id);
+
+    let resp = app.get(&uri, None).await;
+    assert_eq!(resp.headers["x-cache"], "MISS");
+    let resp = app.get(&uri, None).await;
+    assert_eq!(resp.headers["x-cache"], "HIT");
+    assert_eq!(resp.json()["title"], "Hello");
+
+    let resp = app
+        .put(&uri, Some(&token), json!({ "title": "Bonjour" }))
+        .await;
+    assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+
+    let resp = app.get(&uri, None).await;
+    assert_eq!(resp.headers["x-cache"], "MISS");
+    assert_eq!(resp.json()["title"], "Bonjour");
+}
+
+#[tokio::test]
+async fn routes_of_different_routers_are_kept_apart() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    app.create_category(&token, "Rust").await;
+    app.create_tag(&token, "async").await;
+
+    app.get("/api/v1/categories", None).await;
+    let resp = app.get("/api/v1/tags", None).await;
+    assert_eq!(resp.headers["x-cache"], "MISS");
+    assert_eq!(resp.json()[0]["name"], "async");
+}
+
+#[tokio::test]
+async fn writes_only_evict_the_listings_they_touch() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let rust = app.create_category(&token, "Rust").await;
+    let go = app.create_category(&token, "Go").await;
+    app.publish(&token, go, "Goroutines", "Go").await;
+    let rust_uri = format!("/api/v1/categories/{}/articles", rust);
+    let go_uri = format!("/api/v1/categories/{}/articles", go);
+    app.get(&rust_uri, None).await;
+    app.get(&go_uri, None).await;
+
+    app.publish(&token, rust, "Ownership", "Rust").await;
+
+    let resp = app.get(&rust_uri, None).await;
+    assert_eq!(resp.headers["x-cache"], "MISS");
+    assert_eq!(resp.json().as_array().unwrap().len(), 1);
+    let resp = app.get(&go_uri, None).await;
+    assert_eq!(resp.headers["x-cache"], "HIT");
+}
+
+#[tokio::test]
+async fn related_articles_are_evicted_by_any_article() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "Rust").await;
+    let article_id = app
+        .publish(&token, category_id, "Ownership", "Borrowing")
+        .await;
+    let uri = format!("/api/v1/articles/{}/related", article_id);
+    app.get(&uri, None).await;
+    assert_eq!(app.get(&uri, None).await.headers["x-cache"], "HIT");
+
+    app.publish(&token, category_id, "Lifetimes", "Borrowing")
+        .await;
+
+    let resp = app.get(&uri, None).await;
+    assert_eq!(resp.headers["x-cache"], "MISS");
+}
+
+#[tokio::test]
+async fn chapters_are_evicted_with_the_other_chapters_of_their_series() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "Rust").await;
+    let series_id = app.create_series(&token, "Futures").await;
+    let mut ids = vec![];
+    for (title, slug) in [("Pinning", "pinning"), ("Wakers", "wakers")] {
+        let id = app
+            .create_article(
+                &token,
+                json!({
+                    "title": title,
+                    "slug": slug,
+                    "content": title,
+                    "status": 1,
+                    "category_id": category_id,
+                    "series_ids": [series_id],
+                }),
+            )
+            .await;
+        ids.push(id);
+    }
+    let api_uri = format!("/api/v1/articles/{}", ids[0]);
+    for uri in [api_uri.as_str(), "/articles/pinning"] {
+        app.get(uri, None).await;
+        assert_eq!(app.get(uri, None).await.headers["x-cache"], "HIT");
+    }
+
+    let resp = app
+        .put(
+            &format!("/api/v1/articles/{}", ids[1]),
+            Some(&token),
+            json!({ "title": "Executors", "slug": "executors" }),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+
+    let resp = app.get(&api_uri, None).await;
+    assert_eq!(resp.headers["x-cache"], "MISS");
+    assert_eq!(resp.json()["series"][0]["next"]["slug"], "executors");
+    let resp = app.get("/articles/pinning", None).await;
+    assert_eq!(resp.headers["x-cache"], "MISS");
+    let body = String::from_utf8_lossy(&resp.body);
+    assert!(body.contains("Executors"), "{}", body);
+    assert!(body.contains("&#x2f;articles&#x2f;executors"), "{}", body);
+}
+
+#[tokio::test]
+async fn moving_an_article_evicts_its_previous_category() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let rust = app.create_category(&token, "Rust").await;
+    let go = app.create_category(&token, "Go").await;
+    let article_id = app.publish(&token, rust, "Ownership", "Rust").await;
+    let rust_uri = format!("/api/v1/categories/{}/articles", rust);
+    assert_eq!(
+        app.get(&rust_uri, None)
+            .await
+            .json()
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let resp = app
+        .put(
+            &format!("/api/v1/articles/{}", article_id),
+            Some(&token),
+            json!({ "category_id": go }),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+
+    let resp = app.get(&rust_uri, None).await;
+    assert_eq!(resp.headers["x-cache"], "MISS");
+    assert!(resp.json().as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn cached_responses_answer_conditional_requests() {
+    let app = TestApp::spawn().await;
+    app.get("/api/v1/articles", None).await;
+
+    let resp = app.get("/api/v1/articles", None).await;
+    let etag = resp.headers["etag"].clone();
+    let request = Request::get("/api/v1/articles")
+        .header("if-none-match", etag.clone())
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.send(request).await;
+    assert_eq!(resp.status, StatusCode::NOT_MODIFIED);
+    assert_eq!(resp.headers["etag"], etag);
+    assert!(resp.body.is_empty());
+}
+
+#[tokio::test]
+async fn requests_with_credentials_are_not_cached() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+
+    let resp = app.get("/api/v1/articles", Some(&token)).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert!(resp.headers.get("x-cache").is_none());
+}
@@ -0,0 +1,120 @@
+use axum::http::StatusCode;
+use serde_json::json;
+
+use crate::helpers::TestApp;
+
+#[tokio::test]
+async fn create_and_list_categories() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+
+    app.create_category(&token, "rust").await;
+    app.create_category(&token, "life").await;
+
+    let resp = app.get("/api/v1/categories", None).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    let names: Vec<_> = resp
+        .json()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|category| category["name"].as_str().unwrap().to_string())
+        .collect();
+    assert!(names.contains(&"rust".to_string()));
+    assert!(names.contains(&"life".to_string()));
+}
+
+#[tokio::test]
+async fn create_category_needs_a_token() {
+    let app = TestApp::spawn().await;
+
+    let resp = app
+        .post(
+            "/api/v1/categories",
+            None,
+            json!({ "name": "rust", "status": 1 }),
+        )
+        .await;
+
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn create_category_rejects_a_duplicate_name() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    app.create_category(&token, "rust").await;
+
+    let resp = app
+        .post(
+            "/api/v1/categories",
+            Some(&token),
+            json!({ "name": "rust", "status": 1 }),
+        )
+        .await;
+
+    assert_eq!(resp.status, StatusCode::NOT_FOUND);
+    assert_eq!(resp.json()["error"], "Category name already exists");
+}
+
+#[tokio::test]
+async fn update_category() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+
+    let resp = app
+        .put(
+            &format!("/api/v1/categories/{}", category_id),
+            Some(&token),
+            json!({ "name": "rustlang", "description": "Crabs" }),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::OK);
+
+    let body = app.get("/api/v1/categories", None).await.json();
+    let category = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|category| category["name"] == "rustlang")
+        .unwrap();
+    assert_eq!(category["description"], "Crabs");
+}
+
+#[tokio::test]
+async fn get_category_articles() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let rust = app.create_category(&token, "rust").await;
+    let life = app.create_category(&token, "life").await;
+    let article_id = app.publish(&token, rust, "Ownership", "Moves").await;
+    app.publish(&token, life, "Holidays", "Beach").await;
+
+    let resp = app
+        .get(&format!("/api/v1/categories/{}/articles", rust), None)
+        .await;
+
+    assert_eq!(resp.status, StatusCode::OK);
+    let body = resp.json();
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["id"], article_id);
+}
+
+#[tokio::test]
+async fn delete_category_moves_it_to_the_trash() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+
+    let resp = app
+        .delete(&format!("/api/v1/categories/{}", category_id), Some(&token))
+        .await;
+    assert_eq!(resp.status, StatusCode::OK);
+
+    let body = app.get("/api/v1/categories", None).await.json();
+    assert!(body.as_array().unwrap().iter().all(|c| c["name"] != "rust"));
+
+    let body = app.get("/api/v1/trash", Some(&token)).await.json();
+    assert_eq!(body["categories"][0]["id"], category_id);
+}
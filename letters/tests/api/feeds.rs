@@ -0,0 +1,158 @@
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use serde_json::json;
+
+use crate::helpers::TestApp;
+
+#[tokio::test]
+async fn feeds_of_the_site_in_every_format() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    app.publish(&token, category_id, "Pinning", "Self referential futures")
+        .await;
+    app.create_article(
+        &token,
+        json!({ "title": "Draft", "content": "Not yet", "category_id": category_id }),
+    )
+    .await;
+
+    for (uri, content_type) in [
+        ("/feed.xml", "application/rss+xml; charset=utf-8"),
+        ("/atom.xml", "application/atom+xml; charset=utf-8"),
+        ("/feed.json", "application/feed+json; charset=utf-8"),
+    ] {
+        let resp = app.get(uri, None).await;
+
+        assert_eq!(resp.status, StatusCode::OK, "{}", uri);
+        assert_eq!(resp.headers[header::CONTENT_TYPE], content_type);
+        let body = String::from_utf8_lossy(&resp.body);
+        assert!(body.contains("Pinning"), "{}", uri);
+        assert!(!body.contains("Draft"), "{}", uri);
+    }
+
+    let body = app.get("/feed.json", None).await.json();
+    assert_eq!(body["items"].as_array().unwrap().len(), 1);
+    assert_eq!(body["items"][0]["title"], "Pinning");
+}
+
+#[tokio::test]
+async fn feeds_scoped_to_a_category_tag_series_or_author() {
+    let app = TestApp::spawn().await;
+    let (user_id, token) = app.login("alice").await;
+    let (_, other) = app.login("bob").await;
+    let rust = app.create_category(&token, "rust").await;
+    let tag_id = app.create_tag(&token, "async").await;
+    let series_id = app.create_series(&token, "Futures").await;
+    app.create_article(
+        &token,
+        json!({
+            "title": "Pinning",
+            "content": "Self referential futures",
+            "status": 1,
+            "category_id": rust,
+            "tag_ids": [tag_id],
+            "series_ids": [series_id],
+        }),
+    )
+    .await;
+    let go = app.create_category(&other, "go").await;
+    app.publish(&other, go, "Goroutines", "Green threads").await;
+
+    for uri in [
+        format!("/categories/{}/feed.json", rust),
+        format!("/tags/{}/feed.json", tag_id),
+        format!("/series/{}/feed.json", series_id),
+        format!("/authors/{}/feed.json", user_id),
+    ] {
+        let resp = app.get(&uri, None).await;
+
+        assert_eq!(resp.status, StatusCode::OK, "{}", uri);
+        let body = resp.json();
+        let items = body["items"].as_array().unwrap();
+        assert_eq!(items.len(), 1, "{}", uri);
+        assert_eq!(items[0]["title"], "Pinning");
+    }
+
+    let resp = app.get(&format!("/categories/{}/atom.xml", go), None).await;
+    assert!(String::from_utf8_lossy(&resp.body).contains("Goroutines"));
+}
+
+#[tokio::test]
+async fn feeds_of_a_missing_scope_or_format() {
+    let app = TestApp::spawn().await;
+
+    let resp = app.get("/categories/42/feed.xml", None).await;
+    assert_eq!(resp.status, StatusCode::NOT_FOUND);
+
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let resp = app
+        .get(&format!("/categories/{}/feed.txt", category_id), None)
+        .await;
+    assert!(resp.status.is_client_error());
+}
+
+#[tokio::test]
+async fn feeds_answer_conditional_requests() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    app.publish(&token, category_id, "Pinning", "Self referential futures")
+        .await;
+    let etag = app.get("/atom.xml", None).await.headers[header::ETAG].clone();
+
+    let req = Request::get("/atom.xml")
+        .header(header::IF_NONE_MATCH, &etag)
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.send(req).await;
+
+    assert_eq!(resp.status, StatusCode::NOT_MODIFIED);
+    assert!(resp.body.is_empty());
+}
+
+#[tokio::test]
+async fn editing_an_article_moves_the_last_modified_date() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let article_id = app.publish(&token, category_id, "Hello", "Hello").await;
+    let resp = app.get("/feed.xml", None).await;
+    let last_modified = resp.headers[header::LAST_MODIFIED].clone();
+
+    // The header has a precision of a second.
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    app.put(
+        &format!("/api/v1/articles/{}", article_id),
+        Some(&token),
+        json!({ "title": "Bonjour" }),
+    )
+    .await;
+
+    let req = Request::get("/feed.xml")
+        .header(header::IF_MODIFIED_SINCE, &last_modified)
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.send(req).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_ne!(resp.headers[header::LAST_MODIFIED], last_modified);
+}
+
+#[tokio::test]
+async fn empty_feeds_have_no_last_modified_date() {
+    let app = TestApp::spawn().await;
+
+    for uri in ["/feed.xml", "/atom.xml", "/feed.json"] {
+        let resp = app.get(uri, None).await;
+
+        assert_eq!(resp.status, StatusCode::OK);
+        assert!(!resp.headers.contains_key(header::LAST_MODIFIED));
+    }
+    let body = app.get("/feed.xml", None).await.body;
+    assert!(!String::from_utf8_lossy(&body).contains("lastBuildDate"));
+}
@@ -0,0 +1,150 @@
+use axum::http::{header, StatusCode};
+use serde_json::json;
+
+use crate::helpers::TestApp;
+
+/// A published article in a category, a tag and a series, and a draft.
+/// Returns the ids of the category, the tag, the series and the author.
+async fn site(app: &TestApp) -> (i32, i32, i32, i32) {
+    let (user_id, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let tag_id = app.create_tag(&token, "async").await;
+    let series_id = app.create_series(&token, "Futures").await;
+    app.create_article(
+        &token,
+        json!({
+            "title": "Pinning",
+            "slug": "pinning",
+            "content": "Self referential **futures**",
+            "status": 1,
+            "category_id": category_id,
+            "tag_ids": [tag_id],
+            "series_ids": [series_id],
+        }),
+    )
+    .await;
+    app.create_article(
+        &token,
+        json!({
+            "title": "Draft",
+            "slug": "draft",
+            "content": "Not yet",
+            "category_id": category_id,
+        }),
+    )
+    .await;
+    (category_id, tag_id, series_id, user_id)
+}
+
+fn html(body: &[u8]) -> String {
+    String::from_utf8_lossy(body).into_owned()
+}
+
+#[tokio::test]
+async fn index_lists_the_published_articles() {
+    let app = TestApp::spawn().await;
+    site(&app).await;
+
+    for uri in ["/", "/page/1"] {
+        let resp = app.get(uri, None).await;
+
+        assert_eq!(resp.status, StatusCode::OK, "{}", uri);
+        assert_eq!(
+            resp.headers[header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+        let body = html(&resp.body);
+        assert!(body.contains("Pinning"), "{}", uri);
+        assert!(!body.contains("Draft"), "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn article_page_renders_the_content_and_the_series() {
+    let app = TestApp::spawn().await;
+    site(&app).await;
+
+    let resp = app.get("/articles/pinning", None).await;
+
+    assert_eq!(resp.status, StatusCode::OK);
+    let body = html(&resp.body);
+    assert!(body.contains("<h1>Pinning</h1>"));
+    assert!(body.contains("<strong>futures</strong>"));
+    assert!(body.contains("#async"));
+    assert!(body.contains("Futures</a> (1/1)"));
+}
+
+#[tokio::test]
+async fn listing_pages_of_a_category_tag_series_and_author() {
+    let app = TestApp::spawn().await;
+    let (category_id, tag_id, series_id, user_id) = site(&app).await;
+
+    for uri in [
+        format!("/categories/{}", category_id),
+        format!("/categories/{}/page/1", category_id),
+        format!("/tags/{}", tag_id),
+        format!("/tags/{}/page/1", tag_id),
+        format!("/series/{}", series_id),
+        format!("/series/{}/page/1", series_id),
+        format!("/authors/{}", user_id),
+        format!("/authors/{}/page/1", user_id),
+        "/archive".to_string(),
+    ] {
+        let resp = app.get(&uri, None).await;
+
+        assert_eq!(resp.status, StatusCode::OK, "{}", uri);
+        let body = html(&resp.body);
+        assert!(body.contains("Pinning"), "{}", uri);
+        assert!(!body.contains("Draft"), "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn missing_pages_render_the_not_found_page() {
+    let app = TestApp::spawn().await;
+    let (category_id, ..) = site(&app).await;
+
+    for uri in [
+        "/articles/draft".to_string(),
+        "/articles/missing".to_string(),
+        "/page/2".to_string(),
+        "/page/0".to_string(),
+        format!("/categories/{}/page/2", category_id),
+        "/categories/42".to_string(),
+        "/tags/42".to_string(),
+        "/series/42".to_string(),
+        "/authors/42".to_string(),
+    ] {
+        let resp = app.get(&uri, None).await;
+
+        assert_eq!(resp.status, StatusCode::NOT_FOUND, "{}", uri);
+        assert!(html(&resp.body).contains("Page not found"), "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn search_page_lists_the_hits() {
+    let app = TestApp::spawn().await;
+    site(&app).await;
+
+    let resp = app.get("/search?q=futures", None).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert!(html(&resp.body).contains("Pinning"));
+
+    let resp = app.get("/search", None).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert!(!html(&resp.body).contains("Pinning"));
+}
+
+#[tokio::test]
+async fn search_page_stays_within_the_results_limit() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    app.publish(&token, category_id, "Pinning", "Self referential futures")
+        .await;
+
+    let resp = app.get("/search?q=futures&page=1000000000", None).await;
+
+    assert_eq!(resp.status, StatusCode::OK);
+}
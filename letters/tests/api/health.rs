@@ -0,0 +1,88 @@
+use axum::http::StatusCode;
+use migration::{Migrator, MigratorTrait};
+use serde_json::json;
+
+use crate::helpers::{email, TestApp};
+
+#[tokio::test]
+async fn healthz_answers() {
+    let app = TestApp::spawn().await;
+
+    let resp = app.get("/healthz", None).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.body, "ok");
+}
+
+#[tokio::test]
+async fn readyz_checks_the_database_and_the_migrations() {
+    let app = TestApp::spawn().await;
+
+    let resp = app.get("/readyz", None).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.json(), json!({ "database": "ok", "migrations": "ok" }));
+}
+
+#[tokio::test]
+async fn readyz_is_unavailable_while_migrations_are_pending() {
+    let app = TestApp::spawn().await;
+    Migrator::down(app.state.dbc.as_ref(), Some(1))
+        .await
+        .unwrap();
+
+    let resp = app.get("/readyz", None).await;
+    assert_eq!(resp.status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        resp.json(),
+        json!({ "database": "ok", "migrations": "1 pending" })
+    );
+}
+
+#[tokio::test]
+async fn readyz_hides_the_database_errors() {
+    let app = TestApp::spawn().await;
+    app.state.dbc.as_ref().clone().close().await.unwrap();
+
+    let resp = app.get("/readyz", None).await;
+    assert_eq!(resp.status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        resp.json(),
+        json!({ "database": "unavailable", "migrations": "unknown" })
+    );
+}
+
+#[tokio::test]
+async fn metrics_count_requests_by_route_and_domain_events() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "Rust").await;
+    app.publish(&token, category_id, "Hello", "Hello world")
+        .await;
+    app.get("/api/v1/tags", None).await;
+    app.get("/api/v1/tags", None).await;
+    let resp = app
+        .post(
+            "/api/v1/authorize",
+            None,
+            json!({ "email": email("alice"), "password": "wrong" }),
+        )
+        .await;
+    assert_ne!(resp.status, StatusCode::OK);
+
+    let resp = app.get("/metrics", None).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert!(resp.headers["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+    let body = String::from_utf8(resp.body.to_vec()).unwrap();
+    for line in [
+        r#"letters_http_requests_total{method="GET",route="/api/v1/tags",status="200"} 2"#,
+        r#"letters_http_request_duration_seconds_count{method="GET",route="/api/v1/tags"} 2"#,
+        r#"letters_http_request_duration_seconds_bucket{method="GET",route="/api/v1/tags",le="+Inf"} 2"#,
+        "letters_articles_published_total 1",
+        "letters_logins_failed_total 1",
+        "letters_comments 0",
+    ] {
+        assert!(body.lines().any(|l| l == line), "{} not in\n{}", line, body);
+    }
+}
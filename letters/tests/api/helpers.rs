@@ -0,0 +1,270 @@
+use std::sync::Arc;
+
+use argon2::{
+    password_hash::{rand_core::OsRng, SaltString},
+    Argon2, PasswordHasher,
+};
+use axum::{
+    body::{self, Body, Bytes},
+    http::{header, HeaderMap, Method, Request, StatusCode},
+    Router,
+};
+use base64::{engine::general_purpose, Engine as _};
+use letters::{
+    app::{self, AppState},
+    conf::Conf,
+};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{ActiveModelTrait, ColumnTrait, Database, EntityTrait, QueryFilter, QueryOrder, Set};
+use secrecy::SecretString;
+use serde_json::{json, Value};
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+use entity::{
+    article as ArticleEntity, category as CategoryEntity, series as SeriesEntity, tag as TagEntity,
+};
+
+/// Password of every user seeded by the tests.
+pub const PASSWORD: &str = "Pa$$wd123";
+
+/// The application over a database and directories of its own, dropped with
+/// it.
+pub struct TestApp {
+    pub state: Arc<AppState>,
+    router: Router,
+    _dir: TempDir,
+}
+
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl TestResponse {
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap_or_else(|err| {
+            panic!(
+                "{} is not JSON: {}",
+                String::from_utf8_lossy(&self.body),
+                err
+            )
+        })
+    }
+}
+
+impl TestApp {
+    /// Build the router against a fresh SQLite database with every migration
+    /// applied. Nothing is bound, the requests are sent to the router itself.
+    pub async fn spawn() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut conf = Conf::default();
+        conf.auth.secret = SecretString::new("test secret".to_string());
+        conf.auth.timeout_seconds = 3600;
+        conf.trash.purge_interval_hours = 0;
+        conf.search.index_dir = dir.path().join("search").to_string_lossy().into_owned();
+        conf.media.local_dir = dir.path().join("media").to_string_lossy().into_owned();
+        conf.theme.dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../themes/default").to_string();
+        conf.theme.frontend = true;
+        conf.database.url = Some(SecretString::new(format!(
+            "sqlite://{}?mode=rwc",
+            dir.path().join("letters.db").display()
+        )));
+
+        let dbc = Database::connect(conf.database.url()).await.unwrap();
+        Migrator::up(&dbc, None).await.unwrap();
+
+        let state = Arc::new(AppState::new(dbc, &conf).unwrap());
+        let router = app::router(Arc::clone(&state));
+
+        Self {
+            state,
+            router,
+            _dir: dir,
+        }
+    }
+
+    pub async fn request(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<Value>,
+    ) -> TestResponse {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = match body {
+            Some(body) => builder
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => builder.body(Body::empty()),
+        };
+
+        self.send(request.unwrap()).await
+    }
+
+    pub async fn send(&self, request: Request<Body>) -> TestResponse {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        TestResponse {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    pub async fn get(&self, uri: &str, token: Option<&str>) -> TestResponse {
+        self.request(Method::GET, uri, token, None).await
+    }
+
+    pub async fn post(&self, uri: &str, token: Option<&str>, body: Value) -> TestResponse {
+        self.request(Method::POST, uri, token, Some(body)).await
+    }
+
+    pub async fn put(&self, uri: &str, token: Option<&str>, body: Value) -> TestResponse {
+        self.request(Method::PUT, uri, token, Some(body)).await
+    }
+
+    pub async fn delete(&self, uri: &str, token: Option<&str>) -> TestResponse {
+        self.request(Method::DELETE, uri, token, None).await
+    }
+
+    /// Insert a user named `username`, with `{username}@example.com` as email
+    /// and `PASSWORD` as password.
+    pub async fn create_user(&self, username: &str) -> i32 {
+        let model = entity::user::ActiveModel {
+            username: Set(username.to_string()),
+            email: Set(email(username)),
+            password_hash: Set(password_hash(PASSWORD)),
+            ..Default::default()
+        };
+        model.insert(self.state.dbc.as_ref()).await.unwrap().id
+    }
+
+    /// A token of the user, obtained the way a client does.
+    pub async fn token(&self, username: &str, password: &str) -> String {
+        let resp = self
+            .post(
+                "/api/v1/authorize",
+                None,
+                json!({ "email": email(username), "password": password }),
+            )
+            .await;
+        assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+        resp.json()["access_token"].as_str().unwrap().to_string()
+    }
+
+    /// Create a user and log it in, returns its id and token.
+    pub async fn login(&self, username: &str) -> (i32, String) {
+        let user_id = self.create_user(username).await;
+        (user_id, self.token(username, PASSWORD).await)
+    }
+
+    pub async fn create_category(&self, token: &str, name: &str) -> i32 {
+        let resp = self
+            .post(
+                "/api/v1/categories",
+                Some(token),
+                json!({ "name": name, "status": 1 }),
+            )
+            .await;
+        assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+
+        CategoryEntity::Entity::find()
+            .filter(CategoryEntity::Column::Name.eq(name))
+            .one(self.state.dbc.as_ref())
+            .await
+            .unwrap()
+            .unwrap()
+            .id
+    }
+
+    pub async fn create_tag(&self, token: &str, name: &str) -> i32 {
+        let resp = self
+            .post(
+                "/api/v1/tags",
+                Some(token),
+                json!({ "name": name, "type": 0, "status": 1 }),
+            )
+            .await;
+        assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+
+        TagEntity::Entity::find()
+            .filter(TagEntity::Column::Name.eq(name))
+            .one(self.state.dbc.as_ref())
+            .await
+            .unwrap()
+            .unwrap()
+            .id
+    }
+
+    pub async fn create_series(&self, token: &str, name: &str) -> i32 {
+        let resp = self
+            .post(
+                "/api/v1/series",
+                Some(token),
+                json!({ "name": name, "status": 1, "type": 0 }),
+            )
+            .await;
+        assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+
+        SeriesEntity::Entity::find()
+            .filter(SeriesEntity::Column::Name.eq(name))
+            .one(self.state.dbc.as_ref())
+            .await
+            .unwrap()
+            .unwrap()
+            .id
+    }
+
+    /// Create an article from `body`, which needs at least a `title` and a
+    /// `content`, and return its id.
+    pub async fn create_article(&self, token: &str, body: Value) -> i32 {
+        let resp = self.post("/api/v1/articles", Some(token), body).await;
+        assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+
+        ArticleEntity::Entity::find()
+            .order_by_desc(ArticleEntity::Column::Id)
+            .one(self.state.dbc.as_ref())
+            .await
+            .unwrap()
+            .unwrap()
+            .id
+    }
+
+    /// Create a published article in `category_id`.
+    pub async fn publish(&self, token: &str, category_id: i32, title: &str, content: &str) -> i32 {
+        self.create_article(
+            token,
+            json!({
+                "title": title,
+                "content": content,
+                "status": 1,
+                "category_id": category_id,
+            }),
+        )
+        .await
+    }
+}
+
+pub fn email(username: &str) -> String {
+    format!("{}@example.com", username)
+}
+
+/// Hash a password the way the users repository stores it.
+fn password_hash(password: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .unwrap();
+    general_purpose::STANDARD_NO_PAD.encode(hash.to_string())
+}
@@ -0,0 +1,22 @@
+//! End-to-end tests of the routes of `api::v1`, the feeds, sitemap and
+//! images of the site root and the html frontend, sent straight to the
+//! router over a fresh SQLite database.
+
+mod helpers;
+
+mod admin;
+mod articles;
+mod auth;
+mod cache;
+mod categories;
+mod feeds;
+mod frontend;
+mod health;
+mod media;
+mod search;
+mod seo;
+mod series;
+mod tags;
+mod telemetry;
+mod trash;
+mod users;
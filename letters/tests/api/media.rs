@@ -0,0 +1,214 @@
+use std::io::Cursor;
+
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+};
+use image::{ImageFormat, RgbImage};
+
+use crate::helpers::{TestApp, TestResponse};
+
+const BOUNDARY: &str = "letters-test-boundary";
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let image = RgbImage::from_fn(width, height, |x, y| {
+        image::Rgb([(x % 256) as u8, (y % 256) as u8, 128])
+    });
+    let mut data = Cursor::new(vec![]);
+    image.write_to(&mut data, ImageFormat::Png).unwrap();
+    data.into_inner()
+}
+
+async fn upload(
+    app: &TestApp,
+    token: &str,
+    file_name: &str,
+    mime: &str,
+    data: &[u8],
+) -> TestResponse {
+    let mut body = format!(
+        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\nContent-Type: {mime}\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/api/v1/media")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", BOUNDARY),
+        )
+        .body(Body::from(body))
+        .unwrap();
+    app.send(request).await
+}
+
+#[tokio::test]
+async fn upload_an_image() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+
+    let resp = upload(&app, &token, "photo.png", "image/png", &png(400, 300)).await;
+
+    assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+    let body = resp.json();
+    assert_eq!(body["file_name"], "photo.png");
+    assert_eq!(body["mime"], "image/png");
+    assert_eq!(body["width"], 400);
+    assert_eq!(body["height"], 300);
+    assert!(body["blurhash"].is_string());
+    assert!(body["variants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|variant| variant["width"] == 320));
+}
+
+#[tokio::test]
+async fn upload_rejects_a_type_not_allowed() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+
+    let resp = upload(&app, &token, "notes.txt", "text/plain", b"Some notes").await;
+
+    assert_eq!(resp.status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[tokio::test]
+async fn list_and_get_media_of_the_current_user() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let (_, other) = app.login("bob").await;
+    let media_id = upload(&app, &token, "photo.png", "image/png", &png(40, 30))
+        .await
+        .json()["id"]
+        .as_i64()
+        .unwrap();
+
+    let resp = app.get("/api/v1/media", Some(&token)).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.json()[0]["id"], media_id);
+
+    let resp = app.get("/api/v1/media", Some(&other)).await;
+    assert!(resp.json().as_array().unwrap().is_empty());
+
+    let resp = app
+        .get(&format!("/api/v1/media/{}", media_id), Some(&token))
+        .await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.json()["file_name"], "photo.png");
+
+    let resp = app
+        .get(&format!("/api/v1/media/{}", media_id), Some(&other))
+        .await;
+    assert_eq!(resp.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn signed_url_resizes_the_image() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let media_id = upload(&app, &token, "photo.png", "image/png", &png(400, 300))
+        .await
+        .json()["id"]
+        .as_i64()
+        .unwrap();
+
+    let resp = app
+        .get(
+            &format!("/api/v1/media/{}/signed-url?width=100&format=png", media_id),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+    let url = resp.json()["url"].as_str().unwrap().to_string();
+    let path = url.strip_prefix(&app.state.conf.site.base_url).unwrap();
+
+    let resp = app.get(path, None).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.headers[header::CONTENT_TYPE], "image/png");
+    let image = image::load_from_memory(&resp.body).unwrap();
+    assert_eq!(image.width(), 100);
+
+    let resp = app.get(&path.replace("w=100", "w=200"), None).await;
+    assert_eq!(resp.status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn resized_images_need_a_signature() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let media_id = upload(&app, &token, "photo.png", "image/png", &png(400, 300))
+        .await
+        .json()["id"]
+        .as_i64()
+        .unwrap();
+
+    let resp = app
+        .get(&format!("/images/{}?w=100&f=png", media_id), None)
+        .await;
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+
+    let resp = app
+        .get(&format!("/images/{}?w=100&f=png&s=forged", media_id), None)
+        .await;
+    assert_eq!(resp.status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn resized_images_of_a_deleted_media_are_gone() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let media_id = upload(&app, &token, "photo.png", "image/png", &png(400, 300))
+        .await
+        .json()["id"]
+        .as_i64()
+        .unwrap();
+    let url = app
+        .get(
+            &format!(
+                "/api/v1/media/{}/signed-url?width=100&format=webp",
+                media_id
+            ),
+            Some(&token),
+        )
+        .await
+        .json()["url"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let path = url.strip_prefix(&app.state.conf.site.base_url).unwrap();
+    let resp = app.get(path, None).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.headers[header::CONTENT_TYPE], "image/webp");
+
+    app.delete(&format!("/api/v1/media/{}", media_id), Some(&token))
+        .await;
+
+    let resp = app.get(path, None).await;
+    assert_eq!(resp.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn delete_media() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let media_id = upload(&app, &token, "photo.png", "image/png", &png(40, 30))
+        .await
+        .json()["id"]
+        .as_i64()
+        .unwrap();
+
+    let resp = app
+        .delete(&format!("/api/v1/media/{}", media_id), Some(&token))
+        .await;
+    assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+
+    let resp = app
+        .get(&format!("/api/v1/media/{}", media_id), Some(&token))
+        .await;
+    assert_eq!(resp.status, StatusCode::NOT_FOUND);
+}
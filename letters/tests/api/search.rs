@@ -0,0 +1,54 @@
+use axum::http::StatusCode;
+
+use crate::helpers::TestApp;
+
+#[tokio::test]
+async fn search_published_articles() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let article_id = app
+        .publish(&token, category_id, "Pinning", "Self referential futures")
+        .await;
+    app.publish(&token, category_id, "Traits", "Dynamic dispatch")
+        .await;
+
+    let resp = app.get("/api/v1/search?q=futures", None).await;
+
+    assert_eq!(resp.status, StatusCode::OK);
+    let body = resp.json();
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["hits"][0]["id"], article_id);
+}
+
+#[tokio::test]
+async fn search_skips_deleted_articles() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let article_id = app
+        .publish(&token, category_id, "Pinning", "Self referential futures")
+        .await;
+    app.delete(&format!("/api/v1/articles/{}", article_id), Some(&token))
+        .await;
+
+    let body = app.get("/api/v1/search?q=futures", None).await.json();
+
+    assert_eq!(body["total"], 0);
+}
+
+#[tokio::test]
+async fn search_rejects_pages_beyond_the_results_limit() {
+    let app = TestApp::spawn().await;
+
+    let resp = app.get("/api/v1/search?q=a&page=1000000000", None).await;
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+
+    let resp = app
+        .get(&format!("/api/v1/search?q=a&page={}", u64::MAX), None)
+        .await;
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+
+    let resp = app.get("/api/v1/search?q=a&page=100", None).await;
+    assert_eq!(resp.status, StatusCode::OK);
+}
@@ -0,0 +1,65 @@
+use axum::http::{header, StatusCode};
+
+use crate::helpers::TestApp;
+
+#[tokio::test]
+async fn sitemap_lists_the_published_articles() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let article_id = app
+        .publish(&token, category_id, "Pinning", "Self referential futures")
+        .await;
+    let url = app
+        .get(&format!("/api/v1/articles/{}/seo", article_id), None)
+        .await
+        .json()["canonical_url"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let resp = app.get("/sitemap.xml", None).await;
+
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(
+        resp.headers[header::CONTENT_TYPE],
+        "application/xml; charset=utf-8"
+    );
+    let body = String::from_utf8_lossy(&resp.body);
+    assert!(body.contains("<urlset"));
+    assert!(body.contains(&format!("<loc>{}</loc>", url)), "{}", body);
+}
+
+#[tokio::test]
+async fn sitemap_pages_past_the_last_are_missing() {
+    let app = TestApp::spawn().await;
+
+    let resp = app.get("/sitemaps/1.xml", None).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert!(String::from_utf8_lossy(&resp.body).contains("<urlset"));
+
+    for uri in ["/sitemaps/2.xml", "/sitemaps/0.xml", "/sitemaps/index.xml"] {
+        let resp = app.get(uri, None).await;
+
+        assert_eq!(resp.status, StatusCode::NOT_FOUND, "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn robots_points_at_the_sitemap() {
+    let app = TestApp::spawn().await;
+
+    let resp = app.get("/robots.txt", None).await;
+
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(
+        resp.headers[header::CONTENT_TYPE],
+        "text/plain; charset=utf-8"
+    );
+    let body = String::from_utf8_lossy(&resp.body);
+    assert!(body.starts_with("User-agent: *\n"));
+    assert!(body.contains(&format!(
+        "Sitemap: {}/sitemap.xml",
+        app.state.conf.site.base_url
+    )));
+}
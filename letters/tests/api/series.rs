@@ -0,0 +1,192 @@
+use axum::http::{header, StatusCode};
+use serde_json::{json, Value};
+
+use crate::helpers::TestApp;
+
+/// A series of alice with three published chapters, returns the token of
+/// alice, the id of the series and the ids of the chapters.
+async fn series_with_chapters(app: &TestApp) -> (String, i32, Vec<i32>) {
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let series_id = app.create_series(&token, "Async Rust").await;
+
+    let mut chapters = vec![];
+    for title in ["Futures", "Executors", "Streams"] {
+        let article_id = app.publish(&token, category_id, title, "Text").await;
+        let resp = app
+            .post(
+                &format!("/api/v1/series/{}/articles", series_id),
+                Some(&token),
+                json!({ "article_id": article_id }),
+            )
+            .await;
+        assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+        chapters.push(article_id);
+    }
+
+    (token, series_id, chapters)
+}
+
+fn ids(body: &Value) -> Vec<i64> {
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|article| article["id"].as_i64().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn create_and_get_series() {
+    let app = TestApp::spawn().await;
+    let (user_id, token) = app.login("alice").await;
+
+    let series_id = app.create_series(&token, "Async Rust").await;
+
+    let resp = app
+        .get(&format!("/api/v1/series/{}", series_id), None)
+        .await;
+    assert_eq!(resp.status, StatusCode::OK);
+    let body = resp.json();
+    assert_eq!(body["name"], "Async Rust");
+    assert_eq!(body["user_id"], user_id);
+    assert_eq!(body["nums"], 0);
+
+    let body = app.get("/api/v1/series", None).await.json();
+    assert_eq!(body.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn update_series() {
+    let app = TestApp::spawn().await;
+    let (token, series_id, _) = series_with_chapters(&app).await;
+
+    let resp = app
+        .put(
+            &format!("/api/v1/series/{}", series_id),
+            Some(&token),
+            json!({ "name": "Asynchronous Rust" }),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::OK);
+
+    let body = app
+        .get(&format!("/api/v1/series/{}", series_id), None)
+        .await
+        .json();
+    assert_eq!(body["name"], "Asynchronous Rust");
+    assert_eq!(body["nums"], 3);
+}
+
+#[tokio::test]
+async fn chapters_are_listed_in_order() {
+    let app = TestApp::spawn().await;
+    let (_, series_id, chapters) = series_with_chapters(&app).await;
+
+    let resp = app
+        .get(&format!("/api/v1/series/{}/articles", series_id), None)
+        .await;
+
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(
+        ids(&resp.json()),
+        chapters.iter().map(|&id| id as i64).collect::<Vec<_>>()
+    );
+
+    let body = app
+        .get(&format!("/api/v1/articles/{}", chapters[1]), None)
+        .await
+        .json();
+    assert_eq!(body["series"][0]["position"], 2);
+    assert_eq!(body["series"][0]["prev"]["id"], chapters[0]);
+    assert_eq!(body["series"][0]["next"]["id"], chapters[2]);
+}
+
+#[tokio::test]
+async fn reorder_series_articles() {
+    let app = TestApp::spawn().await;
+    let (token, series_id, chapters) = series_with_chapters(&app).await;
+    let reordered = vec![chapters[2], chapters[0], chapters[1]];
+
+    let resp = app
+        .put(
+            &format!("/api/v1/series/{}/articles", series_id),
+            Some(&token),
+            json!({ "article_ids": reordered }),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+
+    let body = app
+        .get(&format!("/api/v1/series/{}/articles", series_id), None)
+        .await
+        .json();
+    assert_eq!(
+        ids(&body),
+        reordered.iter().map(|&id| id as i64).collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn remove_series_article() {
+    let app = TestApp::spawn().await;
+    let (token, series_id, chapters) = series_with_chapters(&app).await;
+
+    let resp = app
+        .delete(
+            &format!("/api/v1/series/{}/articles/{}", series_id, chapters[0]),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+
+    let body = app
+        .get(&format!("/api/v1/series/{}/articles", series_id), None)
+        .await
+        .json();
+    assert_eq!(ids(&body), [chapters[1] as i64, chapters[2] as i64]);
+    let body = app
+        .get(&format!("/api/v1/series/{}", series_id), None)
+        .await
+        .json();
+    assert_eq!(body["nums"], 2);
+}
+
+#[tokio::test]
+async fn export_series_in_chapter_order() {
+    let app = TestApp::spawn().await;
+    let (token, series_id, _) = series_with_chapters(&app).await;
+
+    let resp = app
+        .get(
+            &format!("/api/v1/series/{}/export", series_id),
+            Some(&token),
+        )
+        .await;
+
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.headers[header::CONTENT_TYPE], "application/zip");
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(resp.body.to_vec())).unwrap();
+    let names: Vec<_> = (0..zip.len())
+        .map(|i| zip.by_index(i).unwrap().name().to_string())
+        .collect();
+    assert_eq!(
+        names,
+        ["001-Futures.md", "002-Executors.md", "003-Streams.md"]
+    );
+}
+
+#[tokio::test]
+async fn delete_series() {
+    let app = TestApp::spawn().await;
+    let (token, series_id, _) = series_with_chapters(&app).await;
+
+    let resp = app
+        .delete(&format!("/api/v1/series/{}", series_id), Some(&token))
+        .await;
+    assert_eq!(resp.status, StatusCode::OK);
+
+    let resp = app
+        .get(&format!("/api/v1/series/{}", series_id), None)
+        .await;
+    assert_eq!(resp.status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,120 @@
+use axum::http::StatusCode;
+use serde_json::json;
+
+use crate::helpers::TestApp;
+
+#[tokio::test]
+async fn create_and_list_tags() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+
+    app.create_tag(&token, "async").await;
+
+    let resp = app.get("/api/v1/tags", None).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    let body = resp.json();
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["name"], "async");
+}
+
+#[tokio::test]
+async fn create_tag_rejects_a_duplicate_name() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    app.create_tag(&token, "async").await;
+
+    let resp = app
+        .post(
+            "/api/v1/tags",
+            Some(&token),
+            json!({ "name": "async", "type": 0, "status": 1 }),
+        )
+        .await;
+
+    assert_eq!(resp.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn create_tag_rejects_an_out_of_range_status() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+
+    for status in [-1, 256] {
+        let resp = app
+            .post(
+                "/api/v1/tags",
+                Some(&token),
+                json!({ "name": "async", "type": 0, "status": status }),
+            )
+            .await;
+
+        assert_eq!(resp.status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
+
+#[tokio::test]
+async fn update_tag() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let tag_id = app.create_tag(&token, "async").await;
+
+    let resp = app
+        .put(
+            &format!("/api/v1/tags/{}", tag_id),
+            Some(&token),
+            json!({ "name": "tokio" }),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::OK);
+
+    let body = app.get("/api/v1/tags", None).await.json();
+    assert_eq!(body[0]["name"], "tokio");
+}
+
+#[tokio::test]
+async fn get_tag_articles() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let tag_id = app.create_tag(&token, "async").await;
+    let article_id = app
+        .create_article(
+            &token,
+            json!({
+                "title": "Futures",
+                "content": "Poll",
+                "status": 1,
+                "category_id": category_id,
+                "tag_ids": [tag_id],
+            }),
+        )
+        .await;
+    app.publish(&token, category_id, "Lifetimes", "'a").await;
+
+    let resp = app
+        .get(&format!("/api/v1/tags/{}/articles", tag_id), None)
+        .await;
+
+    assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+    let body = resp.json();
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["id"], article_id);
+}
+
+#[tokio::test]
+async fn delete_tag_moves_it_to_the_trash() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let tag_id = app.create_tag(&token, "async").await;
+
+    let resp = app
+        .delete(&format!("/api/v1/tags/{}", tag_id), Some(&token))
+        .await;
+    assert_eq!(resp.status, StatusCode::OK);
+
+    let body = app.get("/api/v1/tags", None).await.json();
+    assert!(body.as_array().unwrap().is_empty());
+
+    let body = app.get("/api/v1/trash", Some(&token)).await.json();
+    assert_eq!(body["tags"][0]["id"], tag_id);
+}
@@ -0,0 +1,48 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+
+use crate::helpers::TestApp;
+
+#[tokio::test]
+async fn every_response_gets_a_request_id() {
+    let app = TestApp::spawn().await;
+
+    let first = app.get("/api/v1/tags", None).await;
+    let second = app.get("/api/v1/tags", None).await;
+    let first = first.headers["x-request-id"].to_str().unwrap();
+    let second = second.headers["x-request-id"].to_str().unwrap();
+    assert_eq!(first.len(), 36);
+    assert_ne!(first, second);
+}
+
+#[tokio::test]
+async fn request_id_of_the_client_is_kept_and_reported_in_errors() {
+    let app = TestApp::spawn().await;
+
+    let request = Request::get("/api/v1/articles/404")
+        .header("x-request-id", "client-id-1")
+        .header(
+            "traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        )
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.send(request).await;
+    assert_eq!(resp.status, StatusCode::NOT_FOUND);
+    assert_eq!(resp.headers["x-request-id"], "client-id-1");
+    assert_eq!(resp.json()["request_id"], "client-id-1");
+}
+
+#[tokio::test]
+async fn overlong_request_id_is_replaced() {
+    let app = TestApp::spawn().await;
+
+    let request = Request::get("/healthz")
+        .header("x-request-id", "x".repeat(200))
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.send(request).await;
+    assert_eq!(resp.headers["x-request-id"].len(), 36);
+}
@@ -0,0 +1,99 @@
+use axum::http::StatusCode;
+
+use crate::helpers::TestApp;
+
+#[tokio::test]
+async fn trash_needs_a_token() {
+    let app = TestApp::spawn().await;
+
+    let resp = app.get("/api/v1/trash", None).await;
+
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn restore_an_article() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let article_id = app.publish(&token, category_id, "Back", "Text").await;
+    app.delete(&format!("/api/v1/articles/{}", article_id), Some(&token))
+        .await;
+
+    let resp = app
+        .post(
+            &format!("/api/v1/trash/articles/{}/restore", article_id),
+            Some(&token),
+            serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+
+    let resp = app
+        .get(&format!("/api/v1/articles/{}", article_id), None)
+        .await;
+    assert_eq!(resp.status, StatusCode::OK);
+    let body = app.get("/api/v1/trash", Some(&token)).await.json();
+    assert!(body["articles"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn restore_a_category_and_a_tag() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+    let category_id = app.create_category(&token, "rust").await;
+    let tag_id = app.create_tag(&token, "async").await;
+    app.delete(&format!("/api/v1/categories/{}", category_id), Some(&token))
+        .await;
+    app.delete(&format!("/api/v1/tags/{}", tag_id), Some(&token))
+        .await;
+
+    for uri in [
+        format!("/api/v1/trash/categories/{}/restore", category_id),
+        format!("/api/v1/trash/tags/{}/restore", tag_id),
+    ] {
+        let resp = app.post(&uri, Some(&token), serde_json::json!({})).await;
+        assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+    }
+
+    let body = app.get("/api/v1/categories", None).await.json();
+    assert_eq!(body[0]["name"], "rust");
+    let body = app.get("/api/v1/tags", None).await.json();
+    assert_eq!(body[0]["name"], "async");
+}
+
+#[tokio::test]
+async fn restore_a_user() {
+    let app = TestApp::spawn().await;
+    let (user_id, token) = app.login("alice").await;
+    let (_, admin) = app.login("admin").await;
+    app.delete("/api/v1/users/profile", Some(&token)).await;
+
+    let resp = app
+        .post(
+            &format!("/api/v1/trash/users/{}/restore", user_id),
+            Some(&admin),
+            serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::OK, "{:?}", resp.body);
+
+    let resp = app.get("/api/v1/users/profile", Some(&token)).await;
+    assert_eq!(resp.status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn restore_what_is_not_in_the_trash() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+
+    let resp = app
+        .post(
+            "/api/v1/trash/articles/42/restore",
+            Some(&token),
+            serde_json::json!({}),
+        )
+        .await;
+
+    assert_eq!(resp.status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,78 @@
+use axum::http::StatusCode;
+use serde_json::json;
+
+use crate::helpers::{email, TestApp, PASSWORD};
+
+#[tokio::test]
+async fn get_profile_of_the_current_user() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+
+    let resp = app.get("/api/v1/users/profile", Some(&token)).await;
+
+    assert_eq!(resp.status, StatusCode::OK);
+    let body = resp.json();
+    assert_eq!(body["username"], "alice");
+    assert_eq!(body["email"], email("alice"));
+}
+
+#[tokio::test]
+async fn update_profile() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+
+    let resp = app
+        .put(
+            "/api/v1/users/profile",
+            Some(&token),
+            json!({ "username": "alice2", "bio": "Writes letters" }),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::OK);
+
+    let body = app.get("/api/v1/users/profile", Some(&token)).await.json();
+    assert_eq!(body["username"], "alice2");
+    assert_eq!(body["bio"], "Writes letters");
+}
+
+#[tokio::test]
+async fn reset_password() {
+    let app = TestApp::spawn().await;
+    let (_, token) = app.login("alice").await;
+
+    let resp = app
+        .put(
+            "/api/v1/users/password",
+            Some(&token),
+            json!({ "old_password": "wrong", "new_password": "new password" }),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+
+    let resp = app
+        .put(
+            "/api/v1/users/password",
+            Some(&token),
+            json!({ "old_password": PASSWORD, "new_password": "new password" }),
+        )
+        .await;
+    assert_eq!(resp.status, StatusCode::OK);
+
+    app.token("alice", "new password").await;
+}
+
+#[tokio::test]
+async fn delete_profile_moves_the_user_to_the_trash() {
+    let app = TestApp::spawn().await;
+    let (user_id, token) = app.login("alice").await;
+
+    let resp = app.delete("/api/v1/users/profile", Some(&token)).await;
+    assert_eq!(resp.status, StatusCode::OK);
+
+    let resp = app.get("/api/v1/users/profile", Some(&token)).await;
+    assert_eq!(resp.status, StatusCode::NOT_FOUND);
+
+    let (_, admin) = app.login("admin").await;
+    let body = app.get("/api/v1/trash", Some(&admin)).await.json();
+    assert_eq!(body["users"][0]["id"], user_id);
+}
@@ -0,0 +1,120 @@
+use std::fs;
+
+use letters::conf::Conf;
+use secrecy::ExposeSecret;
+
+const DEFAULT: &str = r#"
+[server]
+port = 5000
+mode = "release"
+
+[auth]
+secret = "a secret long enough to sign"
+timeout_seconds = 3600
+
+[database]
+url = "sqlite://letters.db"
+"#;
+
+/// Write `files` into a temporary directory and load `default.toml` from it
+/// with `profile`.
+fn load(files: &[(&str, &str)], profile: &str) -> Conf {
+    load_with_prefix(files, profile, "LETTERS_TEST")
+}
+
+fn load_with_prefix(files: &[(&str, &str)], profile: &str, env_prefix: &str) -> Conf {
+    let dir = tempfile::tempdir().unwrap();
+    for (name, content) in files {
+        fs::write(dir.path().join(name), content).unwrap();
+    }
+    let location = dir.path().join("default.toml");
+
+    Conf::new(location.to_str().unwrap(), profile, env_prefix).unwrap()
+}
+
+#[test]
+fn profile_then_local_override_the_defaults() {
+    let conf = load(
+        &[
+            ("default.toml", DEFAULT),
+            (
+                "production.toml",
+                "[server]\nport = 80\n[auth]\ntimeout_seconds = 60\n",
+            ),
+            ("development.toml", "[server]\nport = 8000\n"),
+            ("local.toml", "[server]\nport = 8080\n"),
+        ],
+        "production",
+    );
+
+    assert_eq!(conf.server.port, 8080);
+    assert_eq!(conf.auth.timeout_seconds, 60);
+    assert_eq!(conf.server.mode.as_deref(), Some("release"));
+    assert_eq!(conf.info.profile.as_deref(), Some("production"));
+    conf.check().unwrap();
+}
+
+#[test]
+fn environment_overrides_the_files() {
+    // A prefix of its own, the other tests run alongside and must not see
+    // these variables.
+    std::env::set_var("LETTERS_ENV_TEST_AUTH__TIMEOUT_SECONDS", "7200");
+    std::env::set_var("LETTERS_ENV_TEST_MEDIA__S3__SECRET_ACCESS_KEY", "s3 secret");
+    std::env::set_var("LETTERS_ENV_TEST_SERVER__PORT", "9000");
+
+    let conf = load_with_prefix(
+        &[
+            ("default.toml", DEFAULT),
+            ("local.toml", "[server]\nport = 8080\n"),
+        ],
+        "development",
+        "LETTERS_ENV_TEST",
+    );
+
+    assert_eq!(conf.auth.timeout_seconds, 7200);
+    assert_eq!(conf.media.s3.secret_access_key.expose_secret(), "s3 secret");
+    assert_eq!(conf.server.port, 9000);
+    assert_eq!(conf.database.url(), "sqlite://letters.db");
+}
+
+#[test]
+fn missing_profile_and_local_files_are_skipped() {
+    let conf = load(&[("default.toml", DEFAULT)], "staging");
+
+    assert_eq!(conf.server.port, 5000);
+    assert_eq!(conf.database.url(), "sqlite://letters.db");
+    conf.check().unwrap();
+}
+
+#[test]
+fn check_lists_every_invalid_key() {
+    let conf = load(
+        &[(
+            "default.toml",
+            "[server]\nport = 5000\nmode = \"prod\"\n[auth]\nsecret = \"short\"\ntimeout_seconds = 60\n\
+             [media]\nbackend = \"s3\"\n[media.s3]\nbucket = \"\"\n[log]\nlevel = \"letters=loud\"\n",
+        )],
+        "development",
+    );
+
+    let error = conf.check().unwrap_err().to_string();
+    for key in [
+        "server.mode",
+        "database.url",
+        "auth.secret",
+        "media.s3",
+        "log.level",
+    ] {
+        assert!(error.contains(key), "{} is not in {}", key, error);
+    }
+}
+
+#[test]
+fn redacted_masks_the_secrets() {
+    let conf = load(&[("default.toml", DEFAULT)], "development").redacted();
+
+    assert_eq!(conf.auth.secret.expose_secret(), "********");
+    assert_eq!(conf.database.url(), "********");
+    // An unset secret stays visibly unset.
+    assert_eq!(conf.media.s3.secret_access_key.expose_secret(), "");
+}
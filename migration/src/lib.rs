@@ -1,12 +1,24 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20220101_000002_series_article_position;
+mod m20220101_000003_create_media;
+mod m20220101_000004_media_image;
+mod m20220101_000005_create_import;
+mod m20220101_000006_portable_types;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20220101_000002_series_article_position::Migration),
+            Box::new(m20220101_000003_create_media::Migration),
+            Box::new(m20220101_000004_media_image::Migration),
+            Box::new(m20220101_000005_create_import::Migration),
+            Box::new(m20220101_000006_portable_types::Migration),
+        ]
     }
 }
@@ -0,0 +1,117 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, FromQueryResult};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(FromQueryResult)]
+struct Row {
+    id: i32,
+    series_id: i32,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SeriesArticle::Table)
+                    .add_column(
+                        ColumnDef::new(SeriesArticle::Position)
+                            .unsigned()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Number the existing chapters by insertion order and fix up the
+        // chapter count of every series.
+        let db = manager.get_connection();
+        let builder = db.get_database_backend();
+        let rows = Row::find_by_statement(
+            builder.build(
+                Query::select()
+                    .columns([SeriesArticle::Id, SeriesArticle::SeriesId])
+                    .from(SeriesArticle::Table)
+                    .order_by(SeriesArticle::SeriesId, Order::Asc)
+                    .order_by(SeriesArticle::Id, Order::Asc),
+            ),
+        )
+        .all(db)
+        .await?;
+
+        let mut current = None;
+        let mut position = 0;
+        for row in rows {
+            if current != Some(row.series_id) {
+                current = Some(row.series_id);
+                position = 0;
+            }
+            position += 1;
+
+            db.execute(
+                builder.build(
+                    Query::update()
+                        .table(SeriesArticle::Table)
+                        .value(SeriesArticle::Position, position)
+                        .and_where(Expr::col(SeriesArticle::Id).eq(row.id)),
+                ),
+            )
+            .await?;
+        }
+
+        db.execute(
+            builder.build(
+                Query::update().table(Series::Table).value(
+                    Series::Nums,
+                    SimpleExpr::SubQuery(
+                        None,
+                        Box::new(
+                            Query::select()
+                                .expr(Expr::col(SeriesArticle::Id).count())
+                                .from(SeriesArticle::Table)
+                                .and_where(
+                                    Expr::col((SeriesArticle::Table, SeriesArticle::SeriesId))
+                                        .equals((Series::Table, Series::Id)),
+                                )
+                                .to_owned()
+                                .into_sub_query_statement(),
+                        ),
+                    ),
+                ),
+            ),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SeriesArticle::Table)
+                    .drop_column(SeriesArticle::Position)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Series {
+    Table,
+    Id,
+    Nums,
+}
+
+#[derive(DeriveIden)]
+enum SeriesArticle {
+    Table,
+    Id,
+    SeriesId,
+    Position,
+}
@@ -0,0 +1,88 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Media::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Media::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Media::StorageKey).string().not_null())
+                    .col(ColumnDef::new(Media::FileName).string().not_null())
+                    .col(ColumnDef::new(Media::Mime).string().not_null())
+                    .col(ColumnDef::new(Media::Size).big_unsigned().not_null())
+                    .col(ColumnDef::new(Media::Hash).string_len(64).not_null())
+                    .col(
+                        ColumnDef::new(Media::CreatedAt)
+                            .timestamp()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Media::UpdatedAt)
+                            .timestamp()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Media::UserId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("media-user-id")
+                            .from(Media::Table, Media::UserId)
+                            .to(User::Table, User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // An owner uploading the same file twice gets the first row back.
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-media-user-hash")
+                    .table(Media::Table)
+                    .col(Media::UserId)
+                    .col(Media::Hash)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Media::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Media {
+    Table,
+    Id,
+    StorageKey,
+    FileName,
+    Mime,
+    Size,
+    Hash,
+    CreatedAt,
+    UpdatedAt,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
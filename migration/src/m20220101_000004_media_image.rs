@@ -0,0 +1,93 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One column per statement, sqlite can not add several at once.
+        for mut column in [
+            ColumnDef::new(Media::Width).unsigned().to_owned(),
+            ColumnDef::new(Media::Height).unsigned().to_owned(),
+            ColumnDef::new(Media::Blurhash).string().to_owned(),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Media::Table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(MediaVariant::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MediaVariant::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(MediaVariant::Width).unsigned().not_null())
+                    .col(ColumnDef::new(MediaVariant::Height).unsigned().not_null())
+                    .col(ColumnDef::new(MediaVariant::Mime).string().not_null())
+                    .col(ColumnDef::new(MediaVariant::StorageKey).string().not_null())
+                    .col(ColumnDef::new(MediaVariant::Size).big_unsigned().not_null())
+                    .col(ColumnDef::new(MediaVariant::MediaId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("mv-media-id")
+                            .from(MediaVariant::Table, MediaVariant::MediaId)
+                            .to(Media::Table, Media::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MediaVariant::Table).to_owned())
+            .await?;
+
+        for column in [Media::Width, Media::Height, Media::Blurhash] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Media::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Media {
+    Table,
+    Id,
+    Width,
+    Height,
+    Blurhash,
+}
+
+#[derive(DeriveIden)]
+enum MediaVariant {
+    Table,
+    Id,
+    Width,
+    Height,
+    Mime,
+    StorageKey,
+    Size,
+    MediaId,
+}
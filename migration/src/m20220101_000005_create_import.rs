@@ -0,0 +1,119 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Redirect::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Redirect::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Redirect::Path)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Redirect::CreatedAt)
+                            .timestamp()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Redirect::ArticleId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("redirect-article-id")
+                            .from(Redirect::Table, Redirect::ArticleId)
+                            .to(Article::Table, Article::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImportRecord::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ImportRecord::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ImportRecord::Source).string().not_null())
+                    .col(ColumnDef::new(ImportRecord::Kind).string().not_null())
+                    .col(ColumnDef::new(ImportRecord::ExternalId).string().not_null())
+                    .col(ColumnDef::new(ImportRecord::EntityId).integer().not_null())
+                    .col(
+                        ColumnDef::new(ImportRecord::CreatedAt)
+                            .timestamp()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-import-record-source-kind-external-id")
+                    .table(ImportRecord::Table)
+                    .col(ImportRecord::Source)
+                    .col(ImportRecord::Kind)
+                    .col(ImportRecord::ExternalId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ImportRecord::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Redirect::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Redirect {
+    Table,
+    Id,
+    Path,
+    CreatedAt,
+    ArticleId,
+}
+
+#[derive(DeriveIden)]
+enum ImportRecord {
+    Table,
+    Id,
+    Source,
+    Kind,
+    ExternalId,
+    EntityId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Article {
+    Table,
+    Id,
+}
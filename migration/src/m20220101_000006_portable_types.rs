@@ -0,0 +1,142 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DbBackend;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Clone, Copy)]
+enum Int {
+    /// Flags and kinds, was `tiny_unsigned`
+    Small,
+    /// Counts, positions and image sizes, was `unsigned`
+    Regular,
+    /// File sizes, was `big_unsigned`
+    Big,
+}
+
+/// Columns the previous migrations made unsigned: PostgreSQL has no unsigned
+/// integers and sea-orm only reads unsigned ones from MySQL. Each is given
+/// with whether it is `NOT NULL` and whether it defaults to 0.
+const INTEGERS: [(&str, &str, Int, bool, bool); 16] = [
+    ("category", "status", Int::Small, true, true),
+    ("tag", "type", Int::Small, true, true),
+    ("tag", "status", Int::Small, true, true),
+    ("article", "source", Int::Small, true, true),
+    ("article", "topping", Int::Small, true, true),
+    ("article", "status", Int::Small, true, true),
+    ("series", "status", Int::Small, true, true),
+    ("series", "nums", Int::Regular, true, true),
+    ("series", "type", Int::Small, true, true),
+    ("series_article", "position", Int::Regular, true, true),
+    ("media", "size", Int::Big, true, false),
+    ("media", "width", Int::Regular, false, false),
+    ("media", "height", Int::Regular, false, false),
+    ("media_variant", "width", Int::Regular, true, false),
+    ("media_variant", "height", Int::Regular, true, false),
+    ("media_variant", "size", Int::Big, true, false),
+];
+
+/// Every timestamp column, PostgreSQL needs them with a time zone to read
+/// them as UTC. `true` when the column is nullable.
+const TIMESTAMPS: [(&str, &str, bool); 27] = [
+    ("user", "created_at", false),
+    ("user", "updated_at", false),
+    ("user", "deleted_at", true),
+    ("category", "created_at", false),
+    ("category", "updated_at", false),
+    ("category", "deleted_at", true),
+    ("tag", "created_at", false),
+    ("tag", "updated_at", false),
+    ("tag", "deleted_at", true),
+    ("article", "created_at", false),
+    ("article", "updated_at", false),
+    ("article", "deleted_at", true),
+    ("article_tag", "created_at", false),
+    ("article_tag", "updated_at", false),
+    ("article_tag", "deleted_at", true),
+    ("series", "published_at", false),
+    ("series", "created_at", false),
+    ("series", "updated_at", false),
+    ("series_article", "created_at", false),
+    ("series_article", "updated_at", false),
+    ("comment", "created_at", false),
+    ("comment", "updated_at", false),
+    ("comment", "deleted_at", true),
+    ("media", "created_at", false),
+    ("media", "updated_at", false),
+    ("redirect", "created_at", false),
+    ("import_record", "created_at", false),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        convert(manager, true).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        convert(manager, false).await
+    }
+}
+
+/// Switch the columns to the portable types, or back to the old ones.
+///
+/// SQLite only knows of type affinities, its columns are left alone. The
+/// tables of PostgreSQL are empty, it could not run the application before.
+async fn convert(manager: &SchemaManager<'_>, portable: bool) -> Result<(), DbErr> {
+    let backend = manager.get_database_backend();
+    if backend == DbBackend::Sqlite {
+        return Ok(());
+    }
+
+    for (table, name, int, not_null, default) in INTEGERS {
+        let mut column = ColumnDef::new(Alias::new(name));
+        match (int, portable) {
+            (Int::Small, true) => column.small_integer(),
+            (Int::Regular, true) => column.integer(),
+            (Int::Big, true) => column.big_integer(),
+            (Int::Small, false) => column.tiny_unsigned(),
+            (Int::Regular, false) => column.unsigned(),
+            (Int::Big, false) => column.big_unsigned(),
+        };
+        if not_null {
+            column.not_null();
+        }
+        if default {
+            column.default(0);
+        }
+        modify(manager, table, column).await?;
+    }
+
+    if backend == DbBackend::Postgres {
+        for (table, name, nullable) in TIMESTAMPS {
+            let mut column = ColumnDef::new(Alias::new(name));
+            if portable {
+                column.timestamp_with_time_zone();
+            } else {
+                column.timestamp();
+            }
+            if !nullable {
+                column.not_null().default(Expr::current_timestamp());
+            }
+            modify(manager, table, column).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn modify(
+    manager: &SchemaManager<'_>,
+    table: &str,
+    mut column: ColumnDef,
+) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Alias::new(table))
+                .modify_column(&mut column)
+                .to_owned(),
+        )
+        .await
+}
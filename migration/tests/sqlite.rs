@@ -0,0 +1,24 @@
+//! Runs every migration against an in-memory SQLite database, so that CI
+//! needs no database server.
+
+use migration::{Migrator, MigratorTrait};
+use sea_orm_migration::sea_orm::Database;
+
+#[tokio::test]
+async fn migrations_run_up_and_down() {
+    let dbc = Database::connect("sqlite::memory:").await.unwrap();
+
+    Migrator::up(&dbc, None).await.unwrap();
+    assert_eq!(
+        Migrator::get_pending_migrations(&dbc).await.unwrap().len(),
+        0
+    );
+
+    Migrator::down(&dbc, None).await.unwrap();
+    assert_eq!(
+        Migrator::get_applied_migrations(&dbc).await.unwrap().len(),
+        0
+    );
+
+    Migrator::up(&dbc, None).await.unwrap();
+}